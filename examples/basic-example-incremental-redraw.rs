@@ -90,7 +90,7 @@ fn main() -> Result<(), core::convert::Infallible> {
         // the widget itself, but now with added smart redraw functionality
         ui.add(
             Label::new("Basic Example (incremental)")
-                .with_font(ascii::FONT_10X20)
+                .with_font(&ascii::FONT_10X20)
                 .smartstate(smartstates.nxt()),
         );
 
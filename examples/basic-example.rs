@@ -60,7 +60,7 @@ fn main() -> Result<(), core::convert::Infallible> {
 
         // === ACTUAL UI CODE STARTS HERE ===
 
-        ui.add(Label::new("Basic Example").with_font(ascii::FONT_10X20));
+        ui.add(Label::new("Basic Example").with_font(&ascii::FONT_10X20));
 
         ui.add(Label::new("Basic Counter (7LOC)"));
 
@@ -161,7 +161,7 @@ fn main() -> Result<(), core::convert::Infallible> {
             }
             ui.style_mut().icon_color = Rgb565::RED;
             ui.add(IconWidget::<size32px::actions::WarningTriangle>::new_from_type().smartstate(smartstates.next()));
-            ui.add(Label::new("Caution!").with_font(ascii::FONT_8X13_BOLD).smartstate(smartstates.next()));
+            ui.add(Label::new("Caution!").with_font(&ascii::FONT_8X13_BOLD).smartstate(smartstates.next()));
             ui.add(Label::new("This is heavy equipment.\nIf you are not sure what \nexactly you are doing,\nyou might hurt yourself badly.\n").smartstate(smartstates.next()));
 
             if ui.add(Button::new("I know what I am doing").smartstate(smartstates.next())).clicked() {
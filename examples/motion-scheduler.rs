@@ -1,16 +1,14 @@
 use embedded_graphics::draw_target::DrawTarget;
-use embedded_graphics::geometry::Dimensions;
 use embedded_graphics::geometry::Size;
 use embedded_graphics::image::Image;
-use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::{PixelColor, Rgb565, RgbColor};
 use embedded_graphics::prelude::Point;
 use embedded_graphics::primitives::{Circle, PrimitiveStyle, StyledDrawable};
-use embedded_graphics::text::Text;
 use embedded_graphics_simulator::sdl2::MouseButton;
 use embedded_graphics_simulator::{
     OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
 };
+use kolibri_embedded_gui::font::FontText;
 use kolibri_embedded_gui::icons::size18px;
 use kolibri_embedded_gui::prelude::*;
 use kolibri_embedded_gui::smartstate::{Container, Smartstate, SmartstateProvider};
@@ -74,14 +72,9 @@ impl Widget for StepWidget<'_> {
 
         let width = match self.step {
             Step::Wait(dur) => {
-                Text::new(
-                    &format!("{}s", dur.as_secs()),
-                    Point::zero(),
-                    MonoTextStyle::new(&ui.style().default_font, ui.style().text_color),
-                )
-                .bounding_box()
-                .size
-                .width
+                ui.style()
+                    .default_font
+                    .text_width(&format!("{}s", dur.as_secs()))
                     + ui.style().spacing.item_spacing.width
                     + ICON_SIZE
             }
@@ -240,17 +233,15 @@ impl Widget for StepWidget<'_> {
                 Step::Wait(dur) => {
                     // text
                     let font = ui.style().default_font;
-                    let text_style = MonoTextStyle::new(&font, col);
                     let pos = Point::new(
                         ICON_SIZE as i32 + ui.style().spacing.item_spacing.width as i32,
                         ICON_SIZE as i32 + ui.style().spacing.item_spacing.height as i32,
                     ) + iresponse.area.top_left;
                     let val = format!("{}s", dur.as_secs());
-                    let mut text = Text::new(&val, pos, text_style);
-                    text.position += Point::new(
-                        0,
-                        (ICON_SIZE / 2 + text.bounding_box().size.height / 4) as i32,
-                    );
+                    let mut text = FontText::new(&val, pos, font, col);
+                    // center next to the icon
+                    text.position +=
+                        Point::new(0, (ICON_SIZE as i32 - font.line_height() as i32) / 2);
                     ui.draw(&text)?;
                 }
             }
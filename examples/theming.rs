@@ -67,10 +67,10 @@ fn main() -> Result<(), core::convert::Infallible> {
 
         // === ACTUAL UI CODE STARTS HERE ===
 
-        ui.add(Label::new("Theming Example").with_font(ascii::FONT_10X20));
+        ui.add(Label::new("Theming Example").with_font(&ascii::FONT_10X20));
 
         ui.add_horizontal(Label::new("Label"));
-        ui.add(Label::new("Small Label").with_font(ascii::FONT_6X13));
+        ui.add(Label::new("Small Label").with_font(&ascii::FONT_6X13));
 
         ui.add_horizontal(Button::new("Button"));
         ui.add(Checkbox::new(&mut checkbox1));
@@ -84,7 +84,7 @@ fn main() -> Result<(), core::convert::Infallible> {
 
         // theming buttons and such
         ui.add(Label::new("Set Colors"));
-        ui.add(Label::new("Note that these are themes cobbled together in\na few minutes, so they might not look great.").with_font(ascii::FONT_5X8));
+        ui.add(Label::new("Note that these are themes cobbled together in\na few minutes, so they might not look great.").with_font(&ascii::FONT_5X8));
         if ui.add_horizontal(Button::new("Dark")).clicked() {
             theme = medsize_rgb565_style();
         }
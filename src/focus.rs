@@ -0,0 +1,274 @@
+//! # Focus
+//!
+//! Focus-based navigation for devices without a pointer (e.g. a touchscreen), such as
+//! devices with a rotary encoder or up / down / enter buttons.
+//!
+//! Focusable widgets (e.g. [Button](crate::widgets::Button), [Checkbox](crate::widgets::Checkbox)
+//! or [Slider](crate::widgets::Slider)) register themselves with the [FocusState] of the [Ui](crate::Ui)
+//! in the order they are drawn. The focus is moved with [Interaction::Next] and
+//! [Interaction::Previous], and the focused widget is activated with [Interaction::Activate].
+//!
+//! Adjustable widgets (like sliders) enter an editing mode when activated, in which
+//! [Interaction::Next] and [Interaction::Previous] change their value instead of moving the focus.
+//! [Interaction::Back] (or activating the widget again) leaves the editing mode.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::widgets::{Button, Slider};
+//! # use kolibri_embedded_gui::Interaction;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let mut volume = 0i16;
+//! # fn read_encoder() -> Interaction { Interaction::Next }
+//! use kolibri_embedded_gui::focus::FocusState;
+//!
+//! // create the focus state outside of the drawing loop
+//! let focus = FocusState::new();
+//!
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     ui.set_focus_state(&focus);
+//!
+//!     // e.g. Interaction::Next when the encoder is turned clockwise
+//!     ui.interact(read_encoder());
+//!
+//!     if ui.add(Button::new("Start")).clicked() {
+//!         // clicked by touch, or by Interaction::Activate while focused
+//!     }
+//!     ui.add(Slider::new(&mut volume, 0..=100));
+//! }
+//! ```
+
+use crate::Interaction;
+use core::cell::Cell;
+use embedded_graphics::primitives::Rectangle;
+
+/// Focus state of the [Ui](crate::Ui), which has to persist across frames.
+///
+/// Set it on the [Ui](crate::Ui) with [Ui::set_focus_state](crate::Ui::set_focus_state) each frame.
+/// The state only consists of a few integers, and is shared with all sub-[Ui](crate::Ui)s
+/// by reference, so no allocations are needed.
+#[derive(Debug, Default)]
+pub struct FocusState {
+    /// Index of the focused widget, in registration order
+    focused: Cell<Option<u16>>,
+    /// Whether the focused widget is being edited
+    editing: Cell<bool>,
+    /// Number of focusable widgets registered in the current frame
+    counter: Cell<u16>,
+    /// Number of focusable widgets registered in the previous frame
+    count: Cell<u16>,
+    /// Whether the interaction of the current frame was already used for navigation
+    navigated: Cell<bool>,
+    /// Index of the first focusable widget of the open modal in the previous frame, if any
+    trap: Cell<Option<u16>>,
+    /// Index of the first focusable widget of the open modal in the current frame, if any
+    trapping: Cell<Option<u16>>,
+}
+
+impl FocusState {
+    /// Creates a new focus state, with no widget focused.
+    pub const fn new() -> Self {
+        FocusState {
+            focused: Cell::new(None),
+            editing: Cell::new(false),
+            counter: Cell::new(0),
+            count: Cell::new(0),
+            navigated: Cell::new(false),
+            trap: Cell::new(None),
+            trapping: Cell::new(None),
+        }
+    }
+
+    /// Returns the index of the focused widget (in drawing order), if any widget is focused.
+    pub fn focused(&self) -> Option<u16> {
+        self.focused.get()
+    }
+
+    /// Focuses the widget with the given index (in drawing order), or removes the focus.
+    pub fn set_focused(&self, focused: Option<u16>) {
+        self.focused.set(focused);
+        self.editing.set(false);
+    }
+
+    /// Returns whether the focused widget is being edited.
+    pub fn is_editing(&self) -> bool {
+        self.editing.get()
+    }
+
+    /// Returns the number of focusable widgets that were drawn in the previous frame.
+    pub fn count(&self) -> u16 {
+        self.count.get()
+    }
+
+    /// Starts a new frame.
+    pub(crate) fn start_frame(&self) {
+        self.count.set(self.counter.get());
+        self.counter.set(0);
+        self.navigated.set(false);
+        self.trap.set(self.trapping.take());
+    }
+
+    /// Traps the focus in the widgets registered from now on until the end of the frame
+    /// (i.e. the content of a modal, see [Ui::modal](crate::Ui::modal)).
+    ///
+    /// From the next frame on, the focus only moves between these widgets, and the widgets
+    /// registered before can't be focused.
+    pub(crate) fn trap(&self) {
+        self.trapping.set(Some(self.counter.get()));
+    }
+
+    /// Moves the focus according to the given interaction.
+    ///
+    /// This only happens once per frame, and not while the focused widget is being edited.
+    pub(crate) fn navigate(&self, interaction: Interaction) {
+        if self.navigated.get() {
+            return;
+        }
+
+        let count = self.count.get().max(1);
+        let first = self.trap.get().unwrap_or(0).min(count - 1);
+        match (interaction, self.editing.get()) {
+            (Interaction::Next, false) => {
+                let next = match self.focused.get() {
+                    Some(focused) if focused >= first && focused + 1 < count => focused + 1,
+                    _ => first,
+                };
+                self.set_focused(Some(next));
+            }
+            (Interaction::Previous, false) => {
+                let prev = match self.focused.get() {
+                    Some(focused) if focused > first && focused < count => focused - 1,
+                    _ => count - 1,
+                };
+                self.set_focused(Some(prev));
+            }
+            (Interaction::Back, true) => self.editing.set(false),
+            (Interaction::Back, false) => self.set_focused(None),
+            _ => return,
+        }
+        self.navigated.set(true);
+    }
+
+    /// Registers a focusable widget, and returns its [Focus] for the given interaction.
+    pub(crate) fn register(&self, interaction: Interaction, adjustable: bool) -> Focus {
+        let index = self.counter.get();
+        self.counter.set(index.saturating_add(1));
+
+        let trapped = self.trap.get().is_some_and(|first| index < first);
+        if trapped || self.focused.get() != Some(index) {
+            return Focus::default();
+        }
+
+        let mut focus = Focus {
+            focused: true,
+            ..Focus::default()
+        };
+        match interaction {
+            Interaction::Activate => {
+                focus.activated = true;
+                if adjustable {
+                    self.editing.set(!self.editing.get());
+                }
+            }
+            Interaction::Next if self.editing.get() => focus.adjust = 1,
+            Interaction::Previous if self.editing.get() => focus.adjust = -1,
+            _ => {}
+        }
+        focus.editing = adjustable && self.editing.get();
+        focus
+    }
+}
+
+/// Focus of a single widget in the current frame, see [Ui::register_focus](crate::Ui::register_focus).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Focus {
+    /// Whether the widget is focused
+    pub focused: bool,
+    /// Whether the widget was activated ([Interaction::Activate]) in this frame
+    pub activated: bool,
+    /// Whether the widget is being edited (only for adjustable widgets)
+    pub editing: bool,
+    /// Adjustment of the edited widget in this frame: `1` for [Interaction::Next],
+    /// `-1` for [Interaction::Previous], `0` otherwise.
+    pub adjust: i8,
+}
+
+impl Focus {
+    /// Maps the focus to a pointer interaction in the given area, so that widgets can handle
+    /// focus like a pointer: a focused widget is hovered, and activating it is a click (release).
+    ///
+    /// Pointer interactions take precedence.
+    pub fn as_pointer_interaction(
+        &self,
+        area: &Rectangle,
+        interaction: Interaction,
+    ) -> Interaction {
+        if interaction.get_point().is_some() {
+            interaction
+        } else if self.activated {
+            Interaction::Release(area.center())
+        } else if self.focused {
+            Interaction::Hover(area.center())
+        } else {
+            Interaction::None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(state: &FocusState, interaction: Interaction, widgets: u16) -> [Focus; 4] {
+        state.start_frame();
+        state.navigate(interaction);
+        let mut focus = [Focus::default(); 4];
+        for (i, f) in focus.iter_mut().enumerate().take(widgets as usize) {
+            // the last widget is adjustable
+            *f = state.register(interaction, i as u16 == widgets - 1);
+        }
+        focus
+    }
+
+    #[test]
+    fn test_focus_navigation() {
+        let state = FocusState::new();
+        frame(&state, Interaction::None, 3);
+        assert_eq!(state.focused(), None);
+
+        assert!(frame(&state, Interaction::Next, 3)[0].focused);
+        assert!(frame(&state, Interaction::Next, 3)[1].focused);
+        assert!(frame(&state, Interaction::Previous, 3)[0].focused);
+        // wraps around
+        assert!(frame(&state, Interaction::Previous, 3)[2].focused);
+        assert!(frame(&state, Interaction::Next, 3)[0].focused);
+
+        frame(&state, Interaction::Back, 3);
+        assert_eq!(state.focused(), None);
+    }
+
+    #[test]
+    fn test_focus_editing() {
+        let state = FocusState::new();
+        state.set_focused(Some(2));
+
+        let focus = frame(&state, Interaction::Activate, 3)[2];
+        assert!(focus.activated && focus.editing);
+
+        // Next / Previous adjust the value instead of moving the focus
+        let focus = frame(&state, Interaction::Next, 3)[2];
+        assert_eq!(focus.adjust, 1);
+        assert_eq!(state.focused(), Some(2));
+        assert_eq!(frame(&state, Interaction::Previous, 3)[2].adjust, -1);
+
+        frame(&state, Interaction::Back, 3);
+        assert!(!state.is_editing());
+        assert_eq!(state.focused(), Some(2));
+    }
+}
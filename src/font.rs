@@ -0,0 +1,512 @@
+//! # Fonts
+//!
+//! All widgets measure and draw their text through the [Font] trait, so kolibri isn't tied to a
+//! single font format. Two implementations are provided:
+//!
+//! - [MonoFont] from embedded-graphics (e.g. `&ascii::FONT_9X15`), which is what the predefined
+//!   styles use.
+//! - [BitmapFont], a proportional font with glyph tables that can be compiled into flash (e.g.
+//!   converted from BDF files). Its glyphs can either be plain bitmaps (1 bit per pixel) or
+//!   anti-aliased with 2 or 4 bits per pixel.
+//!
+//! Fonts report a *coverage* value for every drawn pixel. Anti-aliased pixels are blended
+//! against the background color of the widget with [Style::text_blend](crate::style::Style::text_blend).
+//! Without a blend function (e.g. for [BinaryColor]
+//! displays), every pixel that is covered by at least 50% is drawn in the text color.
+//!
+//! ## Using a font
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::ui::*;
+//! # use kolibri_embedded_gui::widgets::Label;
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! use kolibri_embedded_gui::font::{BitmapFont, BitmapGlyph};
+//!
+//! // a (very small) 2 bit anti-aliased font
+//! static GLYPHS: [BitmapGlyph; 2] = [
+//!     BitmapGlyph::new('-', 4, 0, 3, 3, 1, &[0b0111_1100]),
+//!     BitmapGlyph::new('|', 2, 0, 0, 1, 7, &[0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0]),
+//! ];
+//! static FONT: BitmapFont = BitmapFont::new(&GLYPHS, 2, 8).fallback('-');
+//!
+//! let mut style = medsize_rgb565_style();
+//! style.default_font = &FONT;
+//! let mut ui = Ui::new_fullscreen(&mut display, style);
+//!
+//! ui.add(Label::new("-|-|-"));
+//! // or for a single label
+//! ui.add(Label::new("Hello").with_font(&embedded_graphics::mono_font::ascii::FONT_10X20));
+//! ```
+
+use core::fmt;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Dimensions, OriginDimensions, Point, Size};
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::pixelcolor::{BinaryColor, Gray8, GrayColor, PixelColor, Rgb888, RgbColor};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::{Baseline, Text};
+use embedded_graphics::transform::Transform;
+use embedded_graphics::{Drawable, Pixel};
+
+/// A font that can measure and draw text.
+///
+/// All positions are relative to the top left corner of the text line. The trait is object safe,
+/// so that styles and widgets can refer to any font as `&dyn Font`.
+pub trait Font {
+    /// Returns the height of a line of text.
+    fn line_height(&self) -> u32;
+
+    /// Returns the horizontal advance of a char, i.e. the distance from its position to the
+    /// position of the next char.
+    fn char_width(&self, c: char) -> u32;
+
+    /// Returns the width of a single line of text.
+    fn text_width(&self, text: &str) -> u32 {
+        text.chars().map(|c| self.char_width(c)).sum()
+    }
+
+    /// Returns the byte length of the longest prefix of a single line of text that fits into
+    /// `width` (e.g. to truncate or wrap it).
+    fn fit(&self, text: &str, width: u32) -> usize {
+        let mut used = 0;
+        for (index, c) in text.char_indices() {
+            used += self.char_width(c);
+            if used > width {
+                return index;
+            }
+        }
+        text.len()
+    }
+
+    /// Draws a single line of text with its top left corner at `position`.
+    ///
+    /// `glyph` is called once for every glyph, with the pixels that are (partially) covered by it
+    /// and their coverage from `1` to `255` (fully covered). This way, each glyph can be drawn with
+    /// a single [DrawTarget::draw_iter].
+    fn draw_text(&self, text: &str, position: Point, glyph: &mut dyn FnMut(&mut GlyphPixels));
+}
+
+/// The pixels of a glyph with their coverage, see [Font::draw_text].
+pub type GlyphPixels<'i> = dyn Iterator<Item = (Point, u8)> + 'i;
+
+impl fmt::Debug for dyn Font + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font")
+            .field("line_height", &self.line_height())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Font for MonoFont<'_> {
+    fn line_height(&self) -> u32 {
+        self.character_size.height
+    }
+
+    fn char_width(&self, _c: char) -> u32 {
+        self.character_size.width + self.character_spacing
+    }
+
+    fn text_width(&self, text: &str) -> u32 {
+        text.chars().count() as u32 * self.char_width(' ')
+    }
+
+    fn draw_text(&self, text: &str, position: Point, glyph: &mut dyn FnMut(&mut GlyphPixels)) {
+        let style = MonoTextStyle::new(self, BinaryColor::On);
+        // drawing into the coverage target can't fail
+        let _ = Text::with_baseline(text, position, style, Baseline::Top)
+            .draw(&mut CoverageTarget { glyph });
+    }
+}
+
+/// Draw target that reports the set pixels of a [BinaryColor] drawing as fully covered.
+struct CoverageTarget<'g> {
+    glyph: &'g mut dyn FnMut(&mut GlyphPixels),
+}
+
+impl OriginDimensions for CoverageTarget<'_> {
+    fn size(&self) -> Size {
+        Size::new(u32::MAX >> 1, u32::MAX >> 1)
+    }
+}
+
+impl DrawTarget for CoverageTarget<'_> {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // the mono font draws each glyph with a single call
+        (self.glyph)(
+            &mut pixels
+                .into_iter()
+                .filter(|Pixel(_, color)| color.is_on())
+                .map(|Pixel(point, _)| (point, 255)),
+        );
+        Ok(())
+    }
+}
+
+/// A single glyph of a [BitmapFont].
+///
+/// The bitmap is stored row by row, with the leftmost pixel in the most significant bits. Every
+/// row starts at a new byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitmapGlyph<'a> {
+    /// The char this glyph is drawn for
+    pub c: char,
+    /// Horizontal advance to the next char
+    pub advance: u8,
+    /// Horizontal offset of the bitmap from the char position
+    pub x: i8,
+    /// Vertical offset of the bitmap from the top of the line
+    pub y: i8,
+    /// Width of the bitmap in pixels
+    pub width: u8,
+    /// Height of the bitmap in pixels
+    pub height: u8,
+    /// The packed bitmap
+    pub data: &'a [u8],
+}
+
+impl<'a> BitmapGlyph<'a> {
+    /// Creates a new glyph.
+    pub const fn new(
+        c: char,
+        advance: u8,
+        x: i8,
+        y: i8,
+        width: u8,
+        height: u8,
+        data: &'a [u8],
+    ) -> Self {
+        Self {
+            c,
+            advance,
+            x,
+            y,
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Returns the raw value of a pixel of the bitmap (`0` if it is out of bounds).
+    fn value(&self, x: u32, y: u32, bits_per_pixel: u8) -> u8 {
+        let bpp = bits_per_pixel as u32;
+        let stride = (self.width as u32 * bpp).div_ceil(8);
+        let bit = x * bpp;
+        let Some(byte) = self.data.get((y * stride + bit / 8) as usize) else {
+            return 0;
+        };
+        let mask = (1u16 << bpp) as u8 - 1;
+        (byte >> (8 - bpp - bit % 8)) & mask
+    }
+}
+
+/// A proportional bitmap font.
+///
+/// The glyphs have to be sorted by char, so that they can be found with a binary search. Chars
+/// without a glyph are drawn with the fallback glyph (if there is one), or skipped.
+///
+/// Glyphs are either plain bitmaps (`bits_per_pixel = 1`) or anti-aliased with 2 or 4 bits per
+/// pixel, where the highest value is fully covered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitmapFont<'a> {
+    /// The glyphs, sorted by char
+    pub glyphs: &'a [BitmapGlyph<'a>],
+    /// Bits per pixel of the glyph bitmaps (1, 2 or 4)
+    pub bits_per_pixel: u8,
+    /// Height of a line of text
+    pub line_height: u32,
+    /// Char that is drawn for chars without a glyph
+    pub fallback: Option<char>,
+}
+
+impl<'a> BitmapFont<'a> {
+    /// Creates a new font from a sorted glyph table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits_per_pixel` is not 1, 2 or 4.
+    pub const fn new(glyphs: &'a [BitmapGlyph<'a>], bits_per_pixel: u8, line_height: u32) -> Self {
+        assert!(
+            matches!(bits_per_pixel, 1 | 2 | 4),
+            "bits_per_pixel must be 1, 2 or 4"
+        );
+        Self {
+            glyphs,
+            bits_per_pixel,
+            line_height,
+            fallback: None,
+        }
+    }
+
+    /// Sets the char that is drawn for chars without a glyph.
+    pub const fn fallback(mut self, fallback: char) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Returns the glyph for a char (or the fallback glyph).
+    pub fn glyph(&self, c: char) -> Option<&BitmapGlyph<'a>> {
+        let find = |c: char| {
+            self.glyphs
+                .binary_search_by(|glyph| glyph.c.cmp(&c))
+                .ok()
+                .map(|index| &self.glyphs[index])
+        };
+        find(c).or_else(|| self.fallback.and_then(find))
+    }
+}
+
+impl Font for BitmapFont<'_> {
+    fn line_height(&self) -> u32 {
+        self.line_height
+    }
+
+    fn char_width(&self, c: char) -> u32 {
+        self.glyph(c).map_or(0, |glyph| glyph.advance as u32)
+    }
+
+    fn draw_text(&self, text: &str, position: Point, draw: &mut dyn FnMut(&mut GlyphPixels)) {
+        let max = (1u16 << self.bits_per_pixel) - 1;
+        let mut pen = position;
+        for glyph in text.chars().filter_map(|c| self.glyph(c)) {
+            let origin = pen + Point::new(glyph.x as i32, glyph.y as i32);
+            let (width, height) = (glyph.width as u32, glyph.height as u32);
+            draw(
+                &mut (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .filter_map(|(x, y)| {
+                        let value = glyph.value(x, y, self.bits_per_pixel) as u16;
+                        let coverage = (value * 255 / max) as u8;
+                        (value > 0).then(|| (origin + Point::new(x as i32, y as i32), coverage))
+                    }),
+            );
+            pen.x += glyph.advance as i32;
+        }
+    }
+}
+
+/// Blends a foreground over a background color, with the coverage from `0` (background) to
+/// `255` (foreground).
+pub type BlendFn<COL> = fn(background: COL, foreground: COL, coverage: u8) -> COL;
+
+/// Linear interpolation between two channel values.
+fn mix(background: u8, foreground: u8, coverage: u8) -> u8 {
+    let coverage = coverage as u32;
+    ((background as u32 * (255 - coverage) + foreground as u32 * coverage + 127) / 255) as u8
+}
+
+/// [BlendFn] for RGB colors (e.g. [Rgb565](embedded_graphics::pixelcolor::Rgb565)).
+pub fn blend_rgb<COL>(background: COL, foreground: COL, coverage: u8) -> COL
+where
+    COL: RgbColor + From<Rgb888>,
+    Rgb888: From<COL>,
+{
+    let (bg, fg) = (Rgb888::from(background), Rgb888::from(foreground));
+    Rgb888::new(
+        mix(bg.r(), fg.r(), coverage),
+        mix(bg.g(), fg.g(), coverage),
+        mix(bg.b(), fg.b(), coverage),
+    )
+    .into()
+}
+
+/// [BlendFn] for grayscale colors (e.g. [Gray4](embedded_graphics::pixelcolor::Gray4)).
+pub fn blend_gray<COL>(background: COL, foreground: COL, coverage: u8) -> COL
+where
+    COL: GrayColor + From<Gray8>,
+    Gray8: From<COL>,
+{
+    let (bg, fg) = (Gray8::from(background), Gray8::from(foreground));
+    Gray8::new(mix(bg.luma(), fg.luma(), coverage)).into()
+}
+
+/// A single line of text, drawn with a [Font].
+///
+/// This is the [Font] counterpart of [Text], positioned at its top left corner. If a background
+/// color and a [BlendFn] are set, anti-aliased pixels are blended against the background.
+/// Otherwise, only pixels that are covered by at least 50% are drawn.
+#[derive(Clone, Copy, Debug)]
+pub struct FontText<'t, COL: PixelColor> {
+    /// The text
+    pub text: &'t str,
+    /// Top left corner of the text
+    pub position: Point,
+    /// The font
+    pub font: &'t dyn Font,
+    /// The text color
+    pub color: COL,
+    /// Background color and blend function for anti-aliasing
+    pub blend: Option<(COL, BlendFn<COL>)>,
+}
+
+impl<'t, COL: PixelColor> FontText<'t, COL> {
+    /// Creates a new text without anti-aliasing.
+    pub fn new(text: &'t str, position: Point, font: &'t dyn Font, color: COL) -> Self {
+        Self {
+            text,
+            position,
+            font,
+            color,
+            blend: None,
+        }
+    }
+
+    /// Blends anti-aliased pixels against the `background` color, if there is a blend function.
+    pub fn blend(mut self, background: COL, blend: Option<BlendFn<COL>>) -> Self {
+        self.blend = blend.map(|blend| (background, blend));
+        self
+    }
+}
+
+impl<COL: PixelColor> Dimensions for FontText<'_, COL> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(
+            self.position,
+            Size::new(self.font.text_width(self.text), self.font.line_height()),
+        )
+    }
+}
+
+impl<COL: PixelColor> Transform for FontText<'_, COL> {
+    fn translate(&self, by: Point) -> Self {
+        let mut text = *self;
+        text.position += by;
+        text
+    }
+
+    fn translate_mut(&mut self, by: Point) -> &mut Self {
+        self.position += by;
+        self
+    }
+}
+
+impl<COL: PixelColor> Drawable for FontText<'_, COL> {
+    type Color = COL;
+    /// The position of the next char
+    type Output = Point;
+
+    fn draw<D>(&self, target: &mut D) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = COL>,
+    {
+        let mut result = Ok(());
+        self.font
+            .draw_text(self.text, self.position, &mut |pixels| {
+                if result.is_err() {
+                    return;
+                }
+                result = target.draw_iter(pixels.filter_map(|(point, coverage)| {
+                    let color = match self.blend {
+                        _ if coverage == 255 => self.color,
+                        Some((background, blend)) => blend(background, self.color, coverage),
+                        None if coverage >= 128 => self.color,
+                        None => return None,
+                    };
+                    Some(Pixel(point, color))
+                }));
+            });
+        result.map(|_| self.position + Point::new(self.font.text_width(self.text) as i32, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::mono_font::ascii::FONT_6X10;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    static GLYPHS: [BitmapGlyph; 2] = [
+        // 2 bit anti-aliased: 0, 1/3, 2/3, full
+        BitmapGlyph::new('a', 5, 0, 1, 4, 1, &[0b0001_1011]),
+        BitmapGlyph::new('b', 3, 1, 0, 1, 2, &[0xC0, 0xC0]),
+    ];
+    static FONT: BitmapFont = BitmapFont::new(&GLYPHS, 2, 4).fallback('b');
+
+    #[test]
+    fn test_font_metrics() {
+        assert_eq!(FONT_6X10.text_width("abc"), 18);
+        assert_eq!(Font::line_height(&FONT_6X10), 10);
+
+        assert_eq!(FONT.text_width("ab"), 8);
+        // falls back to 'b'
+        assert_eq!(FONT.text_width("ax"), 8);
+        assert_eq!(FONT.glyph('x').map(|glyph| glyph.c), Some('b'));
+    }
+
+    #[test]
+    fn test_anti_aliased_text() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let text = FontText::new("ab", Point::zero(), &FONT, Rgb565::WHITE)
+            .blend(Rgb565::BLACK, Some(blend_rgb));
+        let next = text.draw(&mut display).unwrap();
+        assert_eq!(next, Point::new(8, 0));
+
+        assert_eq!(display.get_pixel(Point::new(0, 1)), None);
+        let third = display.get_pixel(Point::new(1, 1)).unwrap();
+        let two_thirds = display.get_pixel(Point::new(2, 1)).unwrap();
+        assert!(third.g() > 0 && third.g() < two_thirds.g());
+        assert!(two_thirds.g() < Rgb565::WHITE.g());
+        assert_eq!(display.get_pixel(Point::new(3, 1)), Some(Rgb565::WHITE));
+        assert_eq!(display.get_pixel(Point::new(6, 0)), Some(Rgb565::WHITE));
+
+        // without blending, pixels are thresholded
+        let mut display = MockDisplay::<Rgb565>::new();
+        FontText::new("a", Point::zero(), &FONT, Rgb565::WHITE)
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(display.get_pixel(Point::new(1, 1)), None);
+        assert_eq!(display.get_pixel(Point::new(2, 1)), Some(Rgb565::WHITE));
+    }
+
+    /// Draw target that counts the calls to `draw_iter`.
+    #[derive(Default)]
+    struct CountingTarget {
+        calls: usize,
+    }
+
+    impl OriginDimensions for CountingTarget {
+        fn size(&self) -> Size {
+            Size::new(64, 64)
+        }
+    }
+
+    impl DrawTarget for CountingTarget {
+        type Color = Rgb565;
+        type Error = core::convert::Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            self.calls += 1;
+            pixels.into_iter().for_each(drop);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_glyphs_drawn_at_once() {
+        let mut target = CountingTarget::default();
+        FontText::new("abx", Point::zero(), &FONT, Rgb565::WHITE)
+            .draw(&mut target)
+            .unwrap();
+        assert_eq!(target.calls, 3);
+
+        let mut target = CountingTarget::default();
+        FontText::new("ab", Point::zero(), &FONT_6X10, Rgb565::WHITE)
+            .draw(&mut target)
+            .unwrap();
+        assert_eq!(target.calls, 2);
+    }
+}
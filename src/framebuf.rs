@@ -62,14 +62,16 @@ impl<C: PixelColor> DrawTarget for WidgetFramebuf<'_, C> {
     {
         for pixel in pixels {
             let pt = pixel.0.sub(self.position);
-            let pos = pt.y * self.size.width as i32 + pt.x;
-            if pos < 0 || pos >= self.len as i32
-            /* check for trunc maybe? */
+            if pt.x < 0
+                || pt.y < 0
+                || pt.x >= self.size.width as i32
+                || pt.y >= self.size.height as i32
             {
-                // !! Make sure that len is correct in new() !!
-                // skip pixels outside of the framebuffer
+                // skip pixels outside of the framebuffer (instead of wrapping them into the
+                // next line, e.g. when drawing in bands)
                 continue;
             }
+            let pos = pt.y * self.size.width as i32 + pt.x;
             self.buf[pos as usize] = pixel.1;
         }
 
@@ -159,7 +161,7 @@ impl<C: PixelColor> Drawable for WidgetFramebuf<'_, C> {
     {
         target.fill_contiguous(
             &Rectangle::new(self.position, self.size),
-            self.buf.iter().cloned(),
+            self.buf[..self.len].iter().cloned(),
         )
     }
 }
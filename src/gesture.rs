@@ -0,0 +1,372 @@
+//! # Gestures
+//!
+//! Gesture recognition on top of the raw pointer [Interaction]s: long-presses, double-taps and
+//! swipes.
+//!
+//! The [GestureState] keeps track of the pointer across frames. It needs the time of each frame
+//! in milliseconds, which comes from a monotonic counter of the caller (e.g. a timer, or a SysTick
+//! counter), so no clock is needed in `no_std` environments. The counter may wrap around.
+//!
+//! Recognized gestures are reported on the [Response](crate::Response) of the widget they started
+//! on (see [Response::long_pressed](crate::Response::long_pressed),
+//! [Response::double_clicked](crate::Response::double_clicked) and
+//! [Response::swiped](crate::Response::swiped)), and swipes anywhere on the screen with
+//! [Ui::swiped](crate::Ui::swiped) (e.g. to switch pages).
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::widgets::Button;
+//! # use kolibri_embedded_gui::Interaction;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let mut page = 0;
+//! # fn read_touch() -> Interaction { Interaction::None }
+//! # fn millis() -> u32 { 0 }
+//! use kolibri_embedded_gui::gesture::{GestureState, SwipeDirection};
+//!
+//! // create the gesture state outside of the drawing loop
+//! let gestures = GestureState::new().long_press_ms(800);
+//!
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     ui.set_gesture_state(&gestures, millis());
+//!     ui.interact(read_touch());
+//!
+//!     if ui.add(Button::new("Delete")).long_pressed() {
+//!         // delete without asking
+//!     }
+//!     match ui.swiped() {
+//!         Some(SwipeDirection::Left) => page += 1,
+//!         Some(SwipeDirection::Right) => page -= 1,
+//!         _ => {}
+//!     }
+//! }
+//! ```
+
+use crate::Interaction;
+use core::cell::Cell;
+use embedded_graphics::geometry::Point;
+
+/// Direction of a swipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// A recognized gesture.
+///
+/// Each gesture holds the point at which it started, which decides which widget it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gesture {
+    /// The pointer was held down (without moving) for at least the long-press time.
+    ///
+    /// Reported once, while the pointer is still down. The following release is no tap, and no
+    /// click (see [GestureState::long_press_released]).
+    LongPress(Point),
+    /// The pointer was tapped twice at (nearly) the same point within the double-tap time.
+    ///
+    /// Reported on the second release.
+    DoubleTap(Point),
+    /// The pointer was moved quickly by at least the swipe distance, starting at the point.
+    ///
+    /// Reported on the release.
+    Swipe(SwipeDirection, Point),
+}
+
+impl Gesture {
+    /// Returns the point at which the gesture started.
+    pub fn point(&self) -> Point {
+        match self {
+            Gesture::LongPress(point) | Gesture::DoubleTap(point) | Gesture::Swipe(_, point) => {
+                *point
+            }
+        }
+    }
+}
+
+/// A pointer that is down: where and when it was pressed, and where it is now.
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    start: Point,
+    time: u32,
+    last: Point,
+    long: bool,
+}
+
+/// Gesture state of the [Ui](crate::Ui), which has to persist across frames.
+///
+/// Set it on the [Ui](crate::Ui) with [Ui::set_gesture_state](crate::Ui::set_gesture_state) each
+/// frame. The timing and distance thresholds can be changed with the builder methods.
+#[derive(Debug)]
+pub struct GestureState {
+    long_press_ms: u32,
+    double_tap_ms: u32,
+    swipe_ms: u32,
+    swipe_distance: u32,
+    tap_slop: u32,
+    /// Time of the current frame
+    now: Cell<u32>,
+    /// The pointer that is down, if any
+    press: Cell<Option<Press>>,
+    /// Point and time of the last tap (for double-taps)
+    tap: Cell<Option<(Point, u32)>>,
+    /// Gesture recognized in the current frame
+    gesture: Cell<Option<Gesture>>,
+    /// Whether the pointer interaction of the current frame was already processed
+    fed: Cell<bool>,
+    /// Whether the pointer was released at the end of a long-press in the current frame
+    long_released: Cell<bool>,
+}
+
+impl Default for GestureState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureState {
+    /// Creates a new gesture state with the default thresholds: long-presses after 500ms,
+    /// double-taps within 300ms, and swipes of at least 30px within 500ms.
+    pub const fn new() -> Self {
+        GestureState {
+            long_press_ms: 500,
+            double_tap_ms: 300,
+            swipe_ms: 500,
+            swipe_distance: 30,
+            tap_slop: 8,
+            now: Cell::new(0),
+            press: Cell::new(None),
+            tap: Cell::new(None),
+            gesture: Cell::new(None),
+            fed: Cell::new(false),
+            long_released: Cell::new(false),
+        }
+    }
+
+    /// Sets the time the pointer has to be held down for a long-press.
+    pub const fn long_press_ms(mut self, ms: u32) -> Self {
+        self.long_press_ms = ms;
+        self
+    }
+
+    /// Sets the maximum time between the two taps of a double-tap.
+    pub const fn double_tap_ms(mut self, ms: u32) -> Self {
+        self.double_tap_ms = ms;
+        self
+    }
+
+    /// Sets the maximum duration of a swipe.
+    pub const fn swipe_ms(mut self, ms: u32) -> Self {
+        self.swipe_ms = ms;
+        self
+    }
+
+    /// Sets the minimum distance (in pixels, along the direction of the swipe) of a swipe.
+    pub const fn swipe_distance(mut self, distance: u32) -> Self {
+        self.swipe_distance = distance;
+        self
+    }
+
+    /// Sets how far (in pixels) the pointer may move during a tap or long-press, e.g. to allow
+    /// for noisy touch screens.
+    pub const fn tap_slop(mut self, slop: u32) -> Self {
+        self.tap_slop = slop;
+        self
+    }
+
+    /// Returns the gesture recognized in the current frame, if any.
+    pub fn gesture(&self) -> Option<Gesture> {
+        self.gesture.get()
+    }
+
+    /// Returns whether the pointer is down.
+    pub fn is_pressed(&self) -> bool {
+        self.press.get().is_some()
+    }
+
+    /// Returns whether the pointer was released in the current frame at the end of a
+    /// long-press. This release is no click.
+    pub fn long_press_released(&self) -> bool {
+        self.long_released.get()
+    }
+
+    /// Starts a new frame at the given time (in milliseconds).
+    ///
+    /// Long-presses are recognized here, so that they are reported even if the pointer driver
+    /// doesn't send any interactions while the pointer is held still.
+    pub(crate) fn start_frame(&self, now: u32) {
+        self.now.set(now);
+        self.fed.set(false);
+        self.long_released.set(false);
+        self.gesture.set(None);
+        self.check_long_press();
+    }
+
+    /// Feeds the pointer interaction of the current frame into the recognizer.
+    ///
+    /// This only happens once per frame. Interactions without a point are ignored.
+    pub(crate) fn feed(&self, interaction: Interaction) {
+        if self.fed.get() || interaction.get_point().is_none() {
+            return;
+        }
+        self.fed.set(true);
+
+        let now = self.now.get();
+        match (interaction, self.press.get()) {
+            (Interaction::Click(point) | Interaction::Drag(point), Some(press)) => {
+                self.press.set(Some(Press {
+                    last: point,
+                    ..press
+                }));
+                self.check_long_press();
+            }
+            (Interaction::Click(point) | Interaction::Drag(point), None) => {
+                self.press.set(Some(Press {
+                    start: point,
+                    time: now,
+                    last: point,
+                    long: false,
+                }));
+            }
+            (Interaction::Release(point), Some(press)) => {
+                self.press.set(None);
+                self.long_released.set(press.long);
+                if !press.long {
+                    self.release(press, point);
+                }
+            }
+            _ => self.press.set(None),
+        }
+    }
+
+    /// Recognizes swipes and (double-)taps when the pointer is released.
+    fn release(&self, press: Press, point: Point) {
+        let now = self.now.get();
+        let delta = point - press.start;
+        let (dx, dy) = (delta.x.unsigned_abs(), delta.y.unsigned_abs());
+
+        if dx.max(dy) >= self.swipe_distance {
+            if now.wrapping_sub(press.time) <= self.swipe_ms {
+                let direction = match (dx >= dy, delta.x < 0, delta.y < 0) {
+                    (true, true, _) => SwipeDirection::Left,
+                    (true, false, _) => SwipeDirection::Right,
+                    (false, _, true) => SwipeDirection::Up,
+                    (false, _, false) => SwipeDirection::Down,
+                };
+                self.gesture
+                    .set(Some(Gesture::Swipe(direction, press.start)));
+            }
+            self.tap.set(None);
+        } else if self.within_slop(press.start, point) {
+            match self.tap.take() {
+                Some((tap, time))
+                    if now.wrapping_sub(time) <= self.double_tap_ms
+                        && self.within_slop(tap, point) =>
+                {
+                    self.gesture.set(Some(Gesture::DoubleTap(tap)));
+                }
+                _ => self.tap.set(Some((point, now))),
+            }
+        } else {
+            self.tap.set(None);
+        }
+    }
+
+    fn check_long_press(&self) {
+        let Some(press) = self.press.get() else {
+            return;
+        };
+        if !press.long
+            && self.now.get().wrapping_sub(press.time) >= self.long_press_ms
+            && self.within_slop(press.start, press.last)
+        {
+            self.press.set(Some(Press {
+                long: true,
+                ..press
+            }));
+            self.tap.set(None);
+            self.gesture.set(Some(Gesture::LongPress(press.start)));
+        }
+    }
+
+    fn within_slop(&self, a: Point, b: Point) -> bool {
+        let delta = a - b;
+        delta.x.unsigned_abs().max(delta.y.unsigned_abs()) <= self.tap_slop
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(state: &GestureState, now: u32, interaction: Interaction) -> Option<Gesture> {
+        state.start_frame(now);
+        state.feed(interaction);
+        state.gesture()
+    }
+
+    #[test]
+    fn test_gestures() {
+        let state = GestureState::new();
+        let p = Point::new(10, 10);
+
+        // long-press, reported once while the pointer is held still
+        assert_eq!(frame(&state, 0, Interaction::Click(p)), None);
+        assert_eq!(frame(&state, 400, Interaction::None), None);
+        assert_eq!(
+            frame(&state, 500, Interaction::None),
+            Some(Gesture::LongPress(p))
+        );
+        assert_eq!(frame(&state, 600, Interaction::Drag(p)), None);
+        assert!(!state.long_press_released());
+        assert_eq!(frame(&state, 700, Interaction::Release(p)), None);
+        assert!(state.long_press_released());
+
+        // double-tap, also across a wrapping counter
+        let t = u32::MAX - 50;
+        assert_eq!(frame(&state, t, Interaction::Click(p)), None);
+        assert_eq!(frame(&state, t + 50, Interaction::Release(p)), None);
+        assert!(!state.long_press_released());
+        assert_eq!(frame(&state, 100, Interaction::Click(p)), None);
+        let q = Point::new(12, 9);
+        assert_eq!(
+            frame(&state, 150, Interaction::Release(q)),
+            Some(Gesture::DoubleTap(p))
+        );
+
+        // too slow for a double-tap
+        frame(&state, 1000, Interaction::Click(p));
+        frame(&state, 1050, Interaction::Release(p));
+        frame(&state, 1400, Interaction::Click(p));
+        assert_eq!(frame(&state, 1450, Interaction::Release(p)), None);
+
+        // swipes
+        frame(&state, 2000, Interaction::Click(p));
+        frame(&state, 2050, Interaction::Drag(Point::new(30, 15)));
+        assert_eq!(
+            frame(&state, 2100, Interaction::Release(Point::new(60, 20))),
+            Some(Gesture::Swipe(SwipeDirection::Right, p))
+        );
+        frame(&state, 3000, Interaction::Click(p));
+        assert_eq!(
+            frame(&state, 3100, Interaction::Release(Point::new(0, -40))),
+            Some(Gesture::Swipe(SwipeDirection::Up, p))
+        );
+
+        // too slow for a swipe
+        frame(&state, 4000, Interaction::Click(p));
+        frame(&state, 4100, Interaction::Drag(Point::new(40, 10)));
+        assert_eq!(
+            frame(&state, 5000, Interaction::Release(Point::new(60, 10))),
+            None
+        );
+    }
+}
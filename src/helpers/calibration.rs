@@ -0,0 +1,211 @@
+//! Touch calibration screen.
+//!
+//! Resistive touch controllers report raw ADC values instead of display coordinates, and the
+//! touch panel may be rotated or mirrored relative to the display. The [Calibration] screen draws
+//! crosshair targets one after another, collects a touch on each of them, and computes the
+//! [InteractionTransform] that maps the raw coordinates onto the display. The app can store its
+//! [coefficients](InteractionTransform::coefficients) (e.g. in flash), and set the transform on
+//! the [Ui] with [Ui::set_interaction_transform] from then on.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::Ui;
+//! # use kolibri_embedded_gui::Interaction;
+//! # use embedded_graphics::prelude::*;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # fn read_touch_raw() -> Interaction { Interaction::None }
+//! # fn store_in_flash(_: [i32; 6]) {}
+//! use kolibri_embedded_gui::helpers::calibration::{Calibration, CalibrationPoints};
+//!
+//! let mut calibration = Calibration::new(display.bounding_box(), CalibrationPoints::Five);
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     // no transform is set on the Ui, the calibration needs the raw coordinates
+//!     let raw = read_touch_raw();
+//!     ui.interact(raw);
+//!     if let Ok(Some(transform)) = calibration.show(&mut ui, raw) {
+//!         store_in_flash(transform.coefficients());
+//!         break;
+//!     }
+//! }
+//! ```
+use crate::interaction::InteractionTransform;
+use crate::widgets::Label;
+use crate::{GuiResult, Interaction, Ui};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Line, PrimitiveStyle, Rectangle};
+use heapless::Vec;
+
+/// Half the length of the lines of a crosshair, in pixels.
+const CROSSHAIR_RADIUS: i32 = 8;
+
+/// Number of targets of a [Calibration].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CalibrationPoints {
+    /// Three targets, which is enough to compute the transform
+    Three,
+    /// Five targets (the corners and the center), which averages out inaccurate touches
+    Five,
+}
+
+/// Touch calibration screen, which has to persist across frames (see the
+/// [module documentation](self)).
+#[derive(Clone, Debug)]
+pub struct Calibration {
+    area: Rectangle,
+    targets: Vec<Point, 5>,
+    raw: Vec<Point, 5>,
+    /// Target whose crosshair is drawn, if any
+    drawn: Option<usize>,
+}
+
+impl Calibration {
+    /// Creates a calibration screen for the given area of the display (usually its bounding box).
+    pub fn new(area: Rectangle, points: CalibrationPoints) -> Self {
+        // targets at 10% / 90% of the area, which are easy to hit
+        let at = |x: u32, y: u32| {
+            area.top_left
+                + Point::new(
+                    (area.size.width * x / 10) as i32,
+                    (area.size.height * y / 10) as i32,
+                )
+        };
+        let targets: &[Point] = match points {
+            CalibrationPoints::Three => &[at(1, 1), at(9, 5), at(5, 9)],
+            CalibrationPoints::Five => &[at(1, 1), at(9, 1), at(9, 9), at(1, 9), at(5, 5)],
+        };
+        Self {
+            area,
+            targets: Vec::from_slice(targets).unwrap(),
+            raw: Vec::new(),
+            drawn: None,
+        }
+    }
+
+    /// Returns the display coordinates of the targets.
+    pub fn targets(&self) -> &[Point] {
+        &self.targets
+    }
+
+    /// Returns the index of the target that has to be touched next, or `None` if all targets
+    /// were touched.
+    pub fn current(&self) -> Option<usize> {
+        (self.raw.len() < self.targets.len()).then_some(self.raw.len())
+    }
+
+    /// Starts the calibration over.
+    pub fn reset(&mut self) {
+        self.raw.clear();
+    }
+
+    /// Draws the crosshair of the current target, and records the raw touch on it.
+    ///
+    /// `raw` is the interaction in raw touch coordinates, so no [InteractionTransform] must be set
+    /// on the [Ui]. A touch is recorded when it is released.
+    ///
+    /// ## Returns
+    ///
+    /// The [InteractionTransform] in the frame in which the last target was touched. If the
+    /// touches were unusable (e.g. all at the same point), the calibration starts over.
+    pub fn show<DRAW, COL>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+        raw: Interaction,
+    ) -> GuiResult<Option<InteractionTransform>>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        let mut transform = None;
+        if let (Interaction::Release(point), Some(_)) = (raw, self.current()) {
+            self.raw.push(point).ok();
+            if self.current().is_none() {
+                transform = InteractionTransform::from_points(&self.raw, &self.targets);
+                if transform.is_none() {
+                    self.reset();
+                }
+            }
+        }
+
+        ui.add_centered(Label::new("Touch the crosshair"));
+
+        let current = self.current();
+        if ui.cleared() {
+            self.drawn = None;
+        }
+        if self.drawn != current {
+            if let Some(drawn) = self.drawn {
+                ui.clear_area(self.crosshair_area(drawn))?;
+            }
+            if let Some(current) = current {
+                let target = self.targets[current];
+                let style = PrimitiveStyle::with_stroke(ui.style().text_color, 1);
+                let (h, v) = (
+                    Point::new(CROSSHAIR_RADIUS, 0),
+                    Point::new(0, CROSSHAIR_RADIUS),
+                );
+                ui.draw(&Line::new(target - h, target + h).into_styled(style))?;
+                ui.draw(&Line::new(target - v, target + v).into_styled(style))?;
+            }
+            self.drawn = current;
+        }
+        Ok(transform)
+    }
+
+    /// Returns the area covered by the crosshair of the given target.
+    fn crosshair_area(&self, index: usize) -> Rectangle {
+        let size = 2 * CROSSHAIR_RADIUS as u32 + 1;
+        Rectangle::with_center(self.targets[index], Size::new(size, size)).intersection(&self.area)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use crate::testing::Harness;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_calibration() {
+        let size = Size::new(320, 240);
+        let mut harness = Harness::new(size, medsize_rgb565_style());
+        let mut calibration =
+            Calibration::new(Rectangle::new(Point::zero(), size), CalibrationPoints::Five);
+
+        // raw ADC values of a panel that is mirrored horizontally
+        let raw = |p: Point| Point::new(3800 - p.x * 11, 300 + p.y * 14);
+        let mut result = None;
+        let targets: Vec<Point, 5> = Vec::from_slice(calibration.targets()).unwrap();
+        for (index, target) in targets.into_iter().enumerate() {
+            assert_eq!(calibration.current(), Some(index));
+            for interaction in [
+                Interaction::Click(raw(target)),
+                Interaction::Release(raw(target)),
+            ] {
+                harness.frame(interaction, |ui| {
+                    result = calibration.show(ui, interaction).unwrap();
+                });
+                // the crosshair of the current target is drawn
+                if let Some(current) = calibration.current() {
+                    let target = calibration.targets()[current];
+                    assert_eq!(harness.display().pixel(target), Some(Rgb565::WHITE));
+                }
+            }
+        }
+
+        assert_eq!(calibration.current(), None);
+        let transform = result.unwrap();
+        assert_eq!(
+            transform.apply(raw(Point::new(200, 100))),
+            Point::new(200, 100)
+        );
+    }
+}
@@ -5,6 +5,7 @@
 use crate::smartstate::SmartstateProvider;
 use crate::widgets::Button;
 use crate::widgets::IconButton;
+use crate::widgets::TextCursor;
 use crate::{InternalResponse, Response, Ui};
 use embedded_graphics::prelude::*;
 use embedded_iconoir::size16px;
@@ -523,6 +524,52 @@ pub fn draw_keyboard<
     COL: PixelColor,
     const M: usize,
     const N: usize,
+>(
+    ui: &mut Ui<DRAW, COL>,
+    layout: &Layout<'_>,
+    smartstates: Option<&mut SmartstateProvider<M>>,
+    draw_num_row: bool,
+    pad: bool,
+    shift: &mut bool,
+    open: &mut bool,
+    text: &mut heapless::String<N>,
+) -> Response {
+    // always edit at the end of the text
+    let mut cursor = TextCursor::at_end(text);
+    draw_keyboard_with_cursor(
+        ui,
+        layout,
+        smartstates,
+        draw_num_row,
+        pad,
+        shift,
+        open,
+        text,
+        &mut cursor,
+    )
+}
+
+/// Draw a keyboard to the screen that edits the given string at the position of a [TextCursor].
+///
+/// This works just like [draw_keyboard], but keys are inserted at the cursor (replacing the
+/// selection, if any), and the backspace key deletes the selection or the character before
+/// the cursor. Use the same cursor for a [TextInput](crate::widgets::TextInput) to display the text.
+///
+/// # Arguments
+///
+/// See [draw_keyboard]. Additionally:
+///
+/// * `cursor`: The cursor at which characters are inserted / deleted.
+///
+/// # Returns
+///
+/// See [draw_keyboard].
+#[allow(clippy::too_many_arguments)]
+pub fn draw_keyboard_with_cursor<
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+    const M: usize,
+    const N: usize,
 >(
     ui: &mut Ui<DRAW, COL>,
     layout: &Layout<'_>,
@@ -532,6 +579,7 @@ pub fn draw_keyboard<
     shift: &mut bool,
     open: &mut bool,
     text: &mut heapless::String<N>,
+    cursor: &mut TextCursor,
 ) -> Response {
     // if open: clear to bottom and draw keyboard
     // if not open: clear to bottom
@@ -548,7 +596,6 @@ pub fn draw_keyboard<
         })
         .unwrap_or(true);
 
-    // FIXME: fix redraw after each key press
     if redraw {
         if let Some(smp) = smartstates.as_mut() {
             smp.force_redraw_remaining();
@@ -596,7 +643,7 @@ pub fn draw_keyboard<
 
             if ui.add_horizontal(button).clicked() {
                 clicked = true;
-                if text.push(btn_char).is_ok() {
+                if cursor.insert(text, btn_char) {
                     changed = true;
                 }
             }
@@ -626,17 +673,24 @@ pub fn draw_keyboard<
 
         if ui.add_horizontal(button).clicked() {
             clicked = true;
-            if text.push(btn_char).is_ok() {
+            if cursor.insert(text, btn_char) {
                 changed = true;
             }
         }
     }
     if ui
-        .add(IconButton::<size16px::navigation::NavArrowLeft>::new_from_type())
+        .add({
+            let b = IconButton::<size16px::navigation::NavArrowLeft>::new_from_type();
+            if let Some(smartstates) = smartstates.as_mut() {
+                b.smartstate(smartstates.nxt())
+            } else {
+                b
+            }
+        })
         .clicked()
     {
         clicked = true;
-        if text.pop().is_some() {
+        if cursor.delete_backward(text) {
             changed = true;
         }
     }
@@ -668,7 +722,7 @@ pub fn draw_keyboard<
 
         if ui.add_horizontal(button).clicked() {
             clicked = true;
-            if text.push(btn_char).is_ok() {
+            if cursor.insert(text, btn_char) {
                 changed = true;
             }
         }
@@ -703,7 +757,7 @@ pub fn draw_keyboard<
 
         if ui.add_horizontal(button).clicked() {
             clicked = true;
-            if text.push(btn_char).is_ok() {
+            if cursor.insert(text, btn_char) {
                 changed = true;
             }
         }
@@ -765,7 +819,7 @@ pub fn draw_keyboard<
         .clicked()
     {
         clicked = true;
-        if text.push(' ').is_ok() {
+        if cursor.insert(text, ' ') {
             changed = true;
         }
     }
@@ -792,3 +846,45 @@ pub fn draw_keyboard<
         .set_changed(changed)
         .set_down(redraw)
 }
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use crate::testing::Harness;
+    use crate::Interaction;
+    use embedded_graphics::pixelcolor::{Rgb565, RgbColor};
+
+    #[test]
+    fn test_keyboard_idle_without_redraw() {
+        let mut harness = Harness::new(Size::new(320, 240), medsize_rgb565_style());
+        let mut smartstates = SmartstateProvider::<64>::new();
+        let (mut shift, mut open) = (false, true);
+        let mut text = String::<16>::new();
+        let mut frame = |harness: &mut Harness<Rgb565>| {
+            harness.frame(Interaction::None, |ui| {
+                smartstates.restart_counter();
+                draw_keyboard(
+                    ui,
+                    &Layout::qwertz_with_special(),
+                    Some(&mut smartstates),
+                    true,
+                    true,
+                    &mut shift,
+                    &mut open,
+                    &mut text,
+                );
+            });
+        };
+        frame(&mut harness);
+
+        // with smartstates, an unchanged keyboard (including the backspace key) isn't redrawn
+        harness.display_mut().clear(Rgb565::MAGENTA).unwrap();
+        frame(&mut harness);
+        let display = harness.display();
+        assert!(display
+            .pixels()
+            .iter()
+            .all(|color| *color == Rgb565::MAGENTA));
+    }
+}
@@ -0,0 +1,168 @@
+//! Ready-made message boxes, shown as a [modal](crate::Ui::modal).
+//!
+//! A [MessageBox] shows a message with a row of buttons (OK, OK/Cancel or Yes/No), and returns
+//! the button that was chosen.
+use crate::widgets::{Button, Label};
+use crate::{GuiResult, Ui};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::pixelcolor::PixelColor;
+
+/// A button of a [MessageBox].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MessageBoxButton {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+}
+
+impl MessageBoxButton {
+    /// Returns the label of the button.
+    pub const fn label(&self) -> &'static str {
+        match self {
+            MessageBoxButton::Ok => "OK",
+            MessageBoxButton::Cancel => "Cancel",
+            MessageBoxButton::Yes => "Yes",
+            MessageBoxButton::No => "No",
+        }
+    }
+}
+
+/// A modal message box with a title, a message and a row of buttons.
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::Ui;
+/// # use kolibri_embedded_gui::ui::OverlayState;
+/// # use kolibri_embedded_gui::helpers::message_box::{MessageBox, MessageBoxButton};
+/// # use embedded_graphics::prelude::*;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// let overlay = OverlayState::new();
+/// let mut confirm = true;
+/// // in the drawing loop:
+/// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// ui.set_overlay_state(&overlay);
+/// // ... add widgets ...
+/// let chosen = MessageBox::yes_no("Delete", "Really delete program?")
+///     .show(&mut ui, &mut confirm)
+///     .unwrap_or(None);
+/// if chosen == Some(MessageBoxButton::Yes) {
+///     // delete the program
+/// }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct MessageBox<'a> {
+    title: &'a str,
+    message: &'a str,
+    buttons: &'static [MessageBoxButton],
+}
+
+impl<'a> MessageBox<'a> {
+    /// Creates a message box with an OK button (e.g. for errors).
+    pub const fn ok(title: &'a str, message: &'a str) -> Self {
+        Self {
+            title,
+            message,
+            buttons: &[MessageBoxButton::Ok],
+        }
+    }
+
+    /// Creates a message box with OK and Cancel buttons.
+    pub const fn ok_cancel(title: &'a str, message: &'a str) -> Self {
+        Self {
+            title,
+            message,
+            buttons: &[MessageBoxButton::Ok, MessageBoxButton::Cancel],
+        }
+    }
+
+    /// Creates a message box with Yes and No buttons (e.g. for confirmation prompts).
+    pub const fn yes_no(title: &'a str, message: &'a str) -> Self {
+        Self {
+            title,
+            message,
+            buttons: &[MessageBoxButton::Yes, MessageBoxButton::No],
+        }
+    }
+
+    /// Returns the buttons of the message box.
+    pub fn buttons(&self) -> &'static [MessageBoxButton] {
+        self.buttons
+    }
+
+    /// Shows the message box if `open` is `true` (see [Ui::modal]).
+    ///
+    /// ## Returns
+    ///
+    /// The button that was chosen in this frame, if any. The message box is closed then, and
+    /// `open` is set to `false`.
+    pub fn show<DRAW, COL>(
+        self,
+        ui: &mut Ui<DRAW, COL>,
+        open: &mut bool,
+    ) -> GuiResult<Option<MessageBoxButton>>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        let mut chosen = None;
+        ui.modal(open, self.title, |ui| {
+            ui.add(Label::new(self.message));
+            for button in self.buttons {
+                if ui.add_horizontal(Button::new(button.label())).clicked() {
+                    chosen = Some(*button);
+                    ui.close_modal();
+                }
+            }
+            Ok(())
+        })?;
+        if chosen.is_some() {
+            *open = false;
+        }
+        Ok(chosen)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use crate::testing::{Harness, Script};
+    use crate::Interaction;
+    use embedded_graphics::geometry::{Point, Size};
+
+    #[test]
+    fn test_message_box() {
+        let mut harness = Harness::new(Size::new(160, 96), medsize_rgb565_style());
+        let mut open = true;
+        let mut chosen = None;
+        let mut show = |harness: &mut Harness<_>, interaction| {
+            harness.frame(interaction, |ui| {
+                let message_box = MessageBox::yes_no("Delete", "Sure?");
+                if let Some(button) = message_box.show(ui, &mut open).unwrap() {
+                    chosen = Some(button);
+                }
+            });
+            (chosen, open)
+        };
+        assert_eq!(show(&mut harness, Interaction::None), (None, true));
+
+        // the focus is trapped in the message box: "Yes" is the first focusable widget
+        assert_eq!(show(&mut harness, Interaction::Next), (None, true));
+        assert_eq!(harness.focus().focused(), Some(0));
+        assert_eq!(show(&mut harness, Interaction::Next), (None, true));
+        assert_eq!(
+            show(&mut harness, Interaction::Activate),
+            (Some(MessageBoxButton::No), false)
+        );
+
+        // closed message boxes aren't shown
+        for interaction in Script::new().tap(Point::new(80, 48)) {
+            assert!(!show(&mut harness, interaction).1);
+        }
+    }
+}
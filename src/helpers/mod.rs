@@ -1 +1,3 @@
+pub mod calibration;
 pub mod keyboard;
+pub mod message_box;
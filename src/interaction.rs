@@ -12,6 +12,18 @@ pub enum Interaction {
     /// A hover event (mouse, touch, etc. move while not clicked).
     /// Generally not applicable to touch screens.
     Hover(Point),
+    /// Move the focus to the next focusable widget, or increase the value of the
+    /// widget that is being edited (e.g. rotary encoder turned clockwise, or a "down" button).
+    ///
+    /// See [FocusState](crate::focus::FocusState).
+    Next,
+    /// Move the focus to the previous focusable widget, or decrease the value of the
+    /// widget that is being edited (e.g. rotary encoder turned counter-clockwise, or an "up" button).
+    Previous,
+    /// Activate the focused widget (e.g. click a button, or start / stop editing a slider).
+    Activate,
+    /// Stop editing the focused widget, or remove the focus if it is not being edited.
+    Back,
     /// No interaction
     #[default]
     None,
@@ -20,14 +32,311 @@ pub enum Interaction {
 impl Interaction {
     /// Gets the point associated with the current interaction, if any.
     ///
-    /// This method returns the point associated with the current interaction, such as the click, drag, release, or hover point. If the interaction is [Interaction::None], this method returns [None`.
+    /// This method returns the point associated with the current interaction, such as the click, drag, release, or hover point. If the interaction has no point (e.g. [Interaction::None] or [Interaction::Next]), this method returns [None].
     pub(crate) fn get_point(&self) -> Option<Point> {
         match self {
             Interaction::Click(p) => Some(*p),
             Interaction::Drag(p) => Some(*p),
             Interaction::Release(p) => Some(*p),
             Interaction::Hover(p) => Some(*p),
-            Interaction::None => None,
+            Interaction::Next
+            | Interaction::Previous
+            | Interaction::Activate
+            | Interaction::Back
+            | Interaction::None => None,
+        }
+    }
+}
+
+/// Rotation of a touch panel relative to the display (clockwise), see
+/// [InteractionTransform::rotate].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rotation {
+    #[default]
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+/// Affine transform from raw pointer coordinates (e.g. the ADC values of a resistive touch
+/// controller) to display coordinates, with optional rotation and mirroring.
+///
+/// A point `(x, y)` is mapped to `(a * x + b * y + c, d * x + e * y + f)`. The coefficients are
+/// stored as 16.16 fixed-point numbers, so that no floating point math is needed to transform
+/// points, and they can be stored (e.g. in flash) with [InteractionTransform::coefficients].
+///
+/// Set the transform on the [Ui](crate::Ui) with
+/// [Ui::set_interaction_transform](crate::Ui::set_interaction_transform), so that it is applied to
+/// every interaction before hit-testing. The calibration matrix can be measured with the
+/// [calibration screen](crate::helpers::calibration).
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_graphics::prelude::*;
+/// use kolibri_embedded_gui::interaction::{InteractionTransform, Rotation};
+///
+/// // a touch panel that is mounted upside down on a 320x240 display
+/// let transform = InteractionTransform::identity().rotate(Rotation::Deg180, Size::new(320, 240));
+/// assert_eq!(transform.apply(Point::new(0, 0)), Point::new(319, 239));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InteractionTransform {
+    coefficients: [i32; 6],
+}
+
+impl Default for InteractionTransform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl InteractionTransform {
+    /// Number of fractional bits of the fixed-point coefficients.
+    pub const FRACTION_BITS: u32 = 16;
+    const ONE: i32 = 1 << Self::FRACTION_BITS;
+
+    /// Creates a transform that doesn't change the coordinates.
+    pub const fn identity() -> Self {
+        Self::from_coefficients([Self::ONE, 0, 0, 0, Self::ONE, 0])
+    }
+
+    /// Creates a transform from its fixed-point coefficients `[a, b, c, d, e, f]`, e.g. as stored
+    /// in flash (see [InteractionTransform::coefficients]).
+    pub const fn from_coefficients(coefficients: [i32; 6]) -> Self {
+        Self { coefficients }
+    }
+
+    /// Returns the fixed-point coefficients `[a, b, c, d, e, f]`, with
+    /// [InteractionTransform::FRACTION_BITS] fractional bits.
+    pub const fn coefficients(&self) -> [i32; 6] {
+        self.coefficients
+    }
+
+    /// Computes the transform that maps the raw points onto the given display points as closely
+    /// as possible (least squares), e.g. from the touches of a calibration screen.
+    ///
+    /// At least three points are needed, which must not lie on a line.
+    ///
+    /// ## Returns
+    ///
+    /// The transform, or `None` if there are less than three points, the number of points
+    /// differs, or the raw points lie on a line.
+    pub fn from_points(raw: &[Point], display: &[Point]) -> Option<Self> {
+        if raw.len() < 3 || raw.len() != display.len() {
+            return None;
         }
+
+        // center the raw points, so that the offsets separate from the linear part
+        let n = raw.len() as f64;
+        let mean = |points: &[Point]| {
+            let (x, y) = points
+                .iter()
+                .fold((0.0, 0.0), |(x, y), p| (x + p.x as f64, y + p.y as f64));
+            (x / n, y / n)
+        };
+        let (raw_x, raw_y) = mean(raw);
+        let (display_x, display_y) = mean(display);
+
+        let (mut xx, mut xy, mut yy) = (0.0, 0.0, 0.0);
+        let (mut xu, mut yu, mut xv, mut yv) = (0.0, 0.0, 0.0, 0.0);
+        for (r, d) in raw.iter().zip(display) {
+            let (x, y) = (r.x as f64 - raw_x, r.y as f64 - raw_y);
+            let (u, v) = (d.x as f64 - display_x, d.y as f64 - display_y);
+            xx += x * x;
+            xy += x * y;
+            yy += y * y;
+            xu += x * u;
+            yu += y * u;
+            xv += x * v;
+            yv += y * v;
+        }
+
+        let det = xx * yy - xy * xy;
+        if det.abs() <= f64::EPSILON * xx * yy {
+            return None;
+        }
+        let a = (xu * yy - yu * xy) / det;
+        let b = (yu * xx - xu * xy) / det;
+        let d = (xv * yy - yv * xy) / det;
+        let e = (yv * xx - xv * xy) / det;
+        let c = display_x - a * raw_x - b * raw_y;
+        let f = display_y - d * raw_x - e * raw_y;
+
+        let fixed = |value: f64| {
+            let value = value * Self::ONE as f64;
+            // rounding (`f64::round` isn't available in `no_std`)
+            if value >= 0.0 {
+                (value + 0.5) as i32
+            } else {
+                (value - 0.5) as i32
+            }
+        };
+        Some(Self::from_coefficients([
+            fixed(a),
+            fixed(b),
+            fixed(c),
+            fixed(d),
+            fixed(e),
+            fixed(f),
+        ]))
+    }
+
+    /// Rotates the transformed points clockwise within an area of the given size (the size of the
+    /// coordinate space before the rotation, e.g. the size of the touch panel in pixels).
+    ///
+    /// The offsets saturate at the range of the fixed-point coefficients (about ±32768 pixels).
+    pub fn rotate(self, rotation: Rotation, size: Size) -> Self {
+        let [a, b, c, d, e, f] = self.coefficients;
+        let neg = i32::saturating_neg;
+        let coefficients = match rotation {
+            Rotation::Deg0 => self.coefficients,
+            Rotation::Deg90 => [neg(d), neg(e), Self::flip(f, size.height), a, b, c],
+            Rotation::Deg180 => [
+                neg(a),
+                neg(b),
+                Self::flip(c, size.width),
+                neg(d),
+                neg(e),
+                Self::flip(f, size.height),
+            ],
+            Rotation::Deg270 => [d, e, f, neg(a), neg(b), Self::flip(c, size.width)],
+        };
+        Self::from_coefficients(coefficients)
+    }
+
+    /// Mirrors the transformed points horizontally within an area of the given width.
+    ///
+    /// The offset saturates at the range of the fixed-point coefficients (about ±32768 pixels).
+    pub fn mirror_x(self, width: u32) -> Self {
+        let [a, b, c, d, e, f] = self.coefficients;
+        Self::from_coefficients([
+            a.saturating_neg(),
+            b.saturating_neg(),
+            Self::flip(c, width),
+            d,
+            e,
+            f,
+        ])
+    }
+
+    /// Mirrors the transformed points vertically within an area of the given height.
+    ///
+    /// The offset saturates at the range of the fixed-point coefficients (about ±32768 pixels).
+    pub fn mirror_y(self, height: u32) -> Self {
+        let [a, b, c, d, e, f] = self.coefficients;
+        Self::from_coefficients([
+            a,
+            b,
+            c,
+            d.saturating_neg(),
+            e.saturating_neg(),
+            Self::flip(f, height),
+        ])
+    }
+
+    /// Returns the offset `(size - 1) - offset` that flips a coordinate within the given size.
+    ///
+    /// This is computed in 64 bits, as sizes of 32768 pixels and more overflow the fixed-point
+    /// coefficients, which then saturate.
+    fn flip(offset: i32, size: u32) -> i32 {
+        let flipped = (size as i64 - 1) * Self::ONE as i64 - offset as i64;
+        flipped.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    }
+
+    /// Transforms a point (rounded to the closest pixel).
+    pub fn apply(&self, point: Point) -> Point {
+        let [a, b, c, d, e, f] = self.coefficients.map(|coefficient| coefficient as i64);
+        let (x, y) = (point.x as i64, point.y as i64);
+        let round = 1 << (Self::FRACTION_BITS - 1);
+        Point::new(
+            ((a * x + b * y + c + round) >> Self::FRACTION_BITS) as i32,
+            ((d * x + e * y + f + round) >> Self::FRACTION_BITS) as i32,
+        )
+    }
+
+    /// Transforms the point of an interaction (if it has one).
+    pub fn transform(&self, interaction: Interaction) -> Interaction {
+        match interaction {
+            Interaction::Click(p) => Interaction::Click(self.apply(p)),
+            Interaction::Drag(p) => Interaction::Drag(self.apply(p)),
+            Interaction::Release(p) => Interaction::Release(self.apply(p)),
+            Interaction::Hover(p) => Interaction::Hover(self.apply(p)),
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_and_mirroring() {
+        let size = Size::new(320, 240);
+        let transform = |rotation| InteractionTransform::identity().rotate(rotation, size);
+        let p = Point::new(10, 20);
+        assert_eq!(transform(Rotation::Deg0).apply(p), p);
+        assert_eq!(transform(Rotation::Deg90).apply(p), Point::new(219, 10));
+        assert_eq!(transform(Rotation::Deg180).apply(p), Point::new(309, 219));
+        assert_eq!(transform(Rotation::Deg270).apply(p), Point::new(20, 309));
+
+        let mirrored = InteractionTransform::identity().mirror_x(320).mirror_y(240);
+        assert_eq!(mirrored, transform(Rotation::Deg180));
+        assert_eq!(
+            mirrored.transform(Interaction::Release(p)),
+            Interaction::Release(Point::new(309, 219))
+        );
+        assert_eq!(mirrored.transform(Interaction::Next), Interaction::Next);
+    }
+
+    #[test]
+    fn test_large_sizes() {
+        // the largest size that fits into the coefficients
+        let transform =
+            InteractionTransform::identity().rotate(Rotation::Deg180, Size::new(32768, 32768));
+        assert_eq!(transform.apply(Point::zero()), Point::new(32767, 32767));
+
+        // larger sizes saturate instead of overflowing
+        let transform = InteractionTransform::identity()
+            .rotate(Rotation::Deg90, Size::new(u32::MAX, 40000))
+            .mirror_x(u32::MAX)
+            .mirror_y(40000);
+        assert_eq!(transform.coefficients()[2], i32::MAX);
+        let mirrored = InteractionTransform::identity().mirror_x(32769);
+        assert_eq!(mirrored.apply(Point::zero()), Point::new(32768, 0));
+    }
+
+    #[test]
+    fn test_from_points() {
+        // raw ADC values of a panel that is rotated by 90 degrees
+        let raw = |p: Point| Point::new(3900 - p.y * 15, 200 + p.x * 11);
+        let display = [
+            Point::new(32, 24),
+            Point::new(288, 24),
+            Point::new(288, 216),
+            Point::new(32, 216),
+            Point::new(160, 120),
+        ];
+        let raw_points = display.map(raw);
+
+        let transform = InteractionTransform::from_points(&raw_points[..3], &display[..3]).unwrap();
+        for point in [Point::new(0, 0), Point::new(319, 239), Point::new(100, 50)] {
+            assert_eq!(transform.apply(raw(point)), point);
+        }
+        let transform = InteractionTransform::from_points(&raw_points, &display).unwrap();
+        assert_eq!(transform.apply(raw(Point::new(77, 99))), Point::new(77, 99));
+
+        // on a line
+        let line = [Point::new(0, 0), Point::new(10, 10), Point::new(20, 20)];
+        assert_eq!(
+            InteractionTransform::from_points(&line, &display[..3]),
+            None
+        );
+        assert_eq!(
+            InteractionTransform::from_points(&raw_points[..2], &display[..2]),
+            None
+        );
     }
 }
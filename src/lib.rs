@@ -6,8 +6,13 @@
 // mod icon;
 // pub mod icon;
 
+pub mod focus;
+pub mod font;
+pub mod gesture;
 pub mod smartstate;
 pub mod style;
+#[cfg(feature = "testing")]
+pub mod testing;
 // mod temp;
 pub mod framebuf;
 pub mod helpers;
@@ -1,3 +1,4 @@
+use crate::gesture::{Gesture, SwipeDirection};
 use crate::interaction::Interaction;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::Rectangle;
@@ -17,6 +18,18 @@ pub enum GuiError {
 
     /// The requested operation would cause the bounds to be different from the expected size
     BoundsError,
+
+    /// The [Ui](crate::Ui) is in a measuring pass (see [Ui::with_align](crate::Ui::with_align)).
+    /// The widget was placed, but must not be drawn or interacted with.
+    Measuring,
+
+    /// A container for smartstates (e.g. a [SmartstateMap](crate::smartstate::SmartstateMap))
+    /// is full.
+    NoSmartstateLeft,
+
+    /// The text didn't fit into the buffer of the widget (e.g. a [FmtLabel](crate::widgets::FmtLabel)),
+    /// so only its beginning was drawn.
+    Truncated,
 }
 
 impl GuiError {
@@ -37,6 +50,14 @@ impl InternalResponse {
         Self { area, interaction }
     }
 
+    /// Whether the pointer was released inside the area (i.e. the widget was clicked).
+    ///
+    /// With a [PointerCapture](crate::ui::PointerCapture), the pressed widget also receives the
+    /// release of a pointer that was dragged off of it, which doesn't count as a click.
+    pub fn released(&self) -> bool {
+        matches!(self.interaction, Interaction::Release(point) if self.area.contains(point))
+    }
+
     pub fn empty() -> Self {
         Self {
             area: Rectangle::new(Point::zero(), Size::zero()),
@@ -68,6 +89,14 @@ pub struct Response {
     /// Always `false` for something like a [`Button`](crate::button::Button).
     pub changed: bool,
 
+    /// Which part of the widget changed, for widgets with multiple parts (e.g. the index of
+    /// the selected option of a [`RadioGroup`](crate::widgets::RadioGroup), or a bitmask of the
+    /// changed knobs of a [`RangeSlider`](crate::widgets::slider::RangeSlider)).
+    pub changed_part: Option<usize>,
+
+    /// Gesture that started on the widget in this frame (see [crate::gesture])
+    pub gesture: Option<Gesture>,
+
     /// Whether the widget had an error while drawing
     pub error: Option<GuiError>,
 }
@@ -80,6 +109,8 @@ impl Response {
             click: false,
             redraw: true,
             changed: false,
+            changed_part: None,
+            gesture: None,
             down: false,
             error: None,
         }
@@ -104,6 +135,18 @@ impl Response {
         self
     }
 
+    /// Marks the given part of the widget as changed (which also sets `changed`).
+    pub fn set_changed_part(mut self, part: usize) -> Self {
+        self.changed = true;
+        self.changed_part = Some(part);
+        self
+    }
+
+    pub fn set_gesture(mut self, gesture: Option<Gesture>) -> Self {
+        self.gesture = gesture;
+        self
+    }
+
     pub fn set_error(mut self, error: GuiError) -> Self {
         self.error = Some(error);
         self
@@ -136,6 +179,35 @@ impl Response {
         self.changed
     }
 
+    /// Check which part of the widget changed, for widgets with multiple parts
+    /// (e.g. the knobs of a [`RangeSlider`](crate::widgets::slider::RangeSlider)).
+    pub fn changed_part(&self) -> Option<usize> {
+        self.changed_part
+    }
+
+    /// Check which gesture started on the widget in this frame, if any (see [crate::gesture])
+    pub fn gesture(&self) -> Option<Gesture> {
+        self.gesture
+    }
+
+    /// Check whether the widget was long-pressed (reported once, while the pointer is still down)
+    pub fn long_pressed(&self) -> bool {
+        matches!(self.gesture, Some(Gesture::LongPress(_)))
+    }
+
+    /// Check whether the widget was double-clicked (double-tapped)
+    pub fn double_clicked(&self) -> bool {
+        matches!(self.gesture, Some(Gesture::DoubleTap(_)))
+    }
+
+    /// Check whether a swipe started on the widget, and in which direction
+    pub fn swiped(&self) -> Option<SwipeDirection> {
+        match self.gesture {
+            Some(Gesture::Swipe(direction, _)) => Some(direction),
+            _ => None,
+        }
+    }
+
     /// Check whether the widget had an error while drawing
     /// (e.g. the underlying draw target returned an error), no space was left, ...
     pub fn error(&self) -> Option<GuiError> {
@@ -1,3 +1,8 @@
+use crate::widgets::Hasher;
+use crate::{GuiError, GuiResult};
+use core::hash::Hash;
+use heapless::FnvIndexMap;
+
 /// A container for an optional mutable reference to a value.
 ///
 /// This container is primarily used with [`Smartstate`] to manage widget state and redraw behavior.
@@ -53,6 +58,14 @@ impl<'a, T> Container<'a, T> {
             (modify)(*inner);
         }
     }
+
+    /// Returns the contained value as a slice, which is empty if there is no value.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match self.optional_something.as_deref_mut() {
+            Some(inner) => core::slice::from_mut(inner),
+            None => &mut [],
+        }
+    }
 }
 
 impl<T: Clone> Container<'_, T> {
@@ -197,6 +210,9 @@ impl<T: PartialEq> Container<'_, T> {
 pub struct Smartstate(u32, bool);
 
 impl Smartstate {
+    /// Bit of the state ID that is toggled for disabled widgets
+    const DISABLED: u32 = 1 << 31;
+
     /// Creates an empty state that will trigger a redraw.
     pub fn empty() -> Self {
         Self(0, false)
@@ -218,6 +234,14 @@ impl Smartstate {
         !self.1
     }
 
+    /// Returns the current state ID, or `None` if this is an empty/invalid state.
+    ///
+    /// This is useful for widgets that only redraw the parts that changed compared to
+    /// the previous state (e.g. the [ProgressBar](crate::widgets::ProgressBar)).
+    pub fn value(&self) -> Option<u32> {
+        self.1.then_some(self.0)
+    }
+
     /// Returns true if this matches the given state ID and is valid.
     pub fn is_state(&self, state: u32) -> bool {
         self.1 && self.0 == state
@@ -227,6 +251,15 @@ impl Smartstate {
     pub fn force_redraw(&mut self) {
         self.1 = false;
     }
+
+    /// Toggles the disabled bit of the state ID.
+    ///
+    /// Disabled widgets (see [Ui::enabled](crate::Ui::enabled)) toggle it before and after
+    /// drawing, so that the stored state of a disabled widget never matches the state it computes
+    /// when enabled, and enabling or disabling the widget triggers a redraw.
+    pub fn toggle_disabled(&mut self) {
+        self.0 ^= Self::DISABLED;
+    }
 }
 
 impl PartialEq for Smartstate {
@@ -313,6 +346,21 @@ impl<const N: usize> SmartstateProvider<N> {
         state
     }
 
+    /// Gets the next `n` smartstates (e.g. one per option of a
+    /// [RadioGroup](crate::widgets::RadioGroup)) and advances the position counter past them.
+    ///
+    /// # Panics
+    /// Panics if fewer than `n` smartstates are left.
+    #[inline(always)]
+    pub fn nxt_n(&mut self, n: usize) -> &mut [Smartstate] {
+        let states = self
+            .states
+            .get_mut(self.pos..self.pos + n)
+            .expect("ERROR: Smartstate buffer too small! Increase N in SmartstateProvider<N>.");
+        self.pos += n;
+        states
+    }
+
     /// Gets the current smartstate (at pos-1).
     ///
     /// # Panics
@@ -427,3 +475,347 @@ impl<const N: usize> Default for SmartstateProvider<N> {
         Self::new()
     }
 }
+
+/// Identifier of a widget, used as the key of a [SmartstateMap].
+///
+/// A [WidgetId] is the hash of a user-supplied value (e.g. a `&str` or a tuple with a loop index),
+/// so it stays the same no matter which widgets are drawn before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WidgetId(u64);
+
+impl WidgetId {
+    /// Creates an id by hashing the given value with the given [Hasher].
+    pub fn new<T: Hash + ?Sized>(value: &T, hasher: &Hasher) -> Self {
+        Self(hasher.hash(value))
+    }
+
+    /// Creates an id from a raw value (e.g. a hash that was computed elsewhere).
+    pub const fn from_raw(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw value of the id.
+    pub const fn raw(&self) -> u64 {
+        self.0
+    }
+
+    /// Derives a new id from this one and the given value.
+    ///
+    /// This is useful for widgets in loops or in reusable components, e.g.
+    /// `parent.with(&i, &hasher)`.
+    pub fn with<T: Hash + ?Sized>(self, value: &T, hasher: &Hasher) -> Self {
+        Self(hasher.hash(&(self.0, value)))
+    }
+}
+
+/// Position of the smartstates of one key in a [SmartstateMap]
+#[derive(Clone, Copy, Debug)]
+struct Slot {
+    /// Index of the first smartstate
+    start: usize,
+    /// Number of smartstates
+    len: usize,
+    /// Whether the smartstates were used since the last [SmartstateMap::next_frame]
+    seen: bool,
+}
+
+/// Manages smartstates for multiple widgets, keyed by a [WidgetId].
+///
+/// Unlike the [SmartstateProvider], which hands out smartstates by call order, the
+/// [SmartstateMap] keeps each widget's smartstate regardless of which widgets are drawn before it.
+/// This means that conditionally drawn widgets don't require forcing redraws of all
+/// following widgets.
+///
+/// The map holds up to `N` smartstates without allocating. `N` must be a power of two.
+/// Smartstates of widgets that are no longer drawn are evicted by [SmartstateMap::next_frame].
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::widgets::{Button, Label};
+/// # use kolibri_embedded_gui::GuiResult;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// # let show_warning = true;
+/// use kolibri_embedded_gui::smartstate::SmartstateMap;
+///
+/// // create the map outside of the drawing loop
+/// let mut smartstates = SmartstateMap::<32>::new();
+///
+/// // in the drawing loop:
+/// # let _: GuiResult<()> = (|| {
+/// smartstates.next_frame();
+/// if show_warning {
+///     ui.add(Label::new("Warning!").smartstate(smartstates.get("warning")?));
+/// }
+/// // this button keeps its smartstate, whether the warning is shown or not
+/// ui.add(Button::new("OK").smartstate(smartstates.get("ok")?));
+///
+/// for i in 0..3 {
+///     ui.add(Button::new("Item").smartstate(smartstates.get(&("item", i))?));
+/// }
+/// # Ok(())
+/// # })();
+/// ```
+pub struct SmartstateMap<const N: usize = 16> {
+    /// Slots in the order of their position in `states`
+    slots: FnvIndexMap<WidgetId, Slot, N>,
+    states: [Smartstate; N],
+    /// Number of smartstates in use, from the start of `states`
+    used: usize,
+    hasher: Hasher,
+}
+
+impl<const N: usize> SmartstateMap<N> {
+    /// Creates a new, empty map.
+    pub fn new() -> Self {
+        Self {
+            slots: FnvIndexMap::new(),
+            states: [Smartstate::empty(); N],
+            used: 0,
+            hasher: Hasher::new(),
+        }
+    }
+
+    /// Returns the [WidgetId] for the given key, as used by this map.
+    pub fn id<K: Hash + ?Sized>(&self, key: &K) -> WidgetId {
+        WidgetId::new(key, &self.hasher)
+    }
+
+    /// Gets the smartstate for the given key, inserting an empty one if there is none yet.
+    ///
+    /// ## Returns
+    ///
+    /// The smartstate, or [GuiError::NoSmartstateLeft] if the map is full.
+    pub fn get<K: Hash + ?Sized>(&mut self, key: &K) -> GuiResult<&mut Smartstate> {
+        self.get_id(self.id(key))
+    }
+
+    /// Gets the smartstate for the given [WidgetId], inserting an empty one if there is none yet.
+    ///
+    /// ## Returns
+    ///
+    /// The smartstate, or [GuiError::NoSmartstateLeft] if the map is full.
+    pub fn get_id(&mut self, id: WidgetId) -> GuiResult<&mut Smartstate> {
+        let start = self.slot(id, 1)?;
+        Ok(&mut self.states[start])
+    }
+
+    /// Gets `n` smartstates for the given key (e.g. one per option of a
+    /// [RadioGroup](crate::widgets::RadioGroup)), inserting empty ones if there are none yet.
+    ///
+    /// If the key was used with a different `n` before, its smartstates are replaced by empty ones.
+    ///
+    /// ## Returns
+    ///
+    /// The smartstates, or [GuiError::NoSmartstateLeft] if the map is full.
+    pub fn get_n<K: Hash + ?Sized>(&mut self, key: &K, n: usize) -> GuiResult<&mut [Smartstate]> {
+        let start = self.slot(self.id(key), n)?;
+        Ok(&mut self.states[start..start + n])
+    }
+
+    /// Gets the smartstates for several keys at once, e.g. to hold them in multiple widgets.
+    ///
+    /// ## Returns
+    ///
+    /// The smartstates in the order of the keys, or [GuiError::NoSmartstateLeft] if the map is
+    /// full. Keys must be distinct, otherwise [GuiError::BoundsError] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kolibri_embedded_gui::smartstate::SmartstateMap;
+    /// let mut smartstates = SmartstateMap::<8>::new();
+    /// let [ok, cancel] = smartstates.get_many(["ok", "cancel"]).unwrap();
+    /// ok.set_state(1);
+    /// cancel.set_state(2);
+    /// ```
+    pub fn get_many<K: Hash, const M: usize>(
+        &mut self,
+        keys: [K; M],
+    ) -> GuiResult<[&mut Smartstate; M]> {
+        let mut indices = [0; M];
+        for (index, key) in indices.iter_mut().zip(keys.iter()) {
+            *index = self.slot(self.id(key), 1)?;
+        }
+        self.states
+            .get_disjoint_mut(indices)
+            .map_err(|_| GuiError::BoundsError)
+    }
+
+    /// Returns the index of the first of the `len` smartstates for the given id, inserting empty
+    /// ones if there are none yet (or their number changed), and marks them as seen.
+    fn slot(&mut self, id: WidgetId, len: usize) -> GuiResult<usize> {
+        if let Some(slot) = self.slots.get_mut(&id) {
+            if slot.len == len {
+                slot.seen = true;
+                return Ok(slot.start);
+            }
+            // the slots must stay in the order of their position, so the key is moved to the end
+            self.remove_id(id);
+        }
+
+        let start = self.used;
+        if start + len > N {
+            return Err(GuiError::NoSmartstateLeft);
+        }
+        let slot = Slot {
+            start,
+            len,
+            seen: true,
+        };
+        self.slots
+            .insert(id, slot)
+            .map_err(|_| GuiError::NoSmartstateLeft)?;
+        self.states[start..start + len].fill(Smartstate::empty());
+        self.used += len;
+        Ok(start)
+    }
+
+    /// Evicts the smartstates of all keys that weren't used since the last call, freeing their
+    /// space for other keys.
+    ///
+    /// Call this once per frame (e.g. at the start of the drawing loop), so that the map doesn't
+    /// fill up with smartstates of widgets that aren't drawn anymore.
+    pub fn next_frame(&mut self) {
+        self.slots.retain(|_, slot| core::mem::take(&mut slot.seen));
+        self.compact();
+    }
+
+    /// Moves all smartstates to the start of the storage, after slots were removed.
+    fn compact(&mut self) {
+        let mut used = 0;
+        for slot in self.slots.values_mut() {
+            self.states
+                .copy_within(slot.start..slot.start + slot.len, used);
+            slot.start = used;
+            used += slot.len;
+        }
+        self.used = used;
+    }
+
+    /// Removes the smartstates for the given key, e.g. for a widget that won't be drawn again.
+    ///
+    /// ## Returns
+    ///
+    /// The first smartstate of the key, if there was one.
+    pub fn remove<K: Hash + ?Sized>(&mut self, key: &K) -> Option<Smartstate> {
+        self.remove_id(self.id(key))
+    }
+
+    fn remove_id(&mut self, id: WidgetId) -> Option<Smartstate> {
+        let state = self.slots.get(&id).map(|slot| self.states[slot.start])?;
+        // unlike `remove`, `retain` keeps the order of the slots
+        self.slots.retain(|key, _| *key != id);
+        self.compact();
+        Some(state)
+    }
+
+    /// Removes all smartstates.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.used = 0;
+    }
+
+    /// Returns the number of keys in the map.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns `true` if the map contains no smartstates.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Forces a redraw of the widget with the given key, if it has smartstates.
+    pub fn force_redraw<K: Hash + ?Sized>(&mut self, key: &K) {
+        if let Some(slot) = self.slots.get(&self.id(key)) {
+            for state in &mut self.states[slot.start..slot.start + slot.len] {
+                state.force_redraw();
+            }
+        }
+    }
+
+    /// Forces a redraw of all smartstates.
+    pub fn force_redraw_all(&mut self) {
+        for state in &mut self.states[..self.used] {
+            state.force_redraw();
+        }
+    }
+}
+
+impl<const N: usize> Default for SmartstateMap<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smartstate_map_keeps_state() {
+        let mut map = SmartstateMap::<4>::new();
+        map.get("a").unwrap().set_state(1);
+        map.get(&("b", 2)).unwrap().set_state(2);
+
+        // lookups don't depend on order
+        assert!(map.get(&("b", 2)).unwrap().is_state(2));
+        assert!(map.get("a").unwrap().is_state(1));
+        assert!(map.get("c").unwrap().is_empty());
+        assert_eq!(map.len(), 3);
+
+        map.force_redraw("a");
+        assert!(map.get("a").unwrap().is_empty());
+        assert!(map.get(&("b", 2)).unwrap().is_state(2));
+
+        assert!(map.remove("c").is_some());
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_smartstate_map_full() {
+        let mut map = SmartstateMap::<2>::new();
+        assert!(map.get(&1).is_ok());
+        assert!(map.get(&2).is_ok());
+        assert_eq!(map.get(&3).err(), Some(GuiError::NoSmartstateLeft));
+        assert_eq!(map.get_n(&4, 3).err(), Some(GuiError::NoSmartstateLeft));
+    }
+
+    #[test]
+    fn test_smartstate_map_evicts_unused() {
+        let mut map = SmartstateMap::<4>::new();
+        map.get("a").unwrap().set_state(1);
+        map.get_n("group", 2).unwrap()[1].set_state(2);
+        map.get("b").unwrap().set_state(3);
+
+        // "a" isn't drawn in the next frame
+        map.next_frame();
+        map.get_n("group", 2).unwrap();
+        map.get("b").unwrap();
+        map.next_frame();
+        assert_eq!(map.len(), 2);
+
+        // the remaining smartstates were moved, and their space can be reused
+        assert!(map.get_n("group", 2).unwrap()[1].is_state(2));
+        assert!(map.get("b").unwrap().is_state(3));
+        assert!(map.get("c").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_smartstate_map_get_many() {
+        let mut map = SmartstateMap::<4>::new();
+        let [a, b] = map.get_many(["a", "b"]).unwrap();
+        a.set_state(1);
+        b.set_state(2);
+        assert!(map.get("a").unwrap().is_state(1));
+        assert!(map.get("b").unwrap().is_state(2));
+        assert!(map.get_many(["a", "a"]).is_err());
+    }
+}
@@ -4,7 +4,9 @@
 //! appearance of the UI, including colors, spacing, fonts, and other visual aspects of the
 //! interface. This allows compatibility and abstraction over [embedded_graphics::pixelcolor]
 //! color types, making it easy to switch between color depths or display technologies.
-//! Several predefined themes are included for [Rgb565] displays (e.g. ILI9341).
+//! Several predefined themes are included for [Rgb565] displays (e.g. ILI9341), as well as for
+//! monochrome ([BinaryColor]) and grayscale ([Gray4], [Gray8]) displays. Styles for other color
+//! types are derived from a small [Palette] with a [StyleBuilder].
 //!
 //! # Examples
 //!
@@ -49,9 +51,14 @@
 //! // Later...
 //! *ui.style_mut() = medsize_light_rgb565_style(); // Switch to light theme
 //! ```
+//!
+//! To style only a part of the UI, use [Ui::with_style](crate::Ui::with_style), which restores the
+//! previous style afterwards. Single widgets can override parts of the style with a
+//! [StyleOverride] (see [Ui::add_styled](crate::Ui::add_styled)).
 
-use embedded_graphics::mono_font::{self, MonoFont};
-use embedded_graphics::pixelcolor::{PixelColor, Rgb565};
+use crate::font::{blend_gray, blend_rgb, BlendFn, Font, FontText};
+use embedded_graphics::mono_font;
+use embedded_graphics::pixelcolor::{BinaryColor, Gray4, Gray8, PixelColor, Rgb565};
 use embedded_graphics::prelude::*;
 
 /// Controls spacing between UI elements.
@@ -82,10 +89,15 @@ pub fn medsize_rgb565_debug_style() -> Style<Rgb565> {
         secondary_color: Rgb565::YELLOW,
         icon_color: Rgb565::WHITE,
         text_color: Rgb565::WHITE,
+        disabled_item_background_color: Rgb565::CSS_DIM_GRAY,
+        disabled_primary_color: Rgb565::CSS_DARK_CYAN,
+        disabled_text_color: Rgb565::CSS_LIGHT_GRAY,
+        disabled_border_color: Rgb565::CSS_LIGHT_GRAY,
         default_widget_height: 16,
         border_width: 1,
         highlight_border_width: 1,
-        default_font: mono_font::iso_8859_10::FONT_9X15,
+        default_font: &mono_font::iso_8859_10::FONT_9X15,
+        text_blend: Some(blend_rgb),
         spacing: Spacing {
             item_spacing: Size::new(8, 4),
             button_padding: Size::new(2, 2),
@@ -109,10 +121,15 @@ pub fn medsize_rgb565_style() -> Style<Rgb565> {
         secondary_color: Rgb565::YELLOW,
         icon_color: Rgb565::WHITE,
         text_color: Rgb565::WHITE,
+        disabled_item_background_color: Rgb565::new(0x3, 0x6, 0x3),
+        disabled_primary_color: Rgb565::CSS_DIM_GRAY,
+        disabled_text_color: Rgb565::CSS_DARK_GRAY,
+        disabled_border_color: Rgb565::CSS_DARK_GRAY,
         default_widget_height: 16,
         border_width: 0,
         highlight_border_width: 1,
-        default_font: mono_font::iso_8859_10::FONT_9X15,
+        default_font: &mono_font::iso_8859_10::FONT_9X15,
+        text_blend: Some(blend_rgb),
         spacing: Spacing {
             item_spacing: Size::new(8, 4),
             button_padding: Size::new(5, 5),
@@ -136,10 +153,15 @@ pub fn medsize_light_rgb565_style() -> Style<Rgb565> {
         secondary_color: Rgb565::YELLOW,
         icon_color: Rgb565::CSS_BLACK,
         text_color: Rgb565::CSS_BLACK,
+        disabled_item_background_color: Rgb565::CSS_WHITE_SMOKE,
+        disabled_primary_color: Rgb565::CSS_LIGHT_GRAY,
+        disabled_text_color: Rgb565::CSS_GRAY,
+        disabled_border_color: Rgb565::CSS_GRAY,
         default_widget_height: 16,
         border_width: 0,
         highlight_border_width: 1,
-        default_font: mono_font::iso_8859_10::FONT_9X15,
+        default_font: &mono_font::iso_8859_10::FONT_9X15,
+        text_blend: Some(blend_rgb),
         spacing: Spacing {
             item_spacing: Size::new(8, 4),
             button_padding: Size::new(5, 5),
@@ -163,10 +185,15 @@ pub fn medsize_sakura_rgb565_style() -> Style<Rgb565> {
         secondary_color: Rgb565::YELLOW,
         icon_color: Rgb565::CSS_BLACK,
         text_color: Rgb565::CSS_BLACK,
+        disabled_item_background_color: Rgb565::CSS_LAVENDER_BLUSH,
+        disabled_primary_color: Rgb565::CSS_THISTLE,
+        disabled_text_color: Rgb565::CSS_ROSY_BROWN,
+        disabled_border_color: Rgb565::CSS_ROSY_BROWN,
         default_widget_height: 16,
         border_width: 0,
         highlight_border_width: 1,
-        default_font: mono_font::ascii::FONT_9X15,
+        default_font: &mono_font::ascii::FONT_9X15,
+        text_blend: Some(blend_rgb),
         spacing: Spacing {
             item_spacing: Size::new(8, 4),
             button_padding: Size::new(5, 5),
@@ -190,10 +217,15 @@ pub fn medsize_blue_rgb565_style() -> Style<Rgb565> {
         secondary_color: Rgb565::YELLOW,
         icon_color: Rgb565::CSS_WHITE,
         text_color: Rgb565::CSS_WHITE,
+        disabled_item_background_color: Rgb565::CSS_DARK_SLATE_BLUE,
+        disabled_primary_color: Rgb565::CSS_SLATE_GRAY,
+        disabled_text_color: Rgb565::CSS_LIGHT_STEEL_BLUE,
+        disabled_border_color: Rgb565::CSS_LIGHT_STEEL_BLUE,
         default_widget_height: 16,
         border_width: 0,
         highlight_border_width: 1,
-        default_font: mono_font::iso_8859_10::FONT_9X15,
+        default_font: &mono_font::iso_8859_10::FONT_9X15,
+        text_blend: Some(blend_rgb),
         spacing: Spacing {
             item_spacing: Size::new(8, 4),
             button_padding: Size::new(5, 5),
@@ -217,10 +249,15 @@ pub fn medsize_crt_rgb565_style() -> Style<Rgb565> {
         secondary_color: Rgb565::YELLOW,
         icon_color: Rgb565::CSS_GREEN,
         text_color: Rgb565::CSS_GREEN,
+        disabled_item_background_color: Rgb565::CSS_BLACK,
+        disabled_primary_color: Rgb565::CSS_DARK_GREEN,
+        disabled_text_color: Rgb565::CSS_SEA_GREEN,
+        disabled_border_color: Rgb565::CSS_SEA_GREEN,
         default_widget_height: 16,
         border_width: 1,
         highlight_border_width: 3,
-        default_font: mono_font::iso_8859_10::FONT_9X15,
+        default_font: &mono_font::iso_8859_10::FONT_9X15,
+        text_blend: Some(blend_rgb),
         spacing: Spacing {
             item_spacing: Size::new(8, 4),
             button_padding: Size::new(5, 5),
@@ -244,10 +281,15 @@ pub fn medsize_retro_rgb565_style() -> Style<Rgb565> {
         secondary_color: Rgb565::YELLOW,
         icon_color: Rgb565::CSS_BLACK,
         text_color: Rgb565::CSS_BLACK,
+        disabled_item_background_color: Rgb565::CSS_WHITE,
+        disabled_primary_color: Rgb565::CSS_LIGHT_GRAY,
+        disabled_text_color: Rgb565::CSS_GRAY,
+        disabled_border_color: Rgb565::CSS_GRAY,
         default_widget_height: 16,
         border_width: 1,
         highlight_border_width: 1,
-        default_font: mono_font::ascii::FONT_9X15,
+        default_font: &mono_font::ascii::FONT_9X15,
+        text_blend: Some(blend_rgb),
         spacing: Spacing {
             item_spacing: Size::new(8, 4),
             button_padding: Size::new(5, 5),
@@ -257,6 +299,68 @@ pub fn medsize_retro_rgb565_style() -> Style<Rgb565> {
     }
 }
 
+/// Monochrome theme for [BinaryColor] displays (e.g. SSD1306 OLEDs).
+///
+/// Features a dark background with lit borders and text. Like in the
+/// [retro theme](medsize_retro_rgb565_style), filled accents (e.g. pressed buttons) cover their
+/// text, and disabled widgets are drawn without borders.
+pub fn medsize_binary_style() -> Style<BinaryColor> {
+    StyleBuilder::new(Palette {
+        background: BinaryColor::Off,
+        foreground: BinaryColor::On,
+        accent: BinaryColor::On,
+    })
+    .font(&mono_font::ascii::FONT_6X10)
+    .default_widget_height(10)
+    .button_padding(Size::new(3, 2))
+    .build()
+}
+
+/// Light grayscale theme for [Gray4] displays (e.g. e-paper).
+///
+/// Features a white background with black text and dark gray accents.
+pub fn medsize_gray4_style() -> Style<Gray4> {
+    StyleBuilder::blended(
+        Palette {
+            background: Gray4::WHITE,
+            foreground: Gray4::BLACK,
+            accent: Gray4::new(0x5),
+        },
+        blend_gray,
+    )
+    .build()
+}
+
+/// Dark grayscale theme for [Gray4] displays (e.g. SSD1322 OLEDs).
+///
+/// Features a black background with white text and light gray accents.
+pub fn medsize_dark_gray4_style() -> Style<Gray4> {
+    StyleBuilder::blended(
+        Palette {
+            background: Gray4::BLACK,
+            foreground: Gray4::WHITE,
+            accent: Gray4::new(0xa),
+        },
+        blend_gray,
+    )
+    .build()
+}
+
+/// Dark grayscale theme for [Gray8] displays.
+///
+/// Features a dark gray background with white text and light gray accents.
+pub fn medsize_gray8_style() -> Style<Gray8> {
+    StyleBuilder::blended(
+        Palette {
+            background: Gray8::new(0x20),
+            foreground: Gray8::WHITE,
+            accent: Gray8::new(0xb0),
+        },
+        blend_gray,
+    )
+    .build()
+}
+
 /// Defines the visual appearance of a Kolibri UI.
 ///
 /// The [Style] struct controls all visual aspects of the UI, including colors,
@@ -282,7 +386,8 @@ pub fn medsize_retro_rgb565_style() -> Style<Rgb565> {
 ///         default_padding: Size::new(2, 2),
 ///         window_border_padding: Size::new(3, 3),
 ///     },
-///     default_font: mono_font::ascii::FONT_6X13,
+///     default_font: &mono_font::ascii::FONT_6X13,
+///     text_blend: None,
 ///     border_color: Rgb565::BLACK,
 ///     border_width: 1,
 ///     default_widget_height: 16,
@@ -292,6 +397,10 @@ pub fn medsize_retro_rgb565_style() -> Style<Rgb565> {
 ///     highlight_border_width: 2,
 ///     highlight_item_background_color: Rgb565::BLUE,
 ///     item_background_color: Rgb565::BLACK,
+///     disabled_item_background_color: Rgb565::BLACK,
+///     disabled_primary_color: Rgb565::CSS_DIM_GRAY,
+///     disabled_text_color: Rgb565::CSS_GRAY,
+///     disabled_border_color: Rgb565::CSS_GRAY,
 /// };
 /// ```
 #[derive(Debug, Clone, Copy)]
@@ -311,7 +420,11 @@ pub struct Style<COL: PixelColor> {
     /// Width of borders around widgets
     pub border_width: u32,
     /// Default font used for text rendering
-    pub default_font: MonoFont<'static>,
+    pub default_font: &'static dyn Font,
+    /// Blends anti-aliased text against the background (see [crate::font])
+    ///
+    /// Without a blend function, text pixels are only drawn if they are covered by at least 50%.
+    pub text_blend: Option<BlendFn<COL>>,
     /// Spacing configuration for UI elements
     pub spacing: Spacing,
     /// Background color for items like buttons
@@ -324,4 +437,536 @@ pub struct Style<COL: PixelColor> {
     pub highlight_border_width: u32,
     /// Color used for text
     pub text_color: COL,
+    /// Background color for disabled items (see [Style::disabled])
+    pub disabled_item_background_color: COL,
+    /// Accent color for disabled items, e.g. the fill of a checked checkbox
+    pub disabled_primary_color: COL,
+    /// Color used for text and icons of disabled items
+    pub disabled_text_color: COL,
+    /// Color used for borders of disabled items
+    pub disabled_border_color: COL,
+}
+
+impl<COL: PixelColor> Style<COL> {
+    /// Creates a line of text in the default font and text color at `position` (top left),
+    /// anti-aliased against the `background` color.
+    pub fn text<'t>(&self, text: &'t str, position: Point, background: COL) -> FontText<'t, COL> {
+        FontText::new(text, position, self.default_font, self.text_color)
+            .blend(background, self.text_blend)
+    }
+
+    /// Returns the style in which disabled widgets are drawn (see [Ui::enabled](crate::Ui::enabled)).
+    ///
+    /// Backgrounds, accents, text, icons and borders are drawn in the disabled colors, and
+    /// highlighted (e.g. hovered) items look like normal ones.
+    pub fn disabled(&self) -> Style<COL> {
+        Style {
+            item_background_color: self.disabled_item_background_color,
+            highlight_item_background_color: self.disabled_item_background_color,
+            primary_color: self.disabled_primary_color,
+            secondary_color: self.disabled_primary_color,
+            border_color: self.disabled_border_color,
+            highlight_border_color: self.disabled_border_color,
+            highlight_border_width: self.border_width,
+            icon_color: self.disabled_text_color,
+            text_color: self.disabled_text_color,
+            ..*self
+        }
+    }
+}
+
+/// The few colors from which a [StyleBuilder] derives all colors of a [Style].
+#[derive(Debug, Clone, Copy)]
+pub struct Palette<COL: PixelColor> {
+    /// Background color of the UI
+    pub background: COL,
+    /// Color of text, icons and borders
+    pub foreground: COL,
+    /// Accent color of interactive elements (e.g. checked checkboxes or slider knobs)
+    pub accent: COL,
+}
+
+/// Builds a [Style] for any color type, starting from colors derived from a [Palette] and
+/// medium-sized defaults for fonts, spacing and borders.
+///
+/// Each part of the style can then be overridden with the builder methods.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::pixelcolor::Rgb888;
+/// use embedded_graphics::prelude::*;
+/// use kolibri_embedded_gui::font::blend_rgb;
+/// use kolibri_embedded_gui::style::{Palette, StyleBuilder};
+///
+/// let style = StyleBuilder::blended(
+///     Palette {
+///         background: Rgb888::new(0x10, 0x18, 0x20),
+///         foreground: Rgb888::WHITE,
+///         accent: Rgb888::new(0x00, 0xa0, 0xff),
+///     },
+///     blend_rgb,
+/// )
+/// .border_width(1)
+/// .build();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StyleBuilder<COL: PixelColor> {
+    style: Style<COL>,
+}
+
+impl<COL: PixelColor> StyleBuilder<COL> {
+    /// Starts a high-contrast style that only uses the colors of the palette (e.g. for
+    /// [BinaryColor] displays).
+    ///
+    /// Items are drawn with borders, and highlighted with wider borders in the accent color.
+    /// Disabled items are drawn without borders.
+    pub fn new(palette: Palette<COL>) -> Self {
+        let Palette {
+            background,
+            foreground,
+            accent,
+        } = palette;
+        Self::with_colors(Style {
+            background_color: background,
+            item_background_color: background,
+            highlight_item_background_color: background,
+            border_color: foreground,
+            highlight_border_color: accent,
+            primary_color: accent,
+            secondary_color: accent,
+            icon_color: foreground,
+            text_color: foreground,
+            disabled_item_background_color: background,
+            disabled_primary_color: accent,
+            disabled_text_color: foreground,
+            disabled_border_color: background,
+            border_width: 1,
+            highlight_border_width: 2,
+            text_blend: None,
+            ..Self::defaults(background)
+        })
+    }
+
+    /// Starts a style whose item, highlight and disabled colors are shades between the colors of
+    /// the palette, mixed with the given [BlendFn] (e.g. [blend_rgb] or [blend_gray]).
+    ///
+    /// The blend function is also used to anti-alias text.
+    pub fn blended(palette: Palette<COL>, blend: BlendFn<COL>) -> Self {
+        let Palette {
+            background,
+            foreground,
+            accent,
+        } = palette;
+        // coverage of the foreground, out of 255
+        let shade = |coverage| blend(background, foreground, coverage);
+        Self::with_colors(Style {
+            background_color: background,
+            item_background_color: shade(40),
+            highlight_item_background_color: shade(80),
+            border_color: foreground,
+            highlight_border_color: foreground,
+            primary_color: accent,
+            secondary_color: blend(accent, foreground, 128),
+            icon_color: foreground,
+            text_color: foreground,
+            disabled_item_background_color: shade(20),
+            disabled_primary_color: blend(background, accent, 100),
+            disabled_text_color: shade(110),
+            disabled_border_color: shade(110),
+            border_width: 0,
+            highlight_border_width: 1,
+            text_blend: Some(blend),
+            ..Self::defaults(background)
+        })
+    }
+
+    fn with_colors(style: Style<COL>) -> Self {
+        Self { style }
+    }
+
+    /// Medium-sized defaults (like the [Rgb565] themes), with all colors set to `color`.
+    fn defaults(color: COL) -> Style<COL> {
+        Style {
+            background_color: color,
+            border_color: color,
+            primary_color: color,
+            secondary_color: color,
+            icon_color: color,
+            default_widget_height: 16,
+            border_width: 0,
+            default_font: &mono_font::ascii::FONT_9X15,
+            text_blend: None,
+            spacing: Spacing {
+                item_spacing: Size::new(8, 4),
+                button_padding: Size::new(5, 5),
+                default_padding: Size::new(1, 1),
+                window_border_padding: Size::new(3, 3),
+            },
+            item_background_color: color,
+            highlight_item_background_color: color,
+            highlight_border_color: color,
+            highlight_border_width: 1,
+            text_color: color,
+            disabled_item_background_color: color,
+            disabled_primary_color: color,
+            disabled_text_color: color,
+            disabled_border_color: color,
+        }
+    }
+
+    /// Sets the background color of the UI.
+    pub fn background_color(mut self, color: COL) -> Self {
+        self.style.background_color = color;
+        self
+    }
+
+    /// Sets the background color of items (e.g. buttons).
+    pub fn item_background_color(mut self, color: COL) -> Self {
+        self.style.item_background_color = color;
+        self
+    }
+
+    /// Sets the background color of highlighted (e.g. hovered) items.
+    pub fn highlight_item_background_color(mut self, color: COL) -> Self {
+        self.style.highlight_item_background_color = color;
+        self
+    }
+
+    /// Sets the border color.
+    pub fn border_color(mut self, color: COL) -> Self {
+        self.style.border_color = color;
+        self
+    }
+
+    /// Sets the border color of highlighted items.
+    pub fn highlight_border_color(mut self, color: COL) -> Self {
+        self.style.highlight_border_color = color;
+        self
+    }
+
+    /// Sets the primary accent color.
+    pub fn primary_color(mut self, color: COL) -> Self {
+        self.style.primary_color = color;
+        self
+    }
+
+    /// Sets the secondary accent color.
+    pub fn secondary_color(mut self, color: COL) -> Self {
+        self.style.secondary_color = color;
+        self
+    }
+
+    /// Sets the icon color.
+    pub fn icon_color(mut self, color: COL) -> Self {
+        self.style.icon_color = color;
+        self
+    }
+
+    /// Sets the text color.
+    pub fn text_color(mut self, color: COL) -> Self {
+        self.style.text_color = color;
+        self
+    }
+
+    /// Sets the colors of disabled items: their background, accent, and text (see [Style::disabled]).
+    pub fn disabled_colors(mut self, background: COL, primary: COL, text: COL) -> Self {
+        self.style.disabled_item_background_color = background;
+        self.style.disabled_primary_color = primary;
+        self.style.disabled_text_color = text;
+        self
+    }
+
+    /// Sets the border color of disabled items (see [Style::disabled]).
+    pub fn disabled_border_color(mut self, color: COL) -> Self {
+        self.style.disabled_border_color = color;
+        self
+    }
+
+    /// Sets the default font.
+    pub fn font(mut self, font: &'static dyn Font) -> Self {
+        self.style.default_font = font;
+        self
+    }
+
+    /// Sets the function that blends anti-aliased text against the background.
+    pub fn text_blend(mut self, blend: Option<BlendFn<COL>>) -> Self {
+        self.style.text_blend = blend;
+        self
+    }
+
+    /// Sets the default height of widgets like buttons.
+    pub fn default_widget_height(mut self, height: u32) -> Self {
+        self.style.default_widget_height = height;
+        self
+    }
+
+    /// Sets the border width.
+    pub fn border_width(mut self, width: u32) -> Self {
+        self.style.border_width = width;
+        self
+    }
+
+    /// Sets the border width of highlighted items.
+    pub fn highlight_border_width(mut self, width: u32) -> Self {
+        self.style.highlight_border_width = width;
+        self
+    }
+
+    /// Sets the whole spacing configuration.
+    pub fn spacing(mut self, spacing: Spacing) -> Self {
+        self.style.spacing = spacing;
+        self
+    }
+
+    /// Sets the space between adjacent items.
+    pub fn item_spacing(mut self, spacing: Size) -> Self {
+        self.style.spacing.item_spacing = spacing;
+        self
+    }
+
+    /// Sets the padding inside buttons.
+    pub fn button_padding(mut self, padding: Size) -> Self {
+        self.style.spacing.button_padding = padding;
+        self
+    }
+
+    /// Sets the padding around the border of widgets.
+    pub fn default_padding(mut self, padding: Size) -> Self {
+        self.style.spacing.default_padding = padding;
+        self
+    }
+
+    /// Sets the padding inside the window border.
+    pub fn window_border_padding(mut self, padding: Size) -> Self {
+        self.style.spacing.window_border_padding = padding;
+        self
+    }
+
+    /// Builds the [Style].
+    pub fn build(self) -> Style<COL> {
+        self.style
+    }
+}
+
+/// Overrides parts of a [Style] for a single widget, see [Ui::add_styled](crate::Ui::add_styled).
+///
+/// Everything that isn't overridden is inherited from the style of the [Ui](crate::Ui).
+#[derive(Debug, Clone, Copy)]
+pub struct StyleOverride<COL: PixelColor> {
+    background_color: Option<COL>,
+    item_background_color: Option<COL>,
+    highlight_item_background_color: Option<COL>,
+    border_color: Option<COL>,
+    highlight_border_color: Option<COL>,
+    primary_color: Option<COL>,
+    secondary_color: Option<COL>,
+    icon_color: Option<COL>,
+    text_color: Option<COL>,
+    border_width: Option<u32>,
+    highlight_border_width: Option<u32>,
+    default_widget_height: Option<u32>,
+    default_font: Option<&'static dyn Font>,
+    button_padding: Option<Size>,
+    default_padding: Option<Size>,
+}
+
+impl<COL: PixelColor> Default for StyleOverride<COL> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<COL: PixelColor> StyleOverride<COL> {
+    /// Creates an override that doesn't change anything.
+    pub const fn new() -> Self {
+        StyleOverride {
+            background_color: None,
+            item_background_color: None,
+            highlight_item_background_color: None,
+            border_color: None,
+            highlight_border_color: None,
+            primary_color: None,
+            secondary_color: None,
+            icon_color: None,
+            text_color: None,
+            border_width: None,
+            highlight_border_width: None,
+            default_widget_height: None,
+            default_font: None,
+            button_padding: None,
+            default_padding: None,
+        }
+    }
+
+    /// Overrides the background color.
+    pub const fn background_color(mut self, color: COL) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    /// Overrides the background color of items (e.g. buttons).
+    pub const fn item_background_color(mut self, color: COL) -> Self {
+        self.item_background_color = Some(color);
+        self
+    }
+
+    /// Overrides the background color of highlighted (e.g. hovered) items.
+    pub const fn highlight_item_background_color(mut self, color: COL) -> Self {
+        self.highlight_item_background_color = Some(color);
+        self
+    }
+
+    /// Overrides the border color.
+    pub const fn border_color(mut self, color: COL) -> Self {
+        self.border_color = Some(color);
+        self
+    }
+
+    /// Overrides the border color of highlighted items.
+    pub const fn highlight_border_color(mut self, color: COL) -> Self {
+        self.highlight_border_color = Some(color);
+        self
+    }
+
+    /// Overrides the primary accent color (e.g. of pressed buttons).
+    pub const fn primary_color(mut self, color: COL) -> Self {
+        self.primary_color = Some(color);
+        self
+    }
+
+    /// Overrides the secondary accent color.
+    pub const fn secondary_color(mut self, color: COL) -> Self {
+        self.secondary_color = Some(color);
+        self
+    }
+
+    /// Overrides the icon color.
+    pub const fn icon_color(mut self, color: COL) -> Self {
+        self.icon_color = Some(color);
+        self
+    }
+
+    /// Overrides the text color.
+    pub const fn text_color(mut self, color: COL) -> Self {
+        self.text_color = Some(color);
+        self
+    }
+
+    /// Overrides the border width.
+    pub const fn border_width(mut self, width: u32) -> Self {
+        self.border_width = Some(width);
+        self
+    }
+
+    /// Overrides the border width of highlighted items.
+    pub const fn highlight_border_width(mut self, width: u32) -> Self {
+        self.highlight_border_width = Some(width);
+        self
+    }
+
+    /// Overrides the default widget height.
+    pub const fn default_widget_height(mut self, height: u32) -> Self {
+        self.default_widget_height = Some(height);
+        self
+    }
+
+    /// Overrides the font.
+    pub const fn font(mut self, font: &'static dyn Font) -> Self {
+        self.default_font = Some(font);
+        self
+    }
+
+    /// Overrides the padding inside buttons.
+    pub const fn button_padding(mut self, padding: Size) -> Self {
+        self.button_padding = Some(padding);
+        self
+    }
+
+    /// Overrides the padding around the border of widgets.
+    pub const fn default_padding(mut self, padding: Size) -> Self {
+        self.default_padding = Some(padding);
+        self
+    }
+
+    /// Returns the given style with the overridden parts replaced.
+    pub fn apply(&self, style: Style<COL>) -> Style<COL> {
+        let spacing = Spacing {
+            button_padding: self.button_padding.unwrap_or(style.spacing.button_padding),
+            default_padding: self
+                .default_padding
+                .unwrap_or(style.spacing.default_padding),
+            ..style.spacing
+        };
+        Style {
+            background_color: self.background_color.unwrap_or(style.background_color),
+            item_background_color: self
+                .item_background_color
+                .unwrap_or(style.item_background_color),
+            highlight_item_background_color: self
+                .highlight_item_background_color
+                .unwrap_or(style.highlight_item_background_color),
+            border_color: self.border_color.unwrap_or(style.border_color),
+            highlight_border_color: self
+                .highlight_border_color
+                .unwrap_or(style.highlight_border_color),
+            primary_color: self.primary_color.unwrap_or(style.primary_color),
+            secondary_color: self.secondary_color.unwrap_or(style.secondary_color),
+            icon_color: self.icon_color.unwrap_or(style.icon_color),
+            text_color: self.text_color.unwrap_or(style.text_color),
+            border_width: self.border_width.unwrap_or(style.border_width),
+            highlight_border_width: self
+                .highlight_border_width
+                .unwrap_or(style.highlight_border_width),
+            default_widget_height: self
+                .default_widget_height
+                .unwrap_or(style.default_widget_height),
+            default_font: self.default_font.unwrap_or(style.default_font),
+            spacing,
+            ..style
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::Rgb888;
+
+    #[test]
+    fn test_derived_styles() {
+        let style = medsize_gray4_style();
+        assert_eq!(style.background_color, Gray4::WHITE);
+        assert_eq!(style.item_background_color, Gray4::new(0xd));
+        assert_eq!(style.highlight_item_background_color, Gray4::new(0xa));
+        assert_eq!(style.disabled_text_color, Gray4::new(0x9));
+        assert!(style.text_blend.is_some());
+
+        let style = medsize_binary_style();
+        assert_eq!(style.item_background_color, BinaryColor::Off);
+        assert_eq!(style.highlight_border_color, BinaryColor::On);
+        assert!(style.highlight_border_width > style.border_width);
+        // disabled items look different, although there are only two colors
+        let disabled = style.disabled();
+        assert_eq!(disabled.border_color, BinaryColor::Off);
+        assert_ne!(disabled.border_color, style.border_color);
+        assert_ne!(
+            disabled.highlight_border_color,
+            style.highlight_border_color
+        );
+
+        let palette = Palette {
+            background: Rgb888::BLACK,
+            foreground: Rgb888::WHITE,
+            accent: Rgb888::RED,
+        };
+        let style = StyleBuilder::blended(palette, blend_rgb)
+            .text_color(Rgb888::GREEN)
+            .item_spacing(Size::new(2, 2))
+            .build();
+        assert_eq!(style.item_background_color, Rgb888::new(40, 40, 40));
+        assert_eq!(style.secondary_color, Rgb888::new(255, 128, 128));
+        assert_eq!(style.text_color, Rgb888::GREEN);
+        assert_eq!(style.spacing.item_spacing, Size::new(2, 2));
+    }
 }
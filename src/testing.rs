@@ -0,0 +1,741 @@
+//! # Testing
+//!
+//! Helpers to test widgets and layouts headless, e.g. in CI, without a display or the SDL
+//! simulator. This module requires the `testing` feature (and `std`):
+//!
+//! ```toml
+//! [dev-dependencies]
+//! kolibri-embedded-gui = { version = "*", features = ["testing"] }
+//! ```
+//!
+//! - A [TestDisplay] is an in-memory [DrawTarget]. It can be inspected pixel by pixel, printed as
+//!   ASCII art, and saved as (or loaded from) a PPM image.
+//! - A [Harness] drives a [Ui] over multiple frames, with one [Interaction] per frame (e.g. from a
+//!   [Script]), just like the drawing loop of an application.
+//! - [assert_golden] compares a [TestDisplay] with a golden PPM image. If they differ, the
+//!   differing pixels are shown as ASCII art, and saved as PPM images next to the golden image.
+//!
+//! Golden images are (re)created by running the tests with the environment variable
+//! `KOLIBRI_BLESS` set (see [BLESS_VAR]).
+//!
+//! # Example
+//!
+//! ```
+//! use embedded_graphics::pixelcolor::Rgb565;
+//! use embedded_graphics::prelude::*;
+//! use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! use kolibri_embedded_gui::testing::{assert_golden, Harness, Script};
+//! use kolibri_embedded_gui::widgets::Checkbox;
+//!
+//! let mut harness = Harness::new(Size::new(64, 32), medsize_rgb565_style());
+//! let mut checked = false;
+//!
+//! // click on the checkbox, and redraw it once more
+//! harness.run(Script::new().tap(Point::new(10, 10)).idle(1), |ui| {
+//!     ui.add(Checkbox::new(&mut checked));
+//! });
+//! assert!(checked);
+//!
+//! // compare the result with a golden image
+//! assert_golden(
+//!     harness.display(),
+//!     concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/checkbox.ppm"),
+//! );
+//! ```
+
+extern crate std;
+
+use crate::focus::FocusState;
+use crate::style::Style;
+use crate::ui::{OverlayState, PointerCapture};
+use crate::{Interaction, Ui};
+use core::convert::Infallible;
+use core::fmt;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{OriginDimensions, Point, Size};
+use embedded_graphics::pixelcolor::{PixelColor, Rgb888, RgbColor};
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+use embedded_graphics::Pixel;
+use std::format;
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::vec::Vec;
+
+/// Environment variable that makes [assert_golden] (re)write the golden images instead of
+/// comparing with them.
+pub const BLESS_VAR: &str = "KOLIBRI_BLESS";
+
+/// Maximum size of the ASCII art of a [Diff], in chars
+const MAX_ASCII_DIFF: Size = Size::new(100, 50);
+
+/// In-memory display, e.g. to test widgets without hardware.
+///
+/// Pixels drawn outside of the display are ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestDisplay<COL> {
+    size: Size,
+    pixels: Vec<COL>,
+}
+
+impl<COL: PixelColor> TestDisplay<COL> {
+    /// Creates a display of the given size, filled with the background color.
+    pub fn new(size: Size, background: COL) -> Self {
+        Self {
+            size,
+            pixels: std::vec![background; (size.width * size.height) as usize],
+        }
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let (x, y) = (u32::try_from(point.x).ok()?, u32::try_from(point.y).ok()?);
+        (x < self.size.width && y < self.size.height).then(|| (y * self.size.width + x) as usize)
+    }
+
+    /// Returns the color of a pixel, or [None] if the point is outside of the display.
+    pub fn pixel(&self, point: Point) -> Option<COL> {
+        self.index(point).map(|index| self.pixels[index])
+    }
+
+    /// Returns all pixels, row by row.
+    pub fn pixels(&self) -> &[COL] {
+        &self.pixels
+    }
+
+    /// Renders the display as ASCII art, with one char per pixel and one line per row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use embedded_graphics::pixelcolor::BinaryColor;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+    /// # use kolibri_embedded_gui::testing::TestDisplay;
+    /// let mut display = TestDisplay::new(Size::new(4, 3), BinaryColor::Off);
+    /// Rectangle::new(Point::new(1, 1), Size::new(2, 1))
+    ///     .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// let ascii = display.to_ascii(|color| if color.is_on() { '#' } else { '.' });
+    /// assert_eq!(ascii, "....\n.##.\n....\n");
+    /// ```
+    pub fn to_ascii(&self, map: impl Fn(COL) -> char) -> String {
+        let mut ascii = String::new();
+        for row in self.pixels.chunks(self.size.width.max(1) as usize) {
+            ascii.extend(row.iter().map(|color| map(*color)));
+            ascii.push('\n');
+        }
+        ascii
+    }
+}
+
+impl<COL: PixelColor + Into<Rgb888>> TestDisplay<COL> {
+    /// Converts the display to [Rgb888] colors (as used for golden images).
+    pub fn to_rgb888(&self) -> TestDisplay<Rgb888> {
+        TestDisplay {
+            size: self.size,
+            pixels: self.pixels.iter().map(|color| (*color).into()).collect(),
+        }
+    }
+
+    /// Encodes the display as binary PPM (P6) image.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.size.width, self.size.height).into_bytes();
+        for color in &self.pixels {
+            let color: Rgb888 = (*color).into();
+            ppm.extend_from_slice(&[color.r(), color.g(), color.b()]);
+        }
+        ppm
+    }
+}
+
+impl TestDisplay<Rgb888> {
+    /// Decodes a binary PPM (P6) image with 8 bits per channel.
+    pub fn from_ppm(data: &[u8]) -> Result<Self, &'static str> {
+        let mut rest = data;
+        let mut header = [0u32; 3];
+        if !rest.starts_with(b"P6") {
+            return Err("not a binary PPM image");
+        }
+        rest = &rest[2..];
+        for value in header.iter_mut() {
+            // skip whitespace and comments
+            loop {
+                match rest.first() {
+                    Some(c) if c.is_ascii_whitespace() => rest = &rest[1..],
+                    Some(b'#') => {
+                        let end = rest.iter().position(|c| *c == b'\n').unwrap_or(rest.len());
+                        rest = &rest[end..];
+                    }
+                    _ => break,
+                }
+            }
+            let digits = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+            *value = core::str::from_utf8(&rest[..digits])
+                .ok()
+                .and_then(|digits| digits.parse().ok())
+                .ok_or("invalid PPM header")?;
+            rest = &rest[digits..];
+        }
+        let [width, height, max] = header;
+        if max != 255 {
+            return Err("only 8 bit PPM images are supported");
+        }
+        // a single whitespace char separates the header from the pixels
+        let pixels = rest.get(1..).ok_or("missing PPM pixels")?;
+        if pixels.len() != (width * height * 3) as usize {
+            return Err("PPM image size doesn't match its header");
+        }
+
+        Ok(Self {
+            size: Size::new(width, height),
+            pixels: pixels
+                .chunks(3)
+                .map(|rgb| Rgb888::new(rgb[0], rgb[1], rgb[2]))
+                .collect(),
+        })
+    }
+}
+
+impl<COL: PixelColor> OriginDimensions for TestDisplay<COL> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<COL: PixelColor> DrawTarget for TestDisplay<COL> {
+    type Color = COL;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(index) = self.index(point) {
+                self.pixels[index] = color;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Differences between a [TestDisplay] and an expected (e.g. golden) image.
+#[derive(Clone, Debug)]
+pub struct Diff {
+    actual: TestDisplay<Rgb888>,
+    expected: TestDisplay<Rgb888>,
+    differing: usize,
+    bounds: Option<Rectangle>,
+}
+
+impl Diff {
+    /// Compares a display with the expected image.
+    ///
+    /// If the sizes differ, pixels that only exist in one of them are differing.
+    pub fn new<COL: PixelColor + Into<Rgb888>>(
+        actual: &TestDisplay<COL>,
+        expected: &TestDisplay<Rgb888>,
+    ) -> Self {
+        let actual = actual.to_rgb888();
+        let size = actual.size.component_max(expected.size);
+        let mut differing = 0;
+        let mut corners: Option<(Point, Point)> = None;
+        for point in Rectangle::new(Point::zero(), size).points() {
+            if actual.pixel(point) != expected.pixel(point) {
+                differing += 1;
+                corners = Some(match corners {
+                    Some((tl, br)) => (tl.component_min(point), br.component_max(point)),
+                    None => (point, point),
+                });
+            }
+        }
+
+        Self {
+            actual,
+            expected: expected.clone(),
+            differing,
+            bounds: corners.map(|(tl, br)| Rectangle::with_corners(tl, br)),
+        }
+    }
+
+    /// Returns whether the display matches the expected image.
+    pub fn is_empty(&self) -> bool {
+        self.differing == 0
+    }
+
+    /// Returns the number of differing pixels.
+    pub fn differing_pixels(&self) -> usize {
+        self.differing
+    }
+
+    /// Returns the bounding box of the differing pixels, if any.
+    pub fn bounds(&self) -> Option<Rectangle> {
+        self.bounds
+    }
+
+    /// Renders the differing area (and a margin of one pixel around it) as ASCII art, with
+    /// `X` for differing pixels and `.` for matching ones.
+    ///
+    /// Each line starts with the y coordinate of the row. Large areas are cut off.
+    pub fn to_ascii(&self) -> String {
+        let Some(bounds) = self.bounds else {
+            return String::new();
+        };
+        let area = bounds.offset(1).intersection(&Rectangle::new(
+            Point::zero(),
+            self.actual.size.component_max(self.expected.size),
+        ));
+        let shown = Rectangle::new(area.top_left, area.size.component_min(MAX_ASCII_DIFF));
+
+        let mut ascii = format!("x = {}..{}\n", shown.top_left.x, shown.columns().end);
+        for y in shown.rows() {
+            ascii.push_str(&format!("{y:4} "));
+            ascii.extend(shown.columns().map(|x| {
+                let point = Point::new(x, y);
+                if self.actual.pixel(point) == self.expected.pixel(point) {
+                    '.'
+                } else {
+                    'X'
+                }
+            }));
+            ascii.push('\n');
+        }
+        if shown.size != area.size {
+            ascii.push_str(&format!(
+                "(cut off, the differing area is {}x{})\n",
+                area.size.width, area.size.height
+            ));
+        }
+        ascii
+    }
+
+    /// Encodes the differences as PPM image: differing pixels are red, and matching pixels are
+    /// shown dimmed.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let size = self.actual.size.component_max(self.expected.size);
+        let mut diff = TestDisplay::new(size, Rgb888::BLACK);
+        for point in Rectangle::new(Point::zero(), size).points() {
+            let actual = self.actual.pixel(point);
+            let color = match actual == self.expected.pixel(point) {
+                false => Rgb888::RED,
+                true => actual.map_or(Rgb888::BLACK, |color| {
+                    Rgb888::new(color.r() / 4, color.g() / 4, color.b() / 4)
+                }),
+            };
+            diff.pixels[(point.y as u32 * size.width + point.x as u32) as usize] = color;
+        }
+        diff.to_ppm()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.actual.size != self.expected.size {
+            writeln!(
+                f,
+                "size is {}x{}, expected {}x{}",
+                self.actual.size.width,
+                self.actual.size.height,
+                self.expected.size.width,
+                self.expected.size.height
+            )?;
+        }
+        writeln!(f, "{} pixels differ", self.differing)?;
+        write!(f, "{}", self.to_ascii())
+    }
+}
+
+/// Compares a display with the golden PPM image at `path`, and panics if they differ.
+///
+/// On a mismatch, the actual image and the differences (see [Diff::to_ppm]) are written next to
+/// the golden image (as `<name>.actual.ppm` and `<name>.diff.ppm`), and the differing area is
+/// printed as ASCII art. If the environment variable [BLESS_VAR] is set, the golden image is
+/// (re)written instead.
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::testing::{assert_golden, Harness};
+/// # use kolibri_embedded_gui::widgets::Button;
+/// let mut harness = Harness::new(Size::new(64, 32), medsize_rgb565_style());
+/// harness.frame(Default::default(), |ui| {
+///     ui.add(Button::new("OK"));
+/// });
+/// assert_golden(
+///     harness.display(),
+///     concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/button.ppm"),
+/// );
+/// ```
+#[track_caller]
+pub fn assert_golden<COL: PixelColor + Into<Rgb888>>(
+    display: &TestDisplay<COL>,
+    path: impl AsRef<Path>,
+) {
+    let bless = std::env::var_os(BLESS_VAR).is_some();
+    if let Err(msg) = check_golden(display, path.as_ref(), bless) {
+        panic!("{msg}");
+    }
+}
+
+/// Returns the path of a file next to the golden image, e.g. `button.diff.ppm`.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}.{suffix}.ppm"))
+}
+
+fn check_golden<COL: PixelColor + Into<Rgb888>>(
+    display: &TestDisplay<COL>,
+    path: &Path,
+    bless: bool,
+) -> Result<(), String> {
+    let shown = path.display();
+    if bless {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("couldn't create {shown}: {e}"))?;
+        }
+        return std::fs::write(path, display.to_ppm())
+            .map_err(|e| format!("couldn't write {shown}: {e}"));
+    }
+
+    let data = std::fs::read(path).map_err(|e| {
+        format!("couldn't read golden image {shown} ({e}), run with {BLESS_VAR}=1 to create it")
+    })?;
+    let expected =
+        TestDisplay::from_ppm(&data).map_err(|e| format!("invalid golden image {shown}: {e}"))?;
+
+    let diff = Diff::new(display, &expected);
+    if diff.is_empty() {
+        return Ok(());
+    }
+    let (actual_path, diff_path) = (sibling(path, "actual"), sibling(path, "diff"));
+    std::fs::write(&actual_path, display.to_ppm()).ok();
+    std::fs::write(&diff_path, diff.to_ppm()).ok();
+    Err(format!(
+        "display doesn't match golden image {shown}\n{diff}see {} and {}, or run with {BLESS_VAR}=1 to update the golden image",
+        actual_path.display(),
+        diff_path.display()
+    ))
+}
+
+/// A sequence of [Interaction]s, one per frame.
+///
+/// # Example
+///
+/// ```
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::testing::Script;
+/// # use kolibri_embedded_gui::Interaction;
+/// let script = Script::new()
+///     .tap(Point::new(10, 10))
+///     .idle(2)
+///     .drag(Point::new(0, 20), Point::new(30, 20), 3)
+///     .then(Interaction::Next);
+/// assert_eq!(script.steps().len(), 10);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Script {
+    steps: Vec<Interaction>,
+}
+
+impl Script {
+    /// Creates an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a frame with the given interaction.
+    pub fn then(mut self, interaction: Interaction) -> Self {
+        self.steps.push(interaction);
+        self
+    }
+
+    /// Adds frames without interaction.
+    pub fn idle(mut self, frames: usize) -> Self {
+        self.steps
+            .extend(core::iter::repeat_n(Interaction::None, frames));
+        self
+    }
+
+    /// Adds a frame in which the pointer is pressed at the point.
+    pub fn press(self, point: Point) -> Self {
+        self.then(Interaction::Click(point))
+    }
+
+    /// Adds a frame in which the pointer is released at the point.
+    pub fn release(self, point: Point) -> Self {
+        self.then(Interaction::Release(point))
+    }
+
+    /// Adds a frame in which the (not pressed) pointer hovers over the point.
+    pub fn hover(self, point: Point) -> Self {
+        self.then(Interaction::Hover(point))
+    }
+
+    /// Adds a tap (or click) at the point: a press, and a release in the next frame.
+    pub fn tap(self, point: Point) -> Self {
+        self.press(point).release(point)
+    }
+
+    /// Adds a drag from one point to another: a press at `from`, `steps` frames in which the
+    /// pointer moves towards `to` in equal steps, and a release at `to`.
+    pub fn drag(self, from: Point, to: Point, steps: usize) -> Self {
+        let mut script = self.press(from);
+        let steps = steps.max(1) as i32;
+        for step in 1..=steps {
+            script = script.then(Interaction::Drag(from + (to - from) * step / steps));
+        }
+        script.release(to)
+    }
+
+    /// Returns the interactions, one per frame.
+    pub fn steps(&self) -> &[Interaction] {
+        &self.steps
+    }
+}
+
+impl IntoIterator for Script {
+    type Item = Interaction;
+    type IntoIter = std::vec::IntoIter<Interaction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.steps.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s Script {
+    type Item = Interaction;
+    type IntoIter = core::iter::Copied<core::slice::Iter<'s, Interaction>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.steps.iter().copied()
+    }
+}
+
+/// Drives a [Ui] on a [TestDisplay] over multiple frames.
+///
+/// Each frame, a new [Ui] is created on the display (with a [FocusState], an [OverlayState] and a
+/// [PointerCapture] that persist across frames, and optionally a buffer), the frame's [Interaction] is passed to
+/// it, and the widgets are added by a closure. Popups are drawn at the end of the frame.
+///
+/// State that has to persist across frames (e.g. widget values or smartstates) is kept outside
+/// of the harness, and borrowed by the closure.
+pub struct Harness<COL: PixelColor> {
+    display: TestDisplay<COL>,
+    style: Style<COL>,
+    buffer: Option<Vec<COL>>,
+    focus: FocusState,
+    overlay: OverlayState,
+    capture: PointerCapture,
+    frames: usize,
+}
+
+impl<COL: PixelColor> Harness<COL> {
+    /// Creates a harness with a display of the given size, filled with the background color of
+    /// the style.
+    pub fn new(size: Size, style: Style<COL>) -> Self {
+        Self {
+            display: TestDisplay::new(size, style.background_color),
+            style,
+            buffer: None,
+            focus: FocusState::new(),
+            overlay: OverlayState::new(),
+            capture: PointerCapture::new(),
+            frames: 0,
+        }
+    }
+
+    /// Draws through a buffer of `len` pixels (see [Ui::set_buffer]).
+    pub fn with_buffer(mut self, len: usize) -> Self {
+        self.buffer = Some(std::vec![self.style.background_color; len]);
+        self
+    }
+
+    /// Draws a single frame with the given interaction.
+    pub fn frame(
+        &mut self,
+        interaction: Interaction,
+        f: impl FnOnce(&mut Ui<TestDisplay<COL>, COL>),
+    ) {
+        let mut ui = Ui::new_fullscreen(&mut self.display, self.style);
+        if let Some(buffer) = self.buffer.as_deref_mut() {
+            ui.set_buffer(buffer);
+        }
+        ui.set_focus_state(&self.focus);
+        ui.set_pointer_capture(&self.capture);
+        ui.set_overlay_state(&self.overlay);
+        ui.interact(interaction);
+
+        f(&mut ui);
+
+        ui.draw_overlay().expect("couldn't draw the overlay");
+        self.frames += 1;
+    }
+
+    /// Draws one frame per interaction of the script (or any other sequence of interactions).
+    pub fn run(
+        &mut self,
+        script: impl IntoIterator<Item = Interaction>,
+        mut f: impl FnMut(&mut Ui<TestDisplay<COL>, COL>),
+    ) {
+        for interaction in script {
+            self.frame(interaction, &mut f);
+        }
+    }
+
+    /// Returns the display, e.g. to compare it with a golden image.
+    pub fn display(&self) -> &TestDisplay<COL> {
+        &self.display
+    }
+
+    /// Returns the display, e.g. to clear it.
+    pub fn display_mut(&mut self) -> &mut TestDisplay<COL> {
+        &mut self.display
+    }
+
+    /// Returns the focus state.
+    pub fn focus(&self) -> &FocusState {
+        &self.focus
+    }
+
+    /// Returns the overlay state.
+    pub fn overlay(&self) -> &OverlayState {
+        &self.overlay
+    }
+
+    /// Returns the pointer capture state.
+    pub fn capture(&self) -> &PointerCapture {
+        &self.capture
+    }
+
+    /// Returns the number of frames drawn so far.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use crate::widgets::Checkbox;
+    use embedded_graphics::pixelcolor::Rgb565;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::PrimitiveStyle;
+
+    #[test]
+    fn test_script() {
+        let script = Script::new().tap(Point::new(1, 2)).idle(1).drag(
+            Point::new(0, 0),
+            Point::new(10, 5),
+            2,
+        );
+        assert_eq!(
+            script.steps(),
+            &[
+                Interaction::Click(Point::new(1, 2)),
+                Interaction::Release(Point::new(1, 2)),
+                Interaction::None,
+                Interaction::Click(Point::new(0, 0)),
+                Interaction::Drag(Point::new(5, 2)),
+                Interaction::Drag(Point::new(10, 5)),
+                Interaction::Release(Point::new(10, 5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_harness() {
+        let mut harness = Harness::new(Size::new(64, 32), medsize_rgb565_style());
+        let mut checked = false;
+        let mut changes = 0;
+        harness.run(Script::new().idle(1).tap(Point::new(10, 10)), |ui| {
+            if ui.add(Checkbox::new(&mut checked)).changed() {
+                changes += 1;
+            }
+        });
+        assert!(checked);
+        assert_eq!(changes, 1);
+        assert_eq!(harness.frames(), 3);
+
+        // the placer: raw allocations are packed in rows, and wrapped at the border padding
+        let mut style = medsize_rgb565_style();
+        style.spacing.window_border_padding = Size::new(1, 1);
+        let mut harness = Harness::new(Size::new(8, 6), style).with_buffer(16);
+        harness.frame(Interaction::None, |ui| {
+            for size in [Size::new(2, 1), Size::new(3, 2), Size::new(2, 1)] {
+                let area = ui.allocate_space(size).unwrap().area;
+                ui.draw(&area.into_styled(PrimitiveStyle::with_fill(Rgb565::WHITE)))
+                    .unwrap();
+            }
+        });
+        let ascii = harness
+            .display()
+            .to_ascii(|color| if color == Rgb565::WHITE { '#' } else { '.' });
+        assert_eq!(
+            ascii,
+            "........\n.#####..\n...###..\n.##.....\n........\n........\n"
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut display = TestDisplay::new(Size::new(6, 4), Rgb565::BLUE);
+        let expected = TestDisplay::from_ppm(&display.to_ppm()).unwrap();
+        assert_eq!(expected.to_ppm(), display.to_ppm());
+        assert!(Diff::new(&display, &expected).is_empty());
+
+        Pixel(Point::new(2, 1), Rgb565::RED)
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(3, 2), Rgb565::RED)
+            .draw(&mut display)
+            .unwrap();
+        let diff = Diff::new(&display, &expected);
+        assert_eq!(diff.differing_pixels(), 2);
+        assert_eq!(
+            diff.bounds(),
+            Some(Rectangle::new(Point::new(2, 1), Size::new(2, 2)))
+        );
+        assert_eq!(
+            diff.to_ascii(),
+            "x = 1..5\n   0 ....\n   1 .X..\n   2 ..X.\n   3 ....\n"
+        );
+    }
+
+    #[test]
+    fn test_checkbox_golden() {
+        let mut harness = Harness::new(Size::new(64, 32), medsize_rgb565_style());
+        let mut checked = false;
+        harness.run(Script::new().tap(Point::new(10, 10)).idle(1), |ui| {
+            ui.add(Checkbox::new(&mut checked));
+        });
+        assert!(checked);
+        assert_golden(
+            harness.display(),
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/checkbox.ppm"),
+        );
+    }
+
+    #[test]
+    fn test_golden() {
+        let dir = std::env::temp_dir().join(format!("kolibri-golden-{}", std::process::id()));
+        let path = dir.join("golden.ppm");
+        let mut display = TestDisplay::new(Size::new(8, 8), Rgb565::BLACK);
+
+        assert!(check_golden(&display, &path, false).is_err());
+        check_golden(&display, &path, true).unwrap();
+        check_golden(&display, &path, false).unwrap();
+
+        Pixel(Point::new(4, 4), Rgb565::WHITE)
+            .draw(&mut display)
+            .unwrap();
+        let msg = check_golden(&display, &path, false).unwrap_err();
+        assert!(msg.contains("1 pixels differ"));
+        assert!(sibling(&path, "actual").exists());
+        assert!(sibling(&path, "diff").exists());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
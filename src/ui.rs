@@ -1,10 +1,15 @@
+use crate::focus::{Focus, FocusState};
 use crate::framebuf::WidgetFramebuf;
-use crate::style::Style;
+use crate::gesture::{Gesture, GestureState, SwipeDirection};
+use crate::interaction::InteractionTransform;
+use crate::smartstate::Smartstate;
+use crate::style::{Style, StyleOverride};
 use crate::{GuiError, GuiResult, Interaction, InternalResponse, Response};
 use core::cell::UnsafeCell;
 use core::cmp::{max, min};
 use core::fmt::Debug;
 use core::ops::{Add, AddAssign, Sub};
+use dirty::{RegionList, Tracked};
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::Dimensions;
 use embedded_graphics::pixelcolor::PixelColor;
@@ -14,6 +19,19 @@ use embedded_graphics::primitives::{
 };
 use embedded_graphics::{Drawable, Pixel};
 
+mod capture;
+mod dirty;
+mod grid;
+mod modal;
+mod overlay;
+mod scroll_area;
+
+pub use capture::PointerCapture;
+pub use dirty::DirtyRegions;
+pub use grid::{ColumnWidth, Grid, GridColumn};
+pub use overlay::{ListPopup, OverlayState};
+pub use scroll_area::{ScrollArea, ScrollState};
+
 pub trait Widget {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
@@ -21,21 +39,52 @@ pub trait Widget {
     ) -> GuiResult<Response>;
 }
 
-#[derive(Clone, Copy, Debug)]
+impl<W: Widget> Widget for &mut W {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        (**self).draw(ui)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HorizontalAlign {
     Left,
     Center,
     Right,
 }
 
-#[derive(Clone, Copy, Debug)]
+impl HorizontalAlign {
+    /// Returns the part of the `free` space that goes before the aligned content
+    pub(crate) fn offset(self, free: u32) -> u32 {
+        match self {
+            HorizontalAlign::Left => 0,
+            HorizontalAlign::Center => free / 2,
+            HorizontalAlign::Right => free,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum VerticalAlign {
     Top,
     Center,
     Bottom,
 }
 
-#[derive(Clone, Copy, Debug)]
+impl VerticalAlign {
+    /// Returns the part of the `free` space that goes above the aligned content
+    fn offset(self, free: u32) -> u32 {
+        match self {
+            VerticalAlign::Top => 0,
+            VerticalAlign::Center => free / 2,
+            VerticalAlign::Bottom => free,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Align(pub HorizontalAlign, pub VerticalAlign);
 
 impl Default for Align {
@@ -44,6 +93,105 @@ impl Default for Align {
     }
 }
 
+/// Direction in which the [Placer] places widgets
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+enum Direction {
+    /// Widgets are placed in rows, from left to right, wrapping downwards
+    #[default]
+    Row,
+    /// Widgets are placed in columns, from top to bottom, wrapping to the right
+    Column,
+}
+
+impl Direction {
+    /// Splits a size into its extent along the placement direction (main axis) and
+    /// across it (cross axis)
+    fn split(self, size: Size) -> (u32, u32) {
+        match self {
+            Direction::Row => (size.width, size.height),
+            Direction::Column => (size.height, size.width),
+        }
+    }
+
+    /// Joins main and cross axis extents into a size
+    fn join(self, main: u32, cross: u32) -> Size {
+        match self {
+            Direction::Row => Size::new(main, cross),
+            Direction::Column => Size::new(cross, main),
+        }
+    }
+}
+
+/// Kind of space that is allocated by [Placer::place]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Placement {
+    /// A widget, which gets aligned and measured
+    Item,
+    /// A widget that was already drawn at the current position, which gets measured but not moved
+    Fixed,
+    /// Spacing between widgets, which is neither aligned nor measured
+    Spacing,
+}
+
+/// Maximum number of lines (rows, or columns in column direction, including spacing rows)
+/// that can be aligned as a whole by [Ui::with_align]. Lines beyond this are aligned widget by widget.
+const MAX_ALIGNED_LINES: usize = 32;
+
+#[derive(Clone, Debug)]
+/// Line extents recorded by the [Placer] during a measuring pass.
+///
+/// The recorded extents are used in the drawing pass to align entire lines (and the entire
+/// block of lines) instead of single widgets.
+struct LineMeasurement {
+    /// Line index at which the measurement started
+    start_line: u32,
+    /// End of the last widget in each line along the main axis, relative to `start_line`
+    line_end: [u32; MAX_ALIGNED_LINES],
+    /// End of the block of lines along the cross axis, if any widget was placed
+    block_end: Option<u32>,
+    /// Last line that was already shifted in the drawing pass
+    aligned_line: Option<u32>,
+}
+
+impl LineMeasurement {
+    fn new(start_line: u32) -> Self {
+        LineMeasurement {
+            start_line,
+            line_end: [0; MAX_ALIGNED_LINES],
+            block_end: None,
+            aligned_line: None,
+        }
+    }
+
+    /// Creates a measurement of a block of lines that all end at `main_end`, with the block
+    /// ending at `cross_end`
+    fn block(start_line: u32, main_end: u32, cross_end: u32) -> Self {
+        LineMeasurement {
+            start_line,
+            line_end: [main_end; MAX_ALIGNED_LINES],
+            block_end: Some(cross_end),
+            aligned_line: None,
+        }
+    }
+
+    /// Returns the recorded end of the given line, if the line is covered by the measurement
+    fn line_end(&self, line: u32) -> Option<u32> {
+        line.checked_sub(self.start_line)
+            .and_then(|idx| self.line_end.get(idx as usize))
+            .copied()
+    }
+
+    /// Record a placed widget, given its end along the main and cross axis
+    fn record(&mut self, line: u32, main_end: u32, cross_end: u32) {
+        if let Some(idx) = line.checked_sub(self.start_line) {
+            if let Some(line_end) = self.line_end.get_mut(idx as usize) {
+                *line_end = max(*line_end, main_end);
+            }
+        }
+        self.block_end = Some(self.block_end.map_or(cross_end, |b| max(b, cross_end)));
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Struct for managing placing of widgets in the [Ui]
 ///
@@ -51,9 +199,20 @@ impl Default for Align {
 ///
 /// ## Placement Rules
 ///
-/// - Widgets are placed in rows, from left to right, from top to bottom (for now)
+/// - Widgets are placed in rows, from left to right, from top to bottom (row direction),
+///   or in columns, from top to bottom, from left to right (column direction)
 /// - Placement is deterministic and repeatable
 /// - Placement cannot happen outside of the bounds of the placer
+///
+/// In column direction, "rows" are columns: [Placer::new_row] starts a new column, and the
+/// row height is the width of the current column.
+///
+/// ## Alignment
+///
+/// Without a measurement, every widget is aligned on its own in the remaining space of its line.
+/// If a measurement from a previous (measuring) pass is available (see [Ui::with_align]),
+/// entire lines are shifted so that all widgets of a line are aligned together, and the whole
+/// block of lines is aligned across them.
 struct Placer {
     /// Current row
     row: u32,
@@ -61,14 +220,22 @@ struct Placer {
     col: u32,
     /// Position of the top left corner of the placer
     pos: Point,
-    /// Height of the current row
+    /// Height of the current row (width of the current column in column direction)
     row_height: u32,
     /// Bounds of the placer
     bounds: Size,
     /// Whether to wrap to the next row if the widget doesn't fit
     wrap: bool,
-    #[allow(unused)] // TODO: use in the future
+    /// Alignment of placed widgets
     align: Align,
+    /// Direction in which widgets are placed
+    direction: Direction,
+    /// Size of the area covered by placed widgets (excluding trailing spacing)
+    used: Size,
+    /// Line extents that are being recorded in a measuring pass
+    measuring: Option<LineMeasurement>,
+    /// Line extents from a previous measuring pass, used for aligning whole lines
+    measured: Option<LineMeasurement>,
 }
 
 impl Placer {
@@ -82,6 +249,18 @@ impl Placer {
             bounds,
             wrap,
             align,
+            direction: Direction::Row,
+            used: Size::zero(),
+            measuring: None,
+            measured: None,
+        }
+    }
+
+    /// Create a new placer placing widgets in the given direction
+    fn with_direction(bounds: Size, wrap: bool, align: Align, direction: Direction) -> Self {
+        Placer {
+            direction,
+            ..Placer::new(bounds, wrap, align)
         }
     }
 
@@ -91,23 +270,78 @@ impl Placer {
         self.wrap = wrap;
     }
 
-    /// **STUB / NOT YET USEFUL**: Set the alignment of the placer
-    #[allow(unused)] // TODO: use in the future
+    /// Set the alignment of the placer
     pub fn set_align(&mut self, align: Align) {
         self.align = align;
     }
 
+    /// Returns the offsets along the main and cross axis for the given `free` space
+    fn align_offsets(&self, main_free: u32, cross_free: u32) -> (u32, u32) {
+        match self.direction {
+            Direction::Row => (
+                self.align.0.offset(main_free),
+                self.align.1.offset(cross_free),
+            ),
+            Direction::Column => (
+                self.align.1.offset(main_free),
+                self.align.0.offset(cross_free),
+            ),
+        }
+    }
+
+    /// Returns the current position split into main and cross axis
+    fn main_cross_pos(&self) -> (u32, u32) {
+        self.direction
+            .split(Size::new(self.pos.x as u32, self.pos.y as u32))
+    }
+
+    /// Sets the current position from main and cross axis
+    fn set_main_cross_pos(&mut self, main: u32, cross: u32) {
+        let pos = self.direction.join(main, cross);
+        self.pos = Point::new(pos.width as i32, pos.height as i32);
+    }
+
+    /// Start recording line extents for all subsequently placed widgets.
+    fn start_measuring(&mut self) {
+        self.measuring = Some(LineMeasurement::new(self.row));
+    }
+
+    /// Stop recording line extents and return the recorded measurement.
+    fn finish_measuring(&mut self) -> Option<LineMeasurement> {
+        self.measuring.take()
+    }
+
+    /// Use a measurement for aligning subsequent lines.
+    ///
+    /// This also shifts the current position across the lines according to the alignment.
+    fn apply_measurement(&mut self, measurement: Option<LineMeasurement>) {
+        if let Some(block_end) = measurement.as_ref().and_then(|m| m.block_end) {
+            let (_, bounds_cross) = self.direction.split(self.bounds);
+            let (_, offset) = self.align_offsets(0, bounds_cross.saturating_sub(block_end));
+            let (main, cross) = self.main_cross_pos();
+            self.set_main_cross_pos(main, cross + offset);
+        }
+        self.measured = measurement;
+    }
+
     /// Allocate the next widget with the given `size`, explicitly disabling wrapping for this operation
     ///
     /// ## Returns
     ///
     /// Returns the allocated rectangle, or an error if the widget doesn't fit
     fn next_no_wrap(&mut self, size: Size) -> GuiResult<Rectangle> {
-        let wrap = self.wrap;
-        self.wrap = false;
-        let res = self.next(size);
-        self.wrap = wrap;
-        res
+        self.place(size, false, Placement::Item)
+    }
+
+    /// Allocate spacing between widgets with the given `size`, without wrapping.
+    ///
+    /// Unlike [Placer::next_no_wrap], spacing is neither aligned nor counted towards the size of a line.
+    ///
+    /// ## Returns
+    ///
+    /// Returns the allocated rectangle, or an error if the spacing doesn't fit
+    fn next_spacing(&mut self, size: Size) -> GuiResult<Rectangle> {
+        self.place(size, false, Placement::Spacing)
     }
 
     /// Allocate the next widget with the given `size`.
@@ -116,48 +350,132 @@ impl Placer {
     ///
     /// Returns the allocated rectangle, or an error if the widget doesn't fit
     fn next(&mut self, size: Size) -> GuiResult<Rectangle> {
+        self.place(size, self.wrap, Placement::Item)
+    }
+
+    /// Allocate space for something that was already drawn at the current position, without
+    /// wrapping or aligning it (see [Placer::align_line_start]).
+    ///
+    /// ## Returns
+    ///
+    /// Returns the allocated rectangle, or an error if it doesn't fit
+    fn next_fixed(&mut self, size: Size) -> GuiResult<Rectangle> {
+        self.place(size, false, Placement::Fixed)
+    }
+
+    /// Place something with the given `size`, wrapping to a new line if allowed and necessary.
+    fn place(&mut self, size: Size, wrap: bool, placement: Placement) -> GuiResult<Rectangle> {
+        let (main, cross) = self.direction.split(size);
+        let (bounds_main, bounds_cross) = self.direction.split(self.bounds);
+
         // check that it's in bounds (size < bounds)
-        if !self.check_bounds(size) {
+        if main > bounds_main || cross > bounds_cross {
             return Err(GuiError::NoSpaceLeft);
         }
 
-        // set bounds (temporary) TODO: do this PROPERLY!
-        if let Align(HorizontalAlign::Center, _) = self.align {
-            if self.pos.x as u32 + size.width > self.bounds.width {
+        let (pos_main, pos_cross) = self.main_cross_pos();
+        if pos_main + main > bounds_main || pos_cross + max(self.row_height, cross) > bounds_cross {
+            if !wrap || self.col == 0 {
                 return Err(GuiError::NoSpaceLeft);
             }
-            // Calculate the right x-coordinate to center the widget between self.pos.x and self.bounds.width
-            // (self.bounds.width + self.pos.x as u32 - size.width) / 2
-            self.pos.x = ((self.bounds.width + self.pos.x as u32 - size.width) / 2) as i32;
-        };
-        let right = size.width + self.pos.x as u32;
-        let mut bottom = max(self.row_height, size.height) + self.pos.y as u32;
-        if !self.check_bounds(Size::new(right, bottom)) {
-            if self.wrap {
-                bottom = self.pos.y as u32 + max(self.row_height, size.height);
-                // check that wrap fits
-                if !self.check_bounds(Size::new(0, bottom)) {
-                    return Err(GuiError::NoSpaceLeft);
-                }
-
-                // perform wrap
-                self.new_row(size.height); // TODO: better / proper wrap impl
-            } else {
+
+            // check that the widget fits in a new line
+            if pos_cross + self.row_height + cross > bounds_cross {
                 return Err(GuiError::NoSpaceLeft);
             }
+
+            // perform wrap
+            self.new_row(cross);
+        }
+
+        if placement == Placement::Item {
+            let offset = self.align_offset(main);
+            let (pos_main, pos_cross) = self.main_cross_pos();
+            self.set_main_cross_pos(pos_main + offset, pos_cross);
         }
 
-        // set new col height (expand if necessary)
-        self.row_height = max(self.row_height, size.height);
+        // set new line size (expand if necessary)
+        self.row_height = max(self.row_height, cross);
 
         // set new position
         let item_pos = self.pos;
-        self.pos = Point::new(right as i32, self.pos.y);
+        let (pos_main, pos_cross) = self.main_cross_pos();
+        self.set_main_cross_pos(pos_main + main, pos_cross);
+        self.col += 1;
+
+        let rect = Rectangle::new(item_pos, self.direction.join(main, self.row_height));
+
+        if placement != Placement::Spacing {
+            let end = rect.top_left + rect.size;
+            self.used = Size::new(
+                max(self.used.width, end.x as u32),
+                max(self.used.height, end.y as u32),
+            );
+            if let Some(measuring) = self.measuring.as_mut() {
+                measuring.record(self.row, pos_main + main, pos_cross + self.row_height);
+            }
+        }
 
-        Ok(Rectangle::new(
-            item_pos,
-            Size::new(size.width, self.row_height),
-        ))
+        Ok(rect)
+    }
+
+    /// Returns the offset along the main axis to apply before placing an item of the given
+    /// extent along the main axis.
+    ///
+    /// With a measurement for the current line, the whole line is shifted once (at its first item).
+    /// Otherwise, the item is aligned on its own in the remaining space of the line.
+    fn align_offset(&mut self, main: u32) -> u32 {
+        if self.measuring.is_some() {
+            // measuring happens unaligned
+            return 0;
+        }
+
+        // never shift the item out of bounds, even if the layout changed since measuring
+        let (pos_main, _) = self.main_cross_pos();
+        let (bounds_main, _) = self.direction.split(self.bounds);
+        let free = bounds_main.saturating_sub(pos_main + main);
+
+        if let Some(offset) = self.measured_line_offset() {
+            return min(offset, free);
+        }
+
+        self.align_offsets(free, 0).0
+    }
+
+    /// Returns the offset for the current line from the measurement, if the line was measured.
+    ///
+    /// The offset is only returned once per line, after which `Some(0)` is returned.
+    fn measured_line_offset(&mut self) -> Option<u32> {
+        let line = self.row;
+        let (bounds_main, _) = self.direction.split(self.bounds);
+        let measured = self.measured.as_mut()?;
+        let line_end = measured.line_end(line)?;
+        if measured.aligned_line == Some(line) {
+            return Some(0);
+        }
+        measured.aligned_line = Some(line);
+        let free = bounds_main.saturating_sub(line_end);
+        Some(self.align_offsets(free, 0).0)
+    }
+
+    /// Shift the current position to the aligned start of the current line, if the line was
+    /// measured and has not been aligned yet.
+    ///
+    /// This is used before drawing content whose size is only known afterwards, which is then
+    /// allocated with [Placer::next_fixed].
+    fn align_line_start(&mut self) {
+        if self.measuring.is_some() {
+            return;
+        }
+        if let Some(offset) = self.measured_line_offset() {
+            let (pos_main, pos_cross) = self.main_cross_pos();
+            self.set_main_cross_pos(pos_main + offset, pos_cross);
+        }
+    }
+
+    /// Returns the size of the area covered by placed widgets, from the top left corner of the placer.
+    fn used_size(&self) -> Size {
+        self.used
     }
 
     #[allow(unused)]
@@ -175,11 +493,13 @@ impl Placer {
         )
     }
 
-    /// Advances to the next row, setting the initial row height to the provided `height` parameter.
+    /// Advances to the next row (or column in column direction), setting the initial row height
+    /// (or column width) to the provided `height` parameter.
     fn new_row(&mut self, height: u32) {
         self.row += 1;
         self.col = 0;
-        self.pos = Point::new(0, self.pos.y + self.row_height as i32);
+        let (_, pos_cross) = self.main_cross_pos();
+        self.set_main_cross_pos(0, pos_cross + self.row_height);
         self.row_height = height;
     }
 
@@ -192,11 +512,6 @@ impl Placer {
     fn expand_row_height(&mut self, height: u32) {
         self.row_height = max(self.row_height, height);
     }
-
-    /// Check whether a size is in bounds of the widget (<= widget_size)
-    fn check_bounds(&self, pos: Size) -> bool {
-        pos.width <= self.bounds.width && pos.height <= self.bounds.height
-    }
 }
 
 /// Struct that manages drawing to a [DrawTarget], with optional [WidgetFramebuf] for more efficient drawing.
@@ -207,6 +522,10 @@ struct Painter<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> {
     target: &'a mut DRAW,
     buffer_raw: Option<UnsafeCell<&'a mut [COL]>>,
     framebuf: Option<WidgetFramebuf<'a, COL>>,
+    /// Area outside of which nothing is drawn (e.g. the viewport of a scroll area)
+    clip: Option<Rectangle>,
+    /// Areas drawn to in this frame, if dirty regions are tracked
+    dirty: Option<&'a mut dyn RegionList>,
 }
 
 impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW> {
@@ -216,6 +535,68 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
             target,
             buffer_raw: None,
             framebuf: None,
+            clip: None,
+            dirty: None,
+        }
+    }
+
+    /// Restricts all subsequent drawing operations to the given area.
+    ///
+    /// If a clipping area is already set, the new clipping area is the intersection of both.
+    fn set_clip(&mut self, clip: Rectangle) {
+        self.clip = Some(match self.clip {
+            Some(prev) => prev.intersection(&clip),
+            None => clip,
+        });
+    }
+
+    /// Returns whether anything drawn in the given area would be visible (i.e. not clipped).
+    fn is_visible(&self, area: &Rectangle) -> bool {
+        match self.clip {
+            Some(clip) => !clip.intersection(area).is_zero_sized(),
+            None => true,
+        }
+    }
+
+    /// Draws the given [Drawable] directly to the [DrawTarget], bypassing the framebuffer,
+    /// but respecting the clipping area.
+    fn draw_unbuffered<OUT>(
+        &mut self,
+        item: &impl Drawable<Color = COL, Output = OUT>,
+    ) -> Result<OUT, DRAW::Error> {
+        if self.dirty.is_none() {
+            return match self.clip {
+                Some(clip) => item.draw(&mut self.target.clipped(&clip)),
+                None => item.draw(self.target),
+            };
+        }
+
+        // record the drawn area
+        let (res, area) = match self.clip {
+            Some(clip) => {
+                let mut clipped = self.target.clipped(&clip);
+                let mut tracked = Tracked::new(&mut clipped);
+                (item.draw(&mut tracked), tracked.area)
+            }
+            None => {
+                let mut tracked = Tracked::new(&mut *self.target);
+                (item.draw(&mut tracked), tracked.area)
+            }
+        };
+        if let Some(area) = area {
+            self.mark_dirty(area);
+        }
+        res
+    }
+
+    /// Adds the visible part of the given area to the dirty regions (if they are tracked).
+    fn mark_dirty(&mut self, area: Rectangle) {
+        let mut area = area.intersection(&self.target.bounding_box());
+        if let Some(clip) = self.clip {
+            area = area.intersection(&clip);
+        }
+        if let Some(dirty) = self.dirty.as_deref_mut() {
+            dirty.add(area);
         }
     }
 
@@ -247,6 +628,11 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
         }
     }
 
+    /// Returns the length of the drawing buffer (if there is one).
+    fn buffer_len(&mut self) -> Option<usize> {
+        self.buffer_raw.as_mut().map(|buf| buf.get_mut().len())
+    }
+
     /// Clear the buffer, if it's available.
     ///
     /// ## Returns
@@ -269,10 +655,13 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
     /// If a framebuffer is provided and of sufficient size, it gets used for subsequent drawing operations.
     /// If not, the drawing operations are performed directly on the [DrawTarget].
     fn finalize(&mut self) -> GuiResult<()> {
-        if let Some(buf) = &mut self.framebuf {
-            buf.draw(self.target)
-                .map_err(|_| GuiError::draw_error("Failed to draw framebuf"))?;
-            self.framebuf = None;
+        if let Some(buf) = self.framebuf.take() {
+            match self.clip {
+                Some(clip) => buf.draw(&mut self.target.clipped(&clip)),
+                None => buf.draw(self.target),
+            }
+            .map_err(|_| GuiError::draw_error("Failed to draw framebuf"))?;
+            self.mark_dirty(buf.bounding_box());
         }
         Ok(())
     }
@@ -290,41 +679,11 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
             item.draw(buffer)
                 .ok() /* cannot fail */;
         } else {
-            item.draw(self.target)
+            self.draw_unbuffered(item)
                 .map_err(|_| GuiError::draw_error("Failed to draw item"))?;
         }
         Ok(())
     }
-
-    /// Creates a `Subpainter`, a new [Painter] instance, executes the provided closure with the sub-painter, and returns the result.
-    ///
-    /// This method is useful for creating a temporary [Painter] instance that can be modified for a subset of drawing operations on the main [DrawTarget].
-    /// The sub-painter's [DrawTarget] is a reference to the main [DrawTarget], so any drawing operations performed on the sub-painter will
-    /// be reflected in the main [DrawTarget].
-    ///
-    /// If the main [Painter] instance has a raw buffer set, the sub-painter will inherit that buffer.
-    ///
-    /// ## Panics
-    ///
-    /// Panics if the main [Painter] instance is currently using its framebuffer, as sub-painters cannot be created when the framebuffer is in use.
-    /// Make sure to call [Painter::finalize()] before creating a sub-painter to prevent this.
-    fn with_subpainter<'b, F>(&'b mut self, f: F) -> GuiResult<()>
-    where
-        F: FnOnce(Painter<'b, COL, DRAW>) -> GuiResult<()>,
-    {
-        let target: &'b mut DRAW = self.target;
-        let mut subpainter = Painter::new(target);
-
-        if self.framebuf.is_some() {
-            panic!("Cannot create subpainter when framebuf is in use!");
-        }
-
-        if let Some(buf) = &mut self.buffer_raw {
-            subpainter.set_buffer(unsafe { *buf.get() });
-        }
-        (f)(subpainter)?;
-        Ok(())
-    }
 }
 
 // Basic Implementations for DrawTarget and Dimensions to allow Painter to be used as its inner DrawTarget
@@ -348,7 +707,22 @@ impl<COL: PixelColor, DRAW: DrawTarget<Color = COL, Error = ERR>, ERR> DrawTarge
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        self.target.draw_iter(pixels)
+        if self.dirty.is_none() {
+            return match self.clip {
+                Some(clip) => self.target.clipped(&clip).draw_iter(pixels),
+                None => self.target.draw_iter(pixels),
+            };
+        }
+
+        let mut tracked = Tracked::new(&mut *self.target);
+        let res = match self.clip {
+            Some(clip) => tracked.clipped(&clip).draw_iter(pixels),
+            None => tracked.draw_iter(pixels),
+        };
+        if let Some(area) = tracked.area {
+            self.mark_dirty(area);
+        }
+        res
     }
 }
 
@@ -389,9 +763,36 @@ where
     style: Style<COL>,
     placer: Placer,
     interact: Interaction,
+    /// Transform applied to the points of new interactions (e.g. touch calibration)
+    transform: Option<InteractionTransform>,
     /// Whether the UI was background-cleared this frame
     cleared: bool,
+    /// Whether the UI is in a measuring pass (placing widgets without drawing them)
+    measuring: bool,
+    /// Whether a widget is being added (see [Ui::add_raw])
+    in_widget: bool,
+    /// Area that was allocated last in a measuring pass
+    measured_area: Rectangle,
+    /// Whether widgets react to interactions (see [Ui::enabled])
+    enabled: bool,
     debug_color: Option<COL>,
+    /// Focus state for focus-based navigation, shared with all sub-[Ui]s
+    focus: Option<&'a FocusState>,
+    /// Gesture state, shared with all sub-[Ui]s
+    gestures: Option<&'a GestureState>,
+    /// Gesture recognized in this frame, if it isn't captured (e.g. by a popup)
+    gesture: Option<Gesture>,
+    /// Pointer capture state, shared with all sub-[Ui]s
+    capture: Option<&'a PointerCapture>,
+    /// Offset of the content by the enclosing [ScrollArea]s, which the pointer capture
+    /// compensates (see [PointerCapture::owner])
+    scroll_offset: Point,
+    /// Overlay state for popups, shared with all sub-[Ui]s
+    overlay_state: Option<&'a OverlayState>,
+    /// Popup to draw on top of all widgets at the end of the frame
+    overlay: Option<ListPopup<'a>>,
+    /// Whether the content of a modal closed it (see [Ui::close_modal])
+    closing: bool,
 }
 
 // -- Getter methods for [Ui] --
@@ -514,8 +915,21 @@ where
             style,
             placer,
             interact: Interaction::None,
+            transform: None,
             cleared: false,
+            measuring: false,
+            in_widget: false,
+            measured_area: Rectangle::zero(),
+            enabled: true,
             debug_color: None,
+            focus: None,
+            gestures: None,
+            gesture: None,
+            capture: None,
+            scroll_offset: Point::zero(),
+            overlay_state: None,
+            overlay: None,
+            closing: false,
         }
     }
 
@@ -553,6 +967,11 @@ where
     ///
     /// This interaction is used to update the state of widgets.
     ///
+    /// Call this once per frame on the top-level [Ui], after setting the per-frame states
+    /// (e.g. [Ui::set_focus_state], [Ui::set_gesture_state], [Ui::set_pointer_capture] and
+    /// [Ui::set_overlay_state]). They are all updated here, focus navigation and gestures with
+    /// the interaction the widgets receive (i.e. after an open popup captured the pointer).
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -572,7 +991,311 @@ where
     /// ui.interact(Interaction::Click(Point::new(10, 10)));
     /// ```
     pub fn interact(&mut self, interaction: Interaction) {
-        self.interact = interaction;
+        let interaction = match self.transform {
+            Some(transform) => transform.transform(interaction),
+            None => interaction,
+        };
+        self.interact = match self.overlay_state {
+            Some(overlay) => overlay.capture(interaction),
+            None => interaction,
+        };
+        if let Some(focus) = self.focus {
+            focus.navigate(self.interact);
+        }
+        if let Some(gestures) = self.gestures {
+            gestures.feed(self.interact);
+        }
+        if let Some(capture) = self.capture {
+            // the content of popups and modals is routed through the pointer capture as well
+            capture.track(interaction);
+        }
+        self.update_gesture();
+    }
+
+    /// Sets the [InteractionTransform] that is applied to the points of the interactions passed to
+    /// [Ui::interact], e.g. to map raw touch coordinates to display coordinates.
+    ///
+    /// Call this once per frame on the top-level [Ui], before [Ui::interact].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::Interaction;
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let stored = [0; 6];
+    /// use kolibri_embedded_gui::interaction::InteractionTransform;
+    ///
+    /// // e.g. loaded from flash after a calibration
+    /// let calibration = InteractionTransform::from_coefficients(stored);
+    /// // in the drawing loop:
+    /// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_interaction_transform(calibration);
+    /// // raw ADC values of the touch controller
+    /// ui.interact(Interaction::Click(Point::new(2048, 1800)));
+    /// ```
+    pub fn set_interaction_transform(&mut self, transform: InteractionTransform) {
+        self.transform = Some(transform);
+    }
+
+    /// Sets the [FocusState] used for focus-based navigation (e.g. with a rotary encoder).
+    ///
+    /// Call this once per frame on the top-level [Ui], before [Ui::interact].
+    /// Focusable widgets are then focused with [Interaction::Next] and [Interaction::Previous],
+    /// and activated with [Interaction::Activate]. See [crate::focus] for details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::Interaction;
+    /// # use kolibri_embedded_gui::focus::FocusState;
+    /// # use kolibri_embedded_gui::widgets::Button;
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// let focus = FocusState::new();
+    /// // in the drawing loop:
+    /// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_focus_state(&focus);
+    /// ui.interact(Interaction::Next);
+    /// ui.add(Button::new("Focused"));
+    /// ```
+    pub fn set_focus_state(&mut self, focus: &'a FocusState) {
+        focus.start_frame();
+        self.focus = Some(focus);
+    }
+
+    /// Sets the [GestureState] used to recognize gestures (long-presses, double-taps and swipes),
+    /// with the time of the current frame in milliseconds (see [crate::gesture]).
+    ///
+    /// Call this once per frame on the top-level [Ui], before [Ui::interact]. The gestures
+    /// are then reported on the [Response] of the widget they started on, and swipes by
+    /// [Ui::swiped].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::Interaction;
+    /// # use kolibri_embedded_gui::gesture::GestureState;
+    /// # use kolibri_embedded_gui::widgets::Button;
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let millis = 0;
+    /// let gestures = GestureState::new();
+    /// // in the drawing loop:
+    /// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_gesture_state(&gestures, millis);
+    /// ui.interact(Interaction::Release(Point::new(10, 10)));
+    /// if ui.add(Button::new("Reset")).double_clicked() {
+    ///     // reset
+    /// }
+    /// ```
+    pub fn set_gesture_state(&mut self, gestures: &'a GestureState, now_ms: u32) {
+        gestures.start_frame(now_ms);
+        self.gestures = Some(gestures);
+    }
+
+    /// Takes the gesture of this frame from the [GestureState], unless an open popup captures
+    /// the pointer.
+    ///
+    /// The release that ends a long-press is hidden from the widgets, so that it isn't reported
+    /// as a click as well.
+    fn update_gesture(&mut self) {
+        let captured = self.overlay_state.is_some_and(|overlay| overlay.is_open());
+        self.gesture = self
+            .gestures
+            .and_then(|gestures| gestures.gesture())
+            .filter(|_| !captured);
+        if matches!(self.interact, Interaction::Release(_))
+            && self
+                .gestures
+                .is_some_and(|gestures| gestures.long_press_released())
+        {
+            self.interact = Interaction::None;
+        }
+    }
+
+    /// Returns the direction of the swipe recognized in this frame, if any (e.g. to switch
+    /// pages). See [Ui::set_gesture_state].
+    pub fn swiped(&self) -> Option<SwipeDirection> {
+        match self.gesture {
+            Some(Gesture::Swipe(direction, _)) => Some(direction),
+            _ => None,
+        }
+    }
+
+    /// Adds the widgets of `f` disabled, if `enabled` is `false` (e.g. settings that can't be
+    /// changed while a machine is running).
+    ///
+    /// Disabled widgets are drawn with the disabled colors of the style (see [Style::disabled]),
+    /// don't receive any interactions, and can't be focused. Single widgets can also be disabled
+    /// with their `enabled` builder, e.g. [Button::enabled](crate::widgets::Button::enabled).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::widgets::{Button, Checkbox};
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// let running = true;
+    /// let mut fast = false;
+    /// ui.enabled(!running, |ui| {
+    ///     ui.add(Checkbox::new(&mut fast));
+    ///     ui.add(Button::new("Start"));
+    /// });
+    /// ```
+    pub fn enabled<R>(&mut self, enabled: bool, f: impl FnOnce(&mut Self) -> R) -> R {
+        if enabled || !self.enabled {
+            return f(self);
+        }
+
+        let style = self.style;
+        let interact = core::mem::replace(&mut self.interact, Interaction::None);
+        let gesture = self.gesture.take();
+        let focus = self.focus.take();
+        self.style = style.disabled();
+        self.enabled = false;
+
+        let res = f(self);
+
+        self.style = style;
+        self.interact = interact;
+        self.gesture = gesture;
+        self.focus = focus;
+        self.enabled = true;
+        res
+    }
+
+    /// Adds the widgets of `f` with a different style, e.g. a section of a settings screen that
+    /// uses a larger font. The style of the [Ui] is restored afterwards, even if `f` returns early
+    /// with an error.
+    ///
+    /// To only override parts of the style of a single widget, use [Ui::add_styled].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::{medsize_rgb565_style, medsize_light_rgb565_style};
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::widgets::{Button, Label};
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.with_style(medsize_light_rgb565_style(), |ui| {
+    ///     ui.add(Label::new("Light section"));
+    ///     ui.add(Button::new("Reset"));
+    /// });
+    /// ```
+    pub fn with_style<R>(&mut self, style: Style<COL>, f: impl FnOnce(&mut Self) -> R) -> R {
+        // disabled widgets stay disabled (see Ui::enabled)
+        let style = if self.enabled {
+            style
+        } else {
+            style.disabled()
+        };
+        let previous = core::mem::replace(&mut self.style, style);
+        let res = f(self);
+        self.style = previous;
+        res
+    }
+
+    /// Adds a widget with parts of the [Style] overridden (e.g. a red "E-STOP" button), while the
+    /// rest is inherited from the style of the [Ui] (see [Ui::with_style]).
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] indicating the result of adding the widget (see [Ui::add]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// use kolibri_embedded_gui::style::StyleOverride;
+    /// use kolibri_embedded_gui::widgets::Button;
+    ///
+    /// let stop = StyleOverride::new()
+    ///     .item_background_color(Rgb565::RED)
+    ///     .highlight_item_background_color(Rgb565::CSS_DARK_RED)
+    ///     .text_color(Rgb565::WHITE);
+    /// if ui.add_styled(stop, Button::new("E-STOP")).clicked() {
+    ///     // stop the machine
+    /// }
+    /// ```
+    pub fn add_styled(&mut self, style: StyleOverride<COL>, widget: impl Widget) -> Response {
+        let style = style.apply(self.style);
+        self.with_style(style, |ui| ui.add(widget))
+    }
+
+    /// Draws a widget that can also be disabled on its own, with its `enabled` builder (e.g.
+    /// [Button::enabled](crate::widgets::Button::enabled)).
+    ///
+    /// `draw` draws the widget as in [Ui::enabled]. While the widget is disabled (by itself or
+    /// by the [Ui]), the disabled bit of its smartstates is toggled (see
+    /// [Smartstate::toggle_disabled]), so that enabling or disabling it triggers a redraw.
+    ///
+    /// The smartstates are also forced to redraw if the background was cleared (see
+    /// [Ui::force_redraw_if_cleared]).
+    pub fn draw_enabled<W, R>(
+        &mut self,
+        widget: &mut W,
+        enabled: bool,
+        smartstates: impl Fn(&mut W) -> &mut [Smartstate],
+        draw: impl FnOnce(&mut W, &mut Self) -> R,
+    ) -> R {
+        self.force_redraw_if_cleared(smartstates(widget));
+        let disabled = !(enabled && self.enabled);
+        if disabled {
+            smartstates(widget)
+                .iter_mut()
+                .for_each(Smartstate::toggle_disabled);
+        }
+        let res = self.enabled(enabled, |ui| draw(widget, ui));
+        if disabled {
+            smartstates(widget)
+                .iter_mut()
+                .for_each(Smartstate::toggle_disabled);
+        }
+        res
+    }
+
+    /// Forces the given smartstates of a widget to redraw if the background was cleared in this
+    /// frame (see [Ui::cleared]), e.g. after a popup or modal closed, or when a
+    /// [ScrollArea] was scrolled.
+    ///
+    /// Call this in the widget's `draw` before comparing its smartstates.
+    pub fn force_redraw_if_cleared(&self, smartstates: &mut [Smartstate]) {
+        if self.cleared && !self.measuring {
+            smartstates.iter_mut().for_each(Smartstate::force_redraw);
+        }
+    }
+
+    /// Returns whether widgets added to the [Ui] are enabled (see [Ui::enabled]).
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
     }
 
     /// Adds a widget to the [Ui] and, if requested, clears the remaining horizontal space in the current row.
@@ -640,7 +1363,11 @@ where
     /// ```
     pub fn add(&mut self, widget: impl Widget) -> Response {
         let resp = self.add_raw(widget).unwrap_or_else(Response::from_error);
-        self.new_row();
+        match self.placer.direction {
+            Direction::Row => self.new_row(),
+            // in a column, the next widget is placed below anyway
+            Direction::Column => self.add_item_spacing(),
+        }
         resp
     }
 
@@ -709,10 +1436,16 @@ where
     /// ```
     pub fn add_horizontal(&mut self, widget: impl Widget) -> Response {
         let resp = self.add_raw(widget).unwrap_or_else(Response::from_error);
+        self.add_item_spacing();
+        resp
+    }
+
+    /// Allocates the style's item spacing after a widget in the current row (or column).
+    fn add_item_spacing(&mut self) {
         // Allocate space between widgets; ignore space errors.
-        self.allocate_space_no_wrap(self.style().spacing.item_spacing)
+        self.placer
+            .next_spacing(self.style().spacing.item_spacing)
             .ok();
-        resp
     }
 
     /// Draws a widget directly to the [Ui] without changing the layout.
@@ -746,7 +1479,21 @@ where
     /// }
     /// ```
     pub fn add_raw(&mut self, mut widget: impl Widget) -> GuiResult<Response> {
+        let in_widget = core::mem::replace(&mut self.in_widget, true);
         let res = widget.draw(self);
+        self.in_widget = in_widget;
+
+        let res = match res {
+            // the widget returned right after it was placed, without changing any state
+            Err(GuiError::Measuring) => Ok(Response::new(InternalResponse::new(
+                self.measured_area,
+                Interaction::None,
+            ))),
+            res => res.map(|res| {
+                let gesture = self.check_gesture(res.internal.area);
+                res.set_gesture(gesture)
+            }),
+        };
         if let (Ok(res), Some(debug_color)) = (&res, self.debug_color) {
             res.internal
                 .area
@@ -824,6 +1571,7 @@ where
     /// Advances the layout to a new row in the [Ui].
     ///
     /// This method uses the default spacing and widget height from the current style.
+    /// In a vertical layout (see [Ui::vertical]), this starts a new column instead.
     ///
     /// # Example
     ///
@@ -845,8 +1593,16 @@ where
     /// ui.new_row();
     /// ```
     pub fn new_row(&mut self) {
-        self.new_row_raw(self.style().spacing.item_spacing.height);
-        self.new_row_raw(self.style().default_widget_height);
+        match self.placer.direction {
+            Direction::Row => {
+                self.new_row_raw(self.style().spacing.item_spacing.height);
+                self.new_row_raw(self.style().default_widget_height);
+            }
+            Direction::Column => {
+                self.new_row_raw(self.style().spacing.item_spacing.width);
+                self.new_row_raw(0);
+            }
+        }
     }
 
     /// Advances the layout to a new row in the [Ui] with the specified height.
@@ -905,6 +1661,92 @@ where
         self.placer.expand_row_height(height);
     }
 
+    /// Sets the alignment for subsequently placed widgets.
+    ///
+    /// Each widget is aligned on its own in the remaining space of its row, which works
+    /// best for rows that contain a single widget. Vertical alignment needs to know the
+    /// height of all content in advance, and therefore only has an effect in [Ui::with_align].
+    ///
+    /// To align rows containing multiple widgets as a whole, use [Ui::with_align].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_align(Align(HorizontalAlign::Right, VerticalAlign::Top));
+    /// ui.add(Label::new("Right-aligned"));
+    /// ```
+    pub fn set_align(&mut self, align: Align) {
+        self.placer.set_align(align);
+    }
+
+    /// Returns the alignment currently used for placing widgets.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// let align = ui.get_align();
+    /// assert_eq!(align, Align::default());
+    /// ```
+    pub fn get_align(&self) -> Align {
+        self.placer.align
+    }
+
+    /// Returns whether the [Ui] is currently in a measuring pass of [Ui::with_align].
+    ///
+    /// While measuring, widgets are placed but not drawn, and their space allocation returns
+    /// [GuiError::Measuring]. Code with side effects inside [Ui::with_align] can use this
+    /// to only run once per frame.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// if !ui.is_measuring() {
+    ///     println!("drawing");
+    /// }
+    /// ```
+    pub fn is_measuring(&self) -> bool {
+        self.measuring
+    }
+
     /// Draws a [Drawable] item directly using the [Ui]'s underlying draw target.
     ///
     /// ## Returns
@@ -938,7 +1780,7 @@ where
         &mut self,
         to_draw: &impl Drawable<Color = COL, Output = OUT>,
     ) -> Result<OUT, DRAW::Error> {
-        to_draw.draw(self.painter.target)
+        self.painter.draw_unbuffered(to_draw)
     }
 
     /// Returns the remaining available space for widget placement in the [Ui].
@@ -998,7 +1840,9 @@ where
     /// let interaction = ui.check_interact(some_rectangle);
     /// ```
     pub fn check_interact(&self, area: Rectangle) -> Interaction {
-        if self
+        if let Some(interaction) = self.captured_interaction(area) {
+            interaction
+        } else if self
             .interact
             .get_point()
             .map(|pt| area.contains(pt))
@@ -1010,6 +1854,17 @@ where
         }
     }
 
+    /// Checks whether the gesture of this frame (see [Ui::set_gesture_state]) started in the
+    /// given area.
+    ///
+    /// ## Returns
+    ///
+    /// The [Gesture] if it started in the area, otherwise `None`.
+    pub fn check_gesture(&self, area: Rectangle) -> Option<Gesture> {
+        self.gesture
+            .filter(|gesture| area.contains(gesture.point()))
+    }
+
     /// Allocates an exact space in the [Ui] for a widget of the desired size.
     ///
     /// This method currently wraps [Ui::allocate_space] without extra logic.
@@ -1041,13 +1896,118 @@ where
         self.allocate_space(desired_size)
     }
 
-    /// Allocates space in the [Ui] for a widget of the desired size, with wrapping if needed.
+    /// Allocates space in the [Ui] for a widget of the desired size, with wrapping if needed.
+    ///
+    /// The allocated area is adjusted by the [Ui]'s bounds.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// let allocation = ui.allocate_space(Size::new(100, 40));
+    /// ```
+    pub fn allocate_space(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
+        let rect = self.placer.next(desired_size).map(|mut rect| {
+            rect.top_left.add_assign(self.bounds.top_left);
+            rect
+        })?;
+        if self.measuring {
+            return self.measured(rect);
+        }
+        let inter = self.check_interact(rect);
+
+        Ok(InternalResponse {
+            area: rect,
+            interaction: inter,
+        })
+    }
+
+    /// Handles the allocation of the given area in a measuring pass.
+    ///
+    /// Widgets return early (with [GuiError::Measuring], which [Ui::add_raw] turns into a
+    /// response), so that their state (e.g. smartstates) isn't changed. Outside of widgets, the
+    /// area is returned without any interaction, so that the measuring pass continues.
+    fn measured(&mut self, area: Rectangle) -> GuiResult<InternalResponse> {
+        self.measured_area = area;
+        if self.in_widget {
+            Err(GuiError::Measuring)
+        } else {
+            Ok(InternalResponse::new(area, Interaction::None))
+        }
+    }
+
+    /// Allocates space in the [Ui] for a widget of the desired size without wrapping.
+    ///
+    /// The allocated area is adjusted by the [Ui]'s bounds.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// let allocation = ui.allocate_space_no_wrap(Size::new(80, 25));
+    /// ```
+    pub fn allocate_space_no_wrap(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
+        let area = self.placer.next_no_wrap(desired_size).map(|mut rect| {
+            rect.top_left.add_assign(self.bounds.top_left);
+            rect
+        })?;
+        if self.measuring {
+            return self.measured(area);
+        }
+        let inter = self.check_interact(area);
+
+        Ok(InternalResponse {
+            area,
+            interaction: inter,
+        })
+    }
+
+    /// Registers a focusable widget with the [FocusState] of the [Ui] (if set), and returns
+    /// its [Focus] for this frame.
     ///
-    /// The allocated area is adjusted by the [Ui]'s bounds.
+    /// Widgets must register before allocating their space, so that the registration order
+    /// doesn't change if a widget is not visible (e.g. in a [ScrollArea]). Nothing is registered
+    /// while measuring.
+    ///
+    /// `adjustable` widgets (e.g. sliders) toggle their editing mode when activated.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
+    /// The [Focus] of the widget.
     ///
     /// # Example
     ///
@@ -1065,25 +2025,24 @@ where
     /// # let output_settings = OutputSettingsBuilder::new().build();
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
-    /// # let mut widget = Label::new("Hi");
-    /// let allocation = ui.allocate_space(Size::new(100, 40));
+    /// let focus = ui.register_focus(true);
+    /// let iresponse = ui.allocate_space(Size::new(100, 20)).unwrap();
+    /// if focus.adjust != 0 {
+    ///     // change the value
+    /// }
     /// ```
-    pub fn allocate_space(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
-        let rect = self.placer.next(desired_size).map(|mut rect| {
-            rect.top_left.add_assign(self.bounds.top_left);
-            rect
-        })?;
-        let inter = self.check_interact(rect);
-
-        Ok(InternalResponse {
-            area: rect,
-            interaction: inter,
-        })
+    pub fn register_focus(&mut self, adjustable: bool) -> Focus {
+        match self.focus {
+            Some(focus) if !self.measuring => focus.register(self.interact, adjustable),
+            _ => Focus::default(),
+        }
     }
 
-    /// Allocates space in the [Ui] for a widget of the desired size without wrapping.
+    /// Allocates space for a focusable widget of the desired size, like [Ui::allocate_space].
     ///
-    /// The allocated area is adjusted by the [Ui]'s bounds.
+    /// The widget is registered as focusable (see [Ui::register_focus]), and its focus is
+    /// mapped to the interaction: a focused widget is hovered, and an activated widget is
+    /// released (i.e. clicked).
     ///
     /// ## Returns
     ///
@@ -1105,21 +2064,14 @@ where
     /// # let output_settings = OutputSettingsBuilder::new().build();
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
-    /// # let mut widget = Label::new("Hi");
-    /// let allocation = ui.allocate_space_no_wrap(Size::new(80, 25));
+    /// let allocation = ui.allocate_focusable_space(Size::new(100, 40));
     /// ```
-    pub fn allocate_space_no_wrap(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
-        let area = self.placer.next_no_wrap(desired_size).map(|mut rect| {
-            rect.top_left.add_assign(self.bounds.top_left);
-            rect
-        })?;
-
-        let inter = self.check_interact(area);
-
-        Ok(InternalResponse {
-            area,
-            interaction: inter,
-        })
+    pub fn allocate_focusable_space(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
+        let focus = self.register_focus(false);
+        let mut iresponse = self.allocate_space(desired_size)?;
+        iresponse.interaction =
+            focus.as_pointer_interaction(&iresponse.area, iresponse.interaction);
+        Ok(iresponse)
     }
 
     /// Returns the current row height used in the [Ui]'s layout.
@@ -1362,6 +2314,9 @@ where
     /// ui.clear_background().unwrap();
     /// ```
     pub fn clear_background(&mut self) -> GuiResult<()> {
+        if self.measuring {
+            return Ok(());
+        }
         self.cleared = true;
 
         // clear background
@@ -1374,13 +2329,15 @@ where
                 .size
                 .saturating_add(self.style.spacing.window_border_padding * 2),
         );
+        self.paint_below_overlay(&real_bg);
 
-        real_bg
-            .draw_styled(
-                &PrimitiveStyleBuilder::new()
-                    .fill_color(self.style.background_color)
-                    .build(),
-                self.painter.target,
+        self.painter
+            .draw_unbuffered(
+                &real_bg.into_styled(
+                    PrimitiveStyleBuilder::new()
+                        .fill_color(self.style.background_color)
+                        .build(),
+                ),
             )
             .map_err(|_| GuiError::DrawError(Some("Couldn't clear GUI Background")))
     }
@@ -1449,6 +2406,10 @@ where
     /// ui.start_drawing(&draw_area);
     /// ```
     pub fn start_drawing(&mut self, area: &Rectangle) {
+        if self.measuring {
+            return;
+        }
+        self.paint_below_overlay(area);
         self.painter.start_drawing(area);
         self.painter.clear_buffer(self.style.background_color);
     }
@@ -1514,6 +2475,83 @@ where
         self.painter.finalize()
     }
 
+    /// Paints a widget in the given area: `paint` draws the widget (e.g. with [Ui::draw]), like
+    /// between [Ui::start_drawing] and [Ui::finalize].
+    ///
+    /// If the drawing buffer (see [Ui::set_buffer]) is too small for the whole area, but holds at
+    /// least one line of it, the area is painted in horizontal bands: `paint` is called once per
+    /// band, with drawing clipped to the band, and each band is flushed from the buffer. This way,
+    /// even large widgets are drawn flicker-free with a small buffer. Without a buffer (or if it
+    /// can't hold a single line), `paint` is called once and draws directly.
+    ///
+    /// As `paint` may be called multiple times, it should only draw, and not process any
+    /// interaction or change any state. If the area is entirely clipped (e.g. outside of the
+    /// viewport of a [ScrollArea]), `paint` isn't called at all.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// // a buffer of 10 lines is enough for the whole panel
+    /// let mut buffer = [Rgb565::BLACK; 320 * 10];
+    /// ui.set_buffer(&mut buffer);
+    ///
+    /// let panel = Rectangle::new(Point::new(0, 0), Size::new(320, 200));
+    /// ui.paint(&panel, |ui| {
+    ///     ui.draw(&panel.into_styled(PrimitiveStyle::with_stroke(Rgb565::WHITE, 2)))
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn paint(
+        &mut self,
+        area: &Rectangle,
+        mut paint: impl FnMut(&mut Self) -> GuiResult<()>,
+    ) -> GuiResult<()> {
+        if self.measuring || !self.painter.is_visible(area) {
+            return Ok(());
+        }
+
+        let band_height = match self.painter.buffer_len() {
+            Some(len) if area.size.width > 0 => (len / area.size.width as usize) as u32,
+            _ => 0,
+        };
+        if band_height == 0 || band_height >= area.size.height {
+            self.start_drawing(area);
+            let res = paint(self);
+            return res.and(self.finalize());
+        }
+
+        let clip = self.painter.clip;
+        let mut res = Ok(());
+        let mut y = 0;
+        while y < area.size.height && res.is_ok() {
+            let band = Rectangle::new(
+                area.top_left + Point::new(0, y as i32),
+                Size::new(area.size.width, min(band_height, area.size.height - y)),
+            );
+            y += band.size.height;
+
+            self.painter.set_clip(band);
+            if self.painter.is_visible(&band) {
+                self.start_drawing(&band);
+                res = paint(self).and(self.finalize());
+            }
+            self.painter.clip = clip;
+        }
+        res
+    }
+
     /// Draws a [Drawable] item onto the [Ui].
     ///
     /// If a buffer is active, the item is drawn to the buffer; otherwise, it is drawn directly.
@@ -1546,6 +2584,9 @@ where
     /// ui.draw(&rectangle).unwrap();
     /// ```
     pub fn draw(&mut self, item: &impl Drawable<Color = COL>) -> GuiResult<()> {
+        if self.measuring {
+            return Ok(());
+        }
         self.painter.draw(item)
     }
 }
@@ -1607,20 +2648,7 @@ where
             Align(HorizontalAlign::Left, VerticalAlign::Top),
         );
 
-        self.painter.with_subpainter(|painter| {
-            let mut sub_ui = Ui {
-                painter,
-                bounds,
-                style: self.style,
-                interact: self.interact,
-                placer,
-                cleared: false,
-                debug_color: self.debug_color,
-            };
-            (f)(&mut sub_ui)
-        })?;
-
-        Ok(())
+        self.child_ui(bounds, placer, f).0
     }
 
     /// Creates a sub-[Ui] that shares the same bounds as the parent [Ui].
@@ -1657,22 +2685,283 @@ where
     where
         F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
     {
-        self.painter.with_subpainter(|painter| {
-            let mut sub_ui = Ui {
-                painter,
-                bounds: self.bounds,
-                style: self.style,
-                interact: self.interact,
-                placer: self.placer.clone(),
-                cleared: false,
-                debug_color: self.debug_color,
-            };
-            let res = (f)(&mut sub_ui);
-            self.placer = sub_ui.placer;
-            res
-        })?;
+        let (res, placer) = self.child_ui(self.bounds, self.placer.clone(), f);
+        self.placer = placer;
+        res
+    }
 
-        Ok(())
+    /// Places the widgets added in the closure with the given alignment, then restores the
+    /// previous alignment.
+    ///
+    /// Rows are aligned as a whole, so all widgets in a centered (or right-aligned) row end up
+    /// centered (or right-aligned) together. The vertical alignment is applied to the block of all
+    /// rows added in the closure, within the remaining space of the [Ui].
+    ///
+    /// To achieve this, the closure is run twice for any alignment other than left / top:
+    /// first in a measuring pass, in which widgets are placed but neither drawn nor interacted
+    /// with, and then in the actual drawing pass. In the measuring pass, widgets return right
+    /// after they were placed, so they don't change any state (including their smartstates),
+    /// and their responses have no interaction. Keep other side effects in the closure to widget
+    /// responses, or check [Ui::is_measuring]. Positional smartstate providers
+    /// (e.g. [crate::smartstate::SmartstateProvider::nxt()]) hand out different smartstates in
+    /// the two passes (the ones of the measuring pass stay untouched), so they need room for both,
+    /// unlike a [SmartstateMap](crate::smartstate::SmartstateMap).
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] with the result of the drawing pass of the closure.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # use kolibri_embedded_gui::widgets::Button;
+    /// ui.with_align(Align(HorizontalAlign::Right, VerticalAlign::Bottom), |ui| {
+    ///     ui.add_horizontal(Button::new("Cancel"));
+    ///     if ui.add_horizontal(Button::new("OK")).clicked() {
+    ///         // ...
+    ///     }
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn with_align<F>(&mut self, align: Align, mut f: F) -> GuiResult<()>
+    where
+        F: FnMut(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let prev_align = self.placer.align;
+        let prev_measured = self.placer.measured.take();
+
+        let res = if self.measuring || align == Align::default() {
+            // alignment is irrelevant while measuring, and no measuring is needed for left / top
+            self.placer.set_align(align);
+            (f)(self)
+        } else {
+            // measuring pass: place all widgets without drawing them
+            let start = self.placer.clone();
+            self.placer.start_measuring();
+            self.measuring = true;
+            // errors other than missing space are reported by the drawing pass
+            (f)(self).ok();
+            self.measuring = false;
+            let measurement = self.placer.finish_measuring();
+
+            // drawing pass: place all widgets again, aligned using the measurement
+            self.placer = start;
+            self.placer.set_align(align);
+            self.placer.apply_measurement(measurement);
+            (f)(self)
+        };
+
+        self.placer.measured = prev_measured;
+        self.placer.set_align(prev_align);
+        res
+    }
+
+    /// Like [Ui::with_align], but aligns the content as a single block of the given size
+    /// (measured beforehand), so the closure is only run once.
+    fn with_block_align<F>(&mut self, align: Align, size: Size, mut f: F) -> GuiResult<()>
+    where
+        F: FnMut(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        if self.measuring || align == Align::default() {
+            return self.with_align(align, f);
+        }
+
+        let prev_align = self.placer.align;
+        let prev_measured = self.placer.measured.take();
+
+        let (main, cross) = self.placer.direction.split(size);
+        let measurement = LineMeasurement::block(self.placer.row, main, cross);
+        self.placer.set_align(align);
+        self.placer.apply_measurement(Some(measurement));
+        let res = (f)(self);
+
+        self.placer.measured = prev_measured;
+        self.placer.set_align(prev_align);
+        res
+    }
+
+    /// Places widgets in a column, from top to bottom, in the remaining space of the current row.
+    ///
+    /// The closure gets a sub-[Ui] in which [Ui::add] and [Ui::add_horizontal] both place widgets
+    /// below each other, and [Ui::new_row] starts a new column. Afterwards, the space actually
+    /// used by the column is allocated in the parent [Ui] (followed by the item spacing), so
+    /// multiple columns can be placed next to each other.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] containing the rectangle used by the column.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # use kolibri_embedded_gui::widgets::{Button, Slider};
+    /// # let (mut a, mut b) = (0i16, 0i16);
+    /// let buttons = ui.vertical(|ui| {
+    ///     ui.add(Button::new("Start"));
+    ///     ui.add(Button::new("Stop"));
+    ///     Ok(())
+    /// }).unwrap();
+    /// ui.vertical(|ui| {
+    ///     ui.add(Slider::new(&mut a, 0..=100));
+    ///     ui.add(Slider::new(&mut b, 0..=100));
+    ///     Ok(())
+    /// }).unwrap();
+    /// println!("Button column size: {:?}", buttons.size);
+    /// ```
+    pub fn vertical<F>(&mut self, f: F) -> GuiResult<Rectangle>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.layout_ui(Direction::Column, f)
+    }
+
+    /// Places widgets in a row, from left to right, in the remaining space of the current row
+    /// (or column).
+    ///
+    /// The closure gets a sub-[Ui] with its own row layout. Afterwards, the space actually used
+    /// is allocated in the parent [Ui] (followed by the item spacing). This is mostly useful
+    /// for placing a row of widgets inside a [Ui::vertical] layout.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] containing the rectangle used by the row.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # use kolibri_embedded_gui::widgets::Button;
+    /// ui.vertical(|ui| {
+    ///     ui.add(Label::new("Volume"));
+    ///     ui.horizontal(|ui| {
+    ///         ui.add_horizontal(Button::new("-"));
+    ///         ui.add_horizontal(Button::new("+"));
+    ///         Ok(())
+    ///     })?;
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn horizontal<F>(&mut self, f: F) -> GuiResult<Rectangle>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.layout_ui(Direction::Row, f)
+    }
+
+    /// Runs the closure on a sub-[Ui] covering the remaining space of the current row, placing
+    /// widgets in the given direction, and allocates the space it used in this [Ui].
+    fn layout_ui<F>(&mut self, direction: Direction, f: F) -> GuiResult<Rectangle>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.placer.align_line_start();
+
+        let bounds = Rectangle::new(
+            self.bounds.top_left + self.placer.pos,
+            self.placer.space_available(),
+        );
+        let placer = Placer::with_direction(bounds.size, true, Align::default(), direction);
+
+        let (res, placer) = self.child_ui(bounds, placer, f);
+        let used = placer.used_size();
+
+        let mut area = self.placer.next_fixed(used)?;
+        area.top_left.add_assign(self.bounds.top_left);
+        self.add_item_spacing();
+
+        res.map(|_| area)
+    }
+
+    /// Runs the closure on a sub-[Ui] with the given (absolute) bounds and placer.
+    ///
+    /// Returns the result of the closure and the sub-[Ui]'s placer after the closure ran.
+    fn child_ui<F>(&mut self, bounds: Rectangle, placer: Placer, f: F) -> (GuiResult<()>, Placer)
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        // the sub-Ui is this Ui with different bounds and placer, so that popups shown in it
+        // can be handed up and drawn at the end of the frame (see Ui::draw_overlay)
+        let bounds = core::mem::replace(&mut self.bounds, bounds);
+        let placer = core::mem::replace(&mut self.placer, placer);
+        // the background of the parent is the background of the sub-Ui
+        let cleared = self.cleared;
+        let overlay = self.overlay.take();
+        let (style, interact, transform, measuring, enabled, debug_color) = (
+            self.style,
+            self.interact,
+            self.transform,
+            self.measuring,
+            self.enabled,
+            self.debug_color,
+        );
+        let (focus, gestures, gesture, capture, scroll_offset, overlay_state, clip) = (
+            self.focus,
+            self.gestures,
+            self.gesture,
+            self.capture,
+            self.scroll_offset,
+            self.overlay_state,
+            self.painter.clip,
+        );
+
+        let res = f(self);
+
+        self.bounds = bounds;
+        let placer = core::mem::replace(&mut self.placer, placer);
+        self.cleared = cleared;
+        self.overlay = self.overlay.take().or(overlay);
+        self.style = style;
+        self.interact = interact;
+        self.transform = transform;
+        self.measuring = measuring;
+        self.enabled = enabled;
+        self.debug_color = debug_color;
+        self.focus = focus;
+        self.gestures = gestures;
+        self.gesture = gesture;
+        self.capture = capture;
+        self.scroll_offset = scroll_offset;
+        self.overlay_state = overlay_state;
+        self.painter.clip = clip;
+        (res, placer)
     }
 
     /// Creates a right-side panel sub-[Ui] with the specified width.
@@ -1863,3 +3152,327 @@ where
         self.debug_color = Some(color);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placer_left_top() {
+        let mut placer = Placer::new(Size::new(100, 50), true, Align::default());
+        let a = placer.next(Size::new(20, 10)).unwrap();
+        let b = placer.next(Size::new(30, 15)).unwrap();
+
+        assert_eq!(a.top_left, Point::new(0, 0));
+        assert_eq!(b.top_left, Point::new(20, 0));
+        assert_eq!(b.size, Size::new(30, 15));
+    }
+
+    #[test]
+    fn test_placer_wrap() {
+        let mut placer = Placer::new(Size::new(50, 50), true, Align::default());
+        placer.next(Size::new(30, 10)).unwrap();
+        let b = placer.next(Size::new(30, 10)).unwrap();
+        assert_eq!(b.top_left, Point::new(0, 10));
+
+        // a wrapped widget must fit into the new row
+        assert_eq!(placer.next(Size::new(30, 35)), Err(GuiError::NoSpaceLeft));
+    }
+
+    #[test]
+    fn test_placer_no_wrap() {
+        let mut placer = Placer::new(Size::new(50, 50), true, Align::default());
+        placer.next(Size::new(30, 10)).unwrap();
+        assert_eq!(
+            placer.next_no_wrap(Size::new(30, 10)),
+            Err(GuiError::NoSpaceLeft)
+        );
+    }
+
+    #[test]
+    fn test_placer_align_single_widget() {
+        let mut placer = Placer::new(
+            Size::new(100, 50),
+            true,
+            Align(HorizontalAlign::Right, VerticalAlign::Top),
+        );
+        let a = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(a.top_left, Point::new(80, 0));
+
+        placer.new_row(10);
+        placer.set_align(Align(HorizontalAlign::Center, VerticalAlign::Top));
+        let b = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(b.top_left, Point::new(40, 10));
+    }
+
+    #[test]
+    fn test_placer_align_measured_rows() {
+        let place_rows = |placer: &mut Placer| {
+            let mut rects = [Rectangle::zero(); 3];
+            rects[0] = placer.next(Size::new(20, 10)).unwrap();
+            placer.next_spacing(Size::new(5, 0)).unwrap();
+            rects[1] = placer.next(Size::new(30, 10)).unwrap();
+            placer.next_spacing(Size::new(5, 0)).unwrap();
+            placer.new_row(10);
+            rects[2] = placer.next(Size::new(40, 10)).unwrap();
+            rects
+        };
+
+        let mut placer = Placer::new(Size::new(100, 50), true, Align::default());
+        let start = placer.clone();
+        placer.start_measuring();
+        place_rows(&mut placer);
+        let measurement = placer.finish_measuring();
+
+        let mut placer = start;
+        placer.set_align(Align(HorizontalAlign::Center, VerticalAlign::Bottom));
+        placer.apply_measurement(measurement);
+        let rects = place_rows(&mut placer);
+
+        // first row: 20 + 5 + 30 = 55 wide (trailing spacing is ignored) => offset 22
+        assert_eq!(rects[0].top_left, Point::new(22, 30));
+        assert_eq!(rects[1].top_left, Point::new(47, 30));
+        // second row: 40 wide => offset 30
+        assert_eq!(rects[2].top_left, Point::new(30, 40));
+    }
+
+    #[test]
+    fn test_placer_column() {
+        let mut placer = Placer::with_direction(
+            Size::new(100, 50),
+            true,
+            Align::default(),
+            Direction::Column,
+        );
+        let a = placer.next(Size::new(20, 30)).unwrap();
+        let b = placer.next(Size::new(40, 10)).unwrap();
+        // does not fit below b => wraps into a new column, next to the widest widget
+        let c = placer.next(Size::new(10, 20)).unwrap();
+
+        assert_eq!(a.top_left, Point::new(0, 0));
+        assert_eq!(b.top_left, Point::new(0, 30));
+        assert_eq!(c.top_left, Point::new(40, 0));
+        assert_eq!(placer.used_size(), Size::new(50, 40));
+    }
+
+    #[test]
+    fn test_paint_in_bands() {
+        use crate::style::medsize_rgb565_style;
+        use embedded_graphics::mock_display::MockDisplay;
+        use embedded_graphics::pixelcolor::Rgb565;
+        use embedded_graphics::primitives::Circle;
+
+        let area = Rectangle::new(Point::new(2, 3), Size::new(40, 20));
+        let paint = |ui: &mut Ui<MockDisplay<Rgb565>, Rgb565>| {
+            ui.draw(&area.into_styled(PrimitiveStyle::with_fill(Rgb565::BLUE)))?;
+            ui.draw(
+                &Circle::new(Point::new(10, 4), 18)
+                    .into_styled(PrimitiveStyle::with_fill(Rgb565::RED)),
+            )
+        };
+
+        // direct drawing
+        let mut expected = MockDisplay::<Rgb565>::new();
+        expected.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut expected, medsize_rgb565_style());
+        ui.paint(&area, paint).unwrap();
+
+        // the buffer only holds 4 lines of the area
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut buffer = [Rgb565::BLACK; 40 * 4 + 3];
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.set_buffer(&mut buffer);
+        let mut bands = 0;
+        ui.paint(&area, |ui| {
+            bands += 1;
+            paint(ui)
+        })
+        .unwrap();
+
+        assert_eq!(bands, 5);
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_disabled_widgets() {
+        use crate::smartstate::Smartstate;
+        use crate::style::medsize_rgb565_style;
+        use crate::testing::{Harness, Script};
+        use crate::widgets::{Button, Checkbox};
+        use embedded_graphics::pixelcolor::Rgb565;
+
+        let style = medsize_rgb565_style();
+        let mut harness = Harness::new(Size::new(64, 64), style);
+        let mut checked = false;
+        let mut smartstate = Smartstate::empty();
+
+        for (enabled, expect_checked) in [(false, false), (true, true), (false, true)] {
+            let mut area = Rectangle::zero();
+            harness.run(
+                Script::new().tap(Point::new(8, 8)),
+                |ui: &mut Ui<_, Rgb565>| {
+                    ui.add(Checkbox::new(&mut checked).enabled(enabled));
+                    area = ui
+                        .add(
+                            Button::new("Go")
+                                .smartstate(&mut smartstate)
+                                .enabled(enabled),
+                        )
+                        .internal
+                        .area;
+                },
+            );
+            assert_eq!(checked, expect_checked);
+
+            // toggling the enabled flag redraws the button, despite its smartstate
+            let fill = match enabled {
+                true => style.item_background_color,
+                false => style.disabled_item_background_color,
+            };
+            let pixel = harness.display().pixel(area.top_left + Point::new(2, 2));
+            assert_eq!(pixel, Some(fill));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_style_overrides() {
+        use crate::style::medsize_rgb565_style;
+        use crate::testing::Harness;
+        use crate::widgets::Button;
+        use embedded_graphics::pixelcolor::Rgb565;
+
+        let style = medsize_rgb565_style();
+        let mut harness = Harness::new(Size::new(64, 64), style);
+        let stop = StyleOverride::new().item_background_color(Rgb565::RED);
+        let fill = |harness: &Harness<_>, area: Rectangle| {
+            harness.display().pixel(area.top_left + Point::new(2, 2))
+        };
+
+        let (mut styled, mut plain) = (Rectangle::zero(), Rectangle::zero());
+        harness.frame(Interaction::None, |ui| {
+            styled = ui.add_styled(stop, Button::new("Stop")).internal.area;
+            plain = ui.add(Button::new("Go")).internal.area;
+        });
+        assert_eq!(fill(&harness, styled), Some(Rgb565::RED));
+        assert_eq!(fill(&harness, plain), Some(style.item_background_color));
+
+        // the style is restored after an early return, and disabled widgets stay disabled
+        harness.frame(Interaction::None, |ui| {
+            let res: GuiResult<()> = ui.with_style(stop.apply(style), |ui| {
+                ui.add(Button::new("Stop"));
+                Err(GuiError::NoSpaceLeft)
+            });
+            assert!(res.is_err());
+            assert_eq!(
+                ui.style().item_background_color,
+                style.item_background_color
+            );
+
+            ui.enabled(false, |ui| {
+                styled = ui.add_styled(stop, Button::new("Stop")).internal.area;
+            });
+        });
+        assert_eq!(
+            fill(&harness, styled),
+            Some(style.disabled_item_background_color)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_long_press_is_no_click() {
+        use crate::gesture::GestureState;
+        use crate::style::medsize_rgb565_style;
+        use crate::testing::TestDisplay;
+        use crate::widgets::Button;
+
+        let style = medsize_rgb565_style();
+        let mut display = TestDisplay::new(Size::new(128, 64), style.background_color);
+        let gestures = GestureState::new();
+        let p = Point::new(10, 10);
+        let mut frame = |now: u32, interaction: Interaction| {
+            let mut ui = Ui::new_fullscreen(&mut display, style);
+            ui.set_gesture_state(&gestures, now);
+            ui.interact(interaction);
+            let res = ui.add(Button::new("Delete"));
+            (res.long_pressed(), res.clicked())
+        };
+
+        assert_eq!(frame(0, Interaction::Click(p)), (false, false));
+        assert_eq!(frame(600, Interaction::Drag(p)), (true, false));
+        assert_eq!(frame(700, Interaction::Release(p)), (false, false));
+
+        // a short press is still a click
+        assert_eq!(frame(1000, Interaction::Click(p)), (false, false));
+        assert_eq!(frame(1100, Interaction::Release(p)), (false, true));
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_gestures_fed_after_overlay_capture() {
+        use crate::gesture::GestureState;
+        use crate::style::medsize_rgb565_style;
+        use crate::testing::TestDisplay;
+        use crate::ui::OverlayState;
+        use crate::widgets::Button;
+
+        let style = medsize_rgb565_style();
+        let mut display = TestDisplay::new(Size::new(128, 64), style.background_color);
+        let (gestures, overlay) = (GestureState::new(), OverlayState::new());
+        let p = Point::new(10, 10);
+        let mut frame = |now: u32, interaction: Interaction, popup: bool| {
+            let mut ui = Ui::new_fullscreen(&mut display, style);
+            ui.set_gesture_state(&gestures, now);
+            ui.set_overlay_state(&overlay);
+            ui.interact(interaction);
+            let res = ui.add(Button::new("Delete"));
+            if popup {
+                overlay.open(Rectangle::new(Point::zero(), Size::new(40, 40)));
+            }
+            res.long_pressed()
+        };
+
+        frame(0, Interaction::None, true);
+        // the press on the popup is captured, and doesn't start a gesture below it
+        assert!(!frame(0, Interaction::Click(p), false));
+        // the popup closed, so this is a new press on the button
+        assert!(!frame(600, Interaction::Drag(p), false));
+        assert!(frame(1200, Interaction::Drag(p), false));
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_with_align_measures_everything() {
+        use crate::style::medsize_rgb565_style;
+        use crate::testing::Harness;
+        use crate::widgets::Button;
+
+        let style = medsize_rgb565_style();
+        let mut harness = Harness::new(Size::new(200, 100), style);
+        let mut smartstate = Smartstate::empty();
+        let (mut runs, mut button, mut space) = (0, Rectangle::zero(), Rectangle::zero());
+        harness.frame(Interaction::None, |ui| {
+            let align = Align(HorizontalAlign::Right, VerticalAlign::Bottom);
+            ui.with_align(align, |ui| {
+                runs += 1;
+                let res = ui.add_raw(Button::new("Button").smartstate(&mut smartstate))?;
+                button = res.internal.area;
+                ui.new_row();
+                space = ui.allocate_space(Size::new(20, 10))?.area;
+                Ok(())
+            })
+            .unwrap();
+        });
+        // the closure runs once for measuring and once for drawing, both to completion
+        assert_eq!(runs, 2);
+        // the whole block (not just the first widget) was aligned to the bottom right
+        assert_eq!(space.bottom_right(), Some(Point::new(196, 96)));
+        assert_eq!(button.top_left.x + button.size.width as i32, 197);
+        // the measuring pass didn't consume the smartstate, so the button was drawn
+        let background = harness.display().pixel(Point::new(0, 0));
+        assert_ne!(harness.display().pixel(button.center()), background);
+    }
+}
@@ -0,0 +1,305 @@
+//! # Pointer Capture
+//!
+//! Without pointer capture, each widget only receives the interactions whose point lies inside of
+//! it. So dragging the knob of a [Slider](crate::widgets::Slider) off its track stops it from
+//! updating, and releasing the pointer over a different [Button](crate::widgets::Button) clicks
+//! that button.
+//!
+//! With a [PointerCapture], the widget that was pressed owns the pointer until it is released: it
+//! keeps receiving [Interaction::Drag] and [Interaction::Release] (with the raw point, which may
+//! lie outside of it), and all other widgets receive [Interaction::None]. A release outside of the
+//! pressed widget doesn't click it (see [InternalResponse::released](crate::InternalResponse::released)).
+//!
+//! As the [Ui] is rebuilt every frame, the owner is remembered across frames in the
+//! [PointerCapture] by its area. The offset of enclosing [ScrollArea](super::ScrollArea)s isn't
+//! part of that area, so the owner keeps the pointer while its content is scrolled, and when
+//! other widgets are added or removed without moving it.
+
+use crate::{Interaction, Ui};
+use core::cell::Cell;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::Point;
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::transform::Transform;
+
+/// Pointer capture state of the [Ui], which has to persist across frames.
+///
+/// With pointer capture, the widget that was pressed owns the pointer until it is released: it
+/// keeps receiving [Interaction::Drag] and [Interaction::Release], even if the pointer was
+/// dragged off of it, and all other widgets receive [Interaction::None].
+///
+/// Set it on the [Ui] with [Ui::set_pointer_capture] each frame. Like the
+/// [OverlayState](super::OverlayState), it only consists of a few [Cell]s and is shared with all
+/// sub-[Ui]s by reference.
+#[derive(Debug, Default)]
+pub struct PointerCapture {
+    /// Area of the widget that owns the pointer, without the offset of scroll areas
+    owner: Cell<Option<Rectangle>>,
+    /// Whether the pointer is down
+    down: Cell<bool>,
+    /// Whether the pointer was pressed in the current frame, so that the owner is chosen
+    claiming: Cell<bool>,
+    /// Whether the pointer was released in the current frame
+    released: Cell<bool>,
+}
+
+impl PointerCapture {
+    /// Creates a new pointer capture state, with no widget owning the pointer.
+    pub const fn new() -> Self {
+        PointerCapture {
+            owner: Cell::new(None),
+            down: Cell::new(false),
+            claiming: Cell::new(false),
+            released: Cell::new(false),
+        }
+    }
+
+    /// Returns the area of the widget that owns the pointer, if any.
+    ///
+    /// This is the area the widget would have if no enclosing [ScrollArea](super::ScrollArea)
+    /// was scrolled, which identifies the widget across frames. Widgets with the same area share
+    /// the pointer.
+    pub fn owner(&self) -> Option<Rectangle> {
+        self.owner.get()
+    }
+
+    /// Returns whether a widget owns the pointer that was pressed in a previous frame.
+    ///
+    /// The owner then receives pointer interactions anywhere on the screen, so containers that
+    /// filter the interactions of their content (like a [ScrollArea](super::ScrollArea)) have to
+    /// pass them on.
+    pub fn is_captured(&self) -> bool {
+        !self.claiming.get() && self.owner.get().is_some()
+    }
+
+    /// Starts a new frame. The owner of a pointer released in the previous frame is forgotten.
+    pub(crate) fn start_frame(&self) {
+        if self.released.take() {
+            self.owner.set(None);
+        }
+        self.claiming.set(false);
+    }
+
+    /// Tracks the (raw) pointer interaction of the current frame.
+    pub(crate) fn track(&self, interaction: Interaction) {
+        match interaction {
+            // some pointer drivers repeat the click while the pointer is down
+            Interaction::Click(_) | Interaction::Drag(_) if !self.down.get() => {
+                self.down.set(true);
+                self.owner.set(None);
+                self.claiming.set(true);
+            }
+            Interaction::Release(_) => {
+                self.down.set(false);
+                self.released.set(true);
+            }
+            Interaction::Hover(_) => {
+                self.down.set(false);
+                self.owner.set(None);
+            }
+            _ => {}
+        }
+    }
+
+    /// Routes the pointer interaction to the widget with the given area, whose enclosing scroll
+    /// areas are scrolled by `scroll_offset` in total (see [PointerCapture::owner]).
+    ///
+    /// When the pointer is pressed, each widget below it claims the pointer, so that the last
+    /// one (i.e. the innermost or topmost widget) owns it.
+    fn route(
+        &self,
+        area: Rectangle,
+        scroll_offset: Point,
+        interaction: Interaction,
+    ) -> Interaction {
+        let key = area.translate(scroll_offset);
+        let inside = interaction.get_point().is_some_and(|pt| area.contains(pt));
+        match interaction {
+            Interaction::Hover(_) if inside => interaction,
+            Interaction::Click(_) | Interaction::Drag(_) if self.claiming.get() && inside => {
+                self.owner.set(Some(key));
+                interaction
+            }
+            Interaction::Click(_) | Interaction::Drag(_) | Interaction::Release(_)
+                if !self.claiming.get() && self.owner.get() == Some(key) =>
+            {
+                interaction
+            }
+            _ => Interaction::None,
+        }
+    }
+}
+
+impl<'a, COL, DRAW> Ui<'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Sets the [PointerCapture], so that the widget that was pressed keeps receiving the pointer
+    /// until it is released (see [PointerCapture]).
+    ///
+    /// The widgets are identified by their area (without the offset of scrolled
+    /// [ScrollArea](super::ScrollArea)s), so the pressed widget has to stay in place while the
+    /// pointer is down.
+    ///
+    /// Call this once per frame on the top-level [Ui], before [Ui::interact].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::Interaction;
+    /// # use kolibri_embedded_gui::ui::PointerCapture;
+    /// # use kolibri_embedded_gui::widgets::Slider;
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let mut volume = 0i16;
+    /// let capture = PointerCapture::new();
+    /// // in the drawing loop:
+    /// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_pointer_capture(&capture);
+    /// ui.interact(Interaction::Drag(Point::new(300, 200)));
+    /// // keeps following the pointer, even if it was dragged off the slider
+    /// ui.add(Slider::new(&mut volume, 0..=100));
+    /// ```
+    pub fn set_pointer_capture(&mut self, capture: &'a PointerCapture) {
+        capture.start_frame();
+        self.capture = Some(capture);
+    }
+
+    /// Returns the interaction for the next widget, which has the given area, taking the pointer
+    /// capture into account (see [Ui::check_interact]).
+    pub(super) fn captured_interaction(&self, area: Rectangle) -> Option<Interaction> {
+        self.capture
+            .map(|capture| capture.route(area, self.scroll_offset, self.interact))
+    }
+
+    /// Returns whether a widget owns the pointer (see [PointerCapture::is_captured]).
+    pub(super) fn pointer_captured(&self) -> bool {
+        self.capture.is_some_and(|capture| capture.is_captured())
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::style::medsize_rgb565_style;
+    use crate::testing::{Harness, Script};
+    use crate::ui::{GridColumn, ScrollArea, ScrollState};
+    use crate::widgets::{Button, Slider, Spacer};
+    use crate::Interaction;
+    use embedded_graphics::geometry::{Point, Size};
+    use embedded_graphics::primitives::Rectangle;
+    use embedded_graphics::transform::Transform;
+
+    #[test]
+    fn test_pointer_capture() {
+        let mut harness = Harness::new(Size::new(320, 120), medsize_rgb565_style());
+        let (mut value, mut first, mut second) = (0i32, 0, 0);
+        let mut run = |harness: &mut Harness<_>, script: Script| {
+            harness.run(script, |ui| {
+                ui.add(Slider::new(&mut value, 0..=100));
+                first += ui.add(Button::new("First")).clicked() as u32;
+                second += ui.add(Button::new("Second")).clicked() as u32;
+            });
+            (value, first, second)
+        };
+        run(&mut harness, Script::new().idle(1));
+
+        // dragging the slider off its track keeps updating it
+        let script = Script::new().drag(Point::new(60, 13), Point::new(300, 100), 2);
+        assert_eq!(run(&mut harness, script), (100, 0, 0));
+        assert!(harness.capture().owner().is_some());
+        run(&mut harness, Script::new().idle(1));
+        assert_eq!(harness.capture().owner(), None);
+
+        // releasing over a different button doesn't click either button
+        let script = Script::new().drag(Point::new(20, 40), Point::new(20, 70), 2);
+        assert_eq!(run(&mut harness, script), (100, 0, 0));
+
+        // a regular tap still clicks
+        let script = Script::new().tap(Point::new(20, 70));
+        assert_eq!(run(&mut harness, script), (100, 0, 1));
+    }
+
+    #[test]
+    fn test_pointer_capture_while_scrolling() {
+        let mut harness = Harness::new(Size::new(320, 120), medsize_rgb565_style());
+        let mut state = ScrollState::new();
+        let mut value = 0i32;
+        let mut run = |harness: &mut Harness<_>, scroll: u32, script: Script| {
+            let mut slider = Rectangle::zero();
+            state.set_offset(scroll);
+            harness.run(script, |ui| {
+                ScrollArea::new(&mut state)
+                    .height(80)
+                    .show(ui, |ui| {
+                        ui.add(Spacer::new(Size::new(1, 40)));
+                        slider = ui.add(Slider::new(&mut value, 0..=100)).internal.area;
+                        ui.add(Spacer::new(Size::new(1, 200)));
+                        Ok(())
+                    })
+                    .unwrap();
+            });
+            (value, slider)
+        };
+        let (_, slider) = run(&mut harness, 0, Script::new().idle(1));
+        let start = slider.center();
+        run(&mut harness, 0, Script::new().press(start));
+        assert_eq!(harness.capture().owner(), Some(slider));
+
+        // the slider keeps the pointer, although the content was scrolled below it
+        let drag = Point::new(300, start.y);
+        let (value, scrolled) = run(
+            &mut harness,
+            30,
+            Script::new().then(Interaction::Drag(drag)),
+        );
+        assert_eq!(scrolled, slider.translate(Point::new(0, -30)));
+        assert_eq!(value, 100);
+        assert_eq!(harness.capture().owner(), Some(slider));
+        run(&mut harness, 30, Script::new().release(drag));
+    }
+
+    #[test]
+    fn test_pointer_capture_when_widgets_are_removed() {
+        let mut harness = Harness::new(Size::new(320, 120), medsize_rgb565_style());
+        let columns = [GridColumn::fixed(60), GridColumn::fraction(1, 1)];
+        let (mut value, mut clicks) = (0i32, 0);
+        let mut run = |harness: &mut Harness<_>, button: bool, script: Script| {
+            let mut slider = Rectangle::zero();
+            harness.run(script, |ui| {
+                ui.grid(&columns, |grid| {
+                    if button {
+                        clicks += grid.add(Button::new("Left")).clicked() as u32;
+                    } else {
+                        grid.skip_cell();
+                    }
+                    slider = grid.add(Slider::new(&mut value, 0..=100)).internal.area;
+                    Ok(())
+                })
+                .unwrap();
+            });
+            (value, clicks, slider)
+        };
+        let (_, _, slider) = run(&mut harness, true, Script::new().idle(1));
+        let start = slider.center();
+        run(&mut harness, true, Script::new().press(start));
+
+        // the button drawn before the slider disappears, which doesn't move the slider
+        let drag = Point::new(0, start.y);
+        let script = Script::new().then(Interaction::Drag(drag));
+        assert_eq!(run(&mut harness, false, script), (0, 0, slider));
+        assert_eq!(harness.capture().owner(), Some(slider));
+
+        // and reappears, without receiving the release over it
+        let drag = Point::new(319, start.y);
+        let script = Script::new()
+            .then(Interaction::Drag(drag))
+            .release(Point::new(10, 10));
+        assert_eq!(run(&mut harness, true, script), (100, 0, slider));
+    }
+}
@@ -0,0 +1,321 @@
+//! # Dirty Regions
+//!
+//! By default, the [Ui] draws in *immediate mode*: every widget is flushed to the [DrawTarget] as
+//! soon as it is drawn. With displays that are driven from a framebuffer in RAM (e.g. with DMA),
+//! it is more efficient to let the widgets draw into the framebuffer, and to only push the
+//! changed parts of it to the display at the end of the frame.
+//!
+//! For this, set a [DirtyRegions] list on the [Ui] with [Ui::set_dirty_regions]. The [Ui] then
+//! collects the areas it draws to, merging overlapping and adjacent areas, and hands the merged
+//! regions to a single flush hook at the end of the frame ([Ui::flush_dirty]).
+
+use crate::{GuiError, GuiResult, Ui};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Dimensions, Point};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::Pixel;
+use heapless::Vec;
+
+/// List of the regions of the screen that were drawn to (up to `N` regions).
+///
+/// Create it outside of the drawing loop, and set it on the [Ui] each frame with
+/// [Ui::set_dirty_regions]. Overlapping and adjacent regions are merged as long as this doesn't
+/// add much undrawn area. If the list is full, new regions are merged into the region that grows
+/// the least.
+#[derive(Clone, Debug, Default)]
+pub struct DirtyRegions<const N: usize> {
+    regions: Vec<Rectangle, N>,
+}
+
+impl<const N: usize> DirtyRegions<N> {
+    /// Creates an empty list.
+    pub const fn new() -> Self {
+        Self {
+            regions: Vec::new(),
+        }
+    }
+
+    /// Marks an area as drawn to.
+    pub fn add(&mut self, area: Rectangle) {
+        if area.is_zero_sized() {
+            return;
+        }
+        let mut area = area;
+        while let Some(index) = self
+            .regions
+            .iter()
+            .position(|region| should_merge(region, &area))
+        {
+            area = union(&self.regions.swap_remove(index), &area);
+        }
+
+        if let Err(area) = self.regions.push(area) {
+            let grown =
+                |region: &Rectangle| pixels(&union(region, &area)).saturating_sub(pixels(region));
+            if let Some(region) = self.regions.iter_mut().min_by_key(|region| grown(region)) {
+                *region = union(region, &area);
+            }
+        }
+    }
+
+    /// Returns the (merged) dirty regions.
+    pub fn regions(&self) -> &[Rectangle] {
+        &self.regions
+    }
+
+    /// Returns whether nothing was drawn since the last flush.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Removes all regions.
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+}
+
+/// Type-erased [DirtyRegions], so that the [Ui] doesn't depend on their capacity.
+pub(super) trait RegionList {
+    fn add(&mut self, area: Rectangle);
+    fn regions(&self) -> &[Rectangle];
+    fn clear(&mut self);
+}
+
+impl<const N: usize> RegionList for DirtyRegions<N> {
+    fn add(&mut self, area: Rectangle) {
+        DirtyRegions::add(self, area)
+    }
+
+    fn regions(&self) -> &[Rectangle] {
+        DirtyRegions::regions(self)
+    }
+
+    fn clear(&mut self) {
+        DirtyRegions::clear(self)
+    }
+}
+
+/// Returns the number of pixels of an area.
+fn pixels(area: &Rectangle) -> u64 {
+    area.size.width as u64 * area.size.height as u64
+}
+
+/// Returns the bounding box of two (non-empty) areas.
+fn union(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    let a_end = a.top_left + a.size;
+    let b_end = b.top_left + b.size;
+    Rectangle::with_corners(
+        a.top_left.component_min(b.top_left),
+        a_end.component_max(b_end) - Point::new(1, 1),
+    )
+}
+
+/// Two regions are merged if they overlap or touch, and their bounding box isn't larger than
+/// both of them together.
+fn should_merge(a: &Rectangle, b: &Rectangle) -> bool {
+    let touching = !a.offset(1).intersection(b).is_zero_sized();
+    touching && pixels(&union(a, b)) <= pixels(a) + pixels(b)
+}
+
+/// [DrawTarget] that records the bounding box of everything drawn through it.
+pub(super) struct Tracked<'t, T> {
+    pub(super) target: &'t mut T,
+    pub(super) area: Option<Rectangle>,
+}
+
+impl<'t, T> Tracked<'t, T> {
+    pub(super) fn new(target: &'t mut T) -> Self {
+        Self { target, area: None }
+    }
+
+    fn track(&mut self, area: Rectangle) {
+        if !area.is_zero_sized() {
+            self.area = Some(match &self.area {
+                Some(prev) => union(prev, &area),
+                None => area,
+            });
+        }
+    }
+}
+
+impl<T: Dimensions> Dimensions for Tracked<'_, T> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<T: DrawTarget> DrawTarget for Tracked<'_, T> {
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mut min: Option<(Point, Point)> = None;
+        let res = self
+            .target
+            .draw_iter(pixels.into_iter().inspect(|Pixel(point, _)| {
+                min = Some(match min {
+                    Some((tl, br)) => (tl.component_min(*point), br.component_max(*point)),
+                    None => (*point, *point),
+                });
+            }));
+        if let Some((top_left, bottom_right)) = min {
+            self.track(Rectangle::with_corners(top_left, bottom_right));
+        }
+        res
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.track(*area);
+        self.target.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.track(*area);
+        self.target.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.track(self.target.bounding_box());
+        self.target.clear(color)
+    }
+}
+
+impl<'a, COL, DRAW> Ui<'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Collects the areas that are drawn to in the given [DirtyRegions], instead of relying on
+    /// every widget being flushed to the display right away.
+    ///
+    /// Call this once per frame on the top-level [Ui], before adding any widgets, and flush the
+    /// regions with [Ui::flush_dirty] at the end of the frame. Sub-[Ui]s share the regions of
+    /// their parent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::ui::DirtyRegions;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// let mut dirty = DirtyRegions::<8>::new();
+    /// // in the drawing loop:
+    /// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_dirty_regions(&mut dirty);
+    /// ui.add(Label::new("Hello"));
+    /// ui.flush_dirty(|display, regions| {
+    ///     for region in regions {
+    ///         // e.g. start a DMA transfer of the region of the framebuffer in `display`
+    ///     }
+    ///     Ok::<(), ()>(())
+    /// })
+    /// .ok();
+    /// ```
+    pub fn set_dirty_regions<const N: usize>(&mut self, regions: &'a mut DirtyRegions<N>) {
+        self.painter.dirty = Some(regions);
+    }
+
+    /// Hands the dirty regions collected since the last flush to the `flush` hook (together with
+    /// the [DrawTarget]), and clears them.
+    ///
+    /// Does nothing if there are no [DirtyRegions] (immediate mode) or nothing was drawn.
+    pub fn flush_dirty<E>(
+        &mut self,
+        flush: impl FnOnce(&mut DRAW, &[Rectangle]) -> Result<(), E>,
+    ) -> GuiResult<()> {
+        let painter = &mut self.painter;
+        let Some(dirty) = painter.dirty.as_deref_mut() else {
+            return Ok(());
+        };
+        if dirty.regions().is_empty() {
+            return Ok(());
+        }
+        let res = flush(painter.target, dirty.regions())
+            .map_err(|_| GuiError::draw_error("Couldn't flush dirty regions"));
+        dirty.clear();
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use crate::widgets::Label;
+    use embedded_graphics::geometry::Size;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::{Rgb565, RgbColor};
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn test_merge_regions() {
+        let mut dirty = DirtyRegions::<4>::new();
+        // adjacent
+        dirty.add(rect(0, 0, 10, 10));
+        dirty.add(rect(10, 0, 10, 10));
+        assert_eq!(dirty.regions(), &[rect(0, 0, 20, 10)]);
+
+        // contained
+        dirty.add(rect(2, 2, 4, 4));
+        assert_eq!(dirty.regions(), &[rect(0, 0, 20, 10)]);
+
+        // far apart, or too much undrawn area in between
+        dirty.add(rect(50, 50, 5, 5));
+        dirty.add(rect(0, 10, 2, 40));
+        assert_eq!(dirty.regions().len(), 3);
+
+        // merging may chain
+        dirty.add(rect(20, 0, 30, 10));
+        dirty.add(rect(50, 10, 5, 40));
+        assert_eq!(
+            dirty.regions(),
+            &[rect(0, 10, 2, 40), rect(0, 0, 50, 10), rect(50, 10, 5, 45)]
+        );
+    }
+
+    #[test]
+    fn test_full_regions() {
+        let mut dirty = DirtyRegions::<2>::new();
+        dirty.add(rect(0, 0, 5, 5));
+        dirty.add(rect(40, 40, 5, 5));
+        dirty.add(rect(0, 10, 5, 5));
+        assert_eq!(dirty.regions(), &[rect(0, 0, 5, 15), rect(40, 40, 5, 5)]);
+    }
+
+    #[test]
+    fn test_flush_dirty() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut dirty = DirtyRegions::<4>::new();
+        let mut buffer = [Rgb565::BLACK; 64 * 16];
+
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.set_buffer(&mut buffer);
+        ui.set_dirty_regions(&mut dirty);
+        let first = ui.add_horizontal(Label::new("ab")).internal.area;
+        let second = ui.add_horizontal(Label::new("c")).internal.area;
+
+        let mut flushed = Vec::<Rectangle, 4>::new();
+        ui.flush_dirty(|_, regions| flushed.extend_from_slice(regions))
+            .unwrap();
+        assert_eq!(flushed.as_slice(), &[first, second]);
+
+        // nothing left to flush
+        ui.flush_dirty(|_, _| Err(())).unwrap();
+    }
+}
@@ -0,0 +1,498 @@
+use super::{Align, Placer, Ui, VerticalAlign, Widget};
+use crate::{GuiError, GuiResult, Response};
+use core::cmp::{max, min};
+use core::ops::AddAssign;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// Maximum number of rows of a [Grid] whose height is measured for vertical cell alignment.
+///
+/// Cells in rows beyond this are aligned to the top of their row.
+const MAX_MEASURED_ROWS: usize = 32;
+
+/// Maximum number of cells of a [Grid] whose content size is measured for cell alignment.
+///
+/// The content of cells beyond this is measured again in the drawing pass (see [Ui::with_align]).
+const MAX_MEASURED_CELLS: usize = 64;
+
+/// Sizes recorded in the measuring pass of a [Grid]
+#[derive(Clone, Copy)]
+struct GridMeasurement {
+    /// Height of each row
+    row_heights: [u32; MAX_MEASURED_ROWS],
+    /// Size of the content of each cell, from the top left corner of the cell
+    cell_sizes: [Size; MAX_MEASURED_CELLS],
+}
+
+/// Width of a [Grid] column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnWidth {
+    /// Fixed width in pixels
+    Fixed(u32),
+    /// Fraction (numerator, denominator) of the available width of the grid.
+    ///
+    /// The spacing between columns is subtracted from the available width first,
+    /// so e.g. two columns of `Fraction(1, 2)` fill the entire width.
+    Fraction(u32, u32),
+}
+
+/// A column of a [Grid], with its width and the alignment of its cells' content.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridColumn {
+    pub width: ColumnWidth,
+    pub align: Align,
+}
+
+impl GridColumn {
+    /// Creates a column with a fixed width in pixels and left / top alignment.
+    pub const fn fixed(width: u32) -> Self {
+        GridColumn {
+            width: ColumnWidth::Fixed(width),
+            align: Align(super::HorizontalAlign::Left, VerticalAlign::Top),
+        }
+    }
+
+    /// Creates a column taking `numerator / denominator` of the available width of the grid,
+    /// with left / top alignment.
+    pub const fn fraction(numerator: u32, denominator: u32) -> Self {
+        GridColumn {
+            width: ColumnWidth::Fraction(numerator, denominator),
+            align: Align(super::HorizontalAlign::Left, VerticalAlign::Top),
+        }
+    }
+
+    /// Sets the alignment of the content of the column's cells.
+    pub const fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Returns the width of the column in pixels, given the width available to fractional columns.
+    fn resolve(&self, fraction_width: u32) -> u32 {
+        match self.width {
+            ColumnWidth::Fixed(width) => width,
+            ColumnWidth::Fraction(numerator, denominator) => {
+                (fraction_width as u64 * numerator as u64 / max(denominator, 1) as u64) as u32
+            }
+        }
+    }
+}
+
+/// A grid of cells, created by [Ui::grid].
+///
+/// Cells are filled in order, from left to right, wrapping to the next row after the last column.
+/// Each row is as high as its tallest cell.
+pub struct Grid<'g, 'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    ui: &'g mut Ui<'a, DRAW, COL>,
+    columns: &'g [GridColumn],
+    /// Absolute position of the top left corner of the grid
+    origin: Point,
+    /// Space available for the grid
+    available: Size,
+    /// Width that fractional columns are relative to
+    fraction_width: u32,
+    /// Current column
+    col: usize,
+    /// Current row
+    row: usize,
+    /// Index of the current cell
+    cell: usize,
+    /// Position of the current cell, relative to the origin
+    cell_pos: Point,
+    /// Height of the current row
+    row_height: u32,
+    /// Size covered by the cells so far
+    used: Size,
+    /// Sizes recorded in this pass
+    measurement: GridMeasurement,
+    /// Sizes from a previous (measuring) pass
+    measured: Option<GridMeasurement>,
+}
+
+impl<'g, 'a, DRAW, COL> Grid<'g, 'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    fn new(
+        ui: &'g mut Ui<'a, DRAW, COL>,
+        columns: &'g [GridColumn],
+        area: Rectangle,
+        measured: Option<GridMeasurement>,
+    ) -> Self {
+        let column_spacing =
+            ui.style().spacing.item_spacing.width * columns.len().saturating_sub(1) as u32;
+        Grid {
+            ui,
+            columns,
+            origin: area.top_left,
+            available: area.size,
+            fraction_width: area.size.width.saturating_sub(column_spacing),
+            col: 0,
+            row: 0,
+            cell: 0,
+            cell_pos: Point::zero(),
+            row_height: 0,
+            used: Size::zero(),
+            measurement: GridMeasurement {
+                row_heights: [0; MAX_MEASURED_ROWS],
+                cell_sizes: [Size::zero(); MAX_MEASURED_CELLS],
+            },
+            measured,
+        }
+    }
+
+    /// Adds a widget to the next cell of the grid.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] indicating the result of adding the widget.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.grid(&[GridColumn::fraction(1, 2), GridColumn::fraction(1, 2)], |grid| {
+    ///     grid.add(Label::new("Name"));
+    ///     grid.add(Label::new("Kolibri"));
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn add(&mut self, mut widget: impl Widget) -> Response {
+        let mut resp = None;
+        let res = self.cell(|ui| {
+            resp = Some(ui.add_raw(&mut widget)?);
+            Ok(())
+        });
+        match (res, resp) {
+            (Ok(()), Some(resp)) => resp,
+            (Err(e), _) => Response::from_error(e),
+            (Ok(()), None) => Response::from_error(GuiError::NoSpaceLeft),
+        }
+    }
+
+    /// Fills the next cell of the grid with the widgets added in the closure.
+    ///
+    /// The closure gets a sub-[Ui] covering the cell, in which the content is aligned with
+    /// the column's alignment. For non-default alignments, the closure is run twice (once in the
+    /// measuring pass of the grid, see [Ui::grid]), so keep side effects to widget responses.
+    ///
+    /// The content of the cell is aligned as a single block of the measured size, so its lines
+    /// are not aligned to each other.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] with the result of the closure.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::{Button, Label};
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut volume = 5u8;
+    /// ui.grid(&[GridColumn::fixed(100), GridColumn::fraction(1, 1)], |grid| {
+    ///     grid.add(Label::new("Volume"));
+    ///     grid.cell(|ui| {
+    ///         if ui.add_horizontal(Button::new("-")).clicked() {
+    ///             volume = volume.saturating_sub(1);
+    ///         }
+    ///         if ui.add_horizontal(Button::new("+")).clicked() {
+    ///             volume = volume.saturating_add(1);
+    ///         }
+    ///         Ok(())
+    ///     })
+    /// }).unwrap();
+    /// ```
+    pub fn cell<F>(&mut self, mut f: F) -> GuiResult<()>
+    where
+        F: FnMut(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let column = *self.columns.get(self.col).ok_or(GuiError::NoSpaceLeft)?;
+
+        let width = min(
+            column.resolve(self.fraction_width),
+            self.available.width.saturating_sub(self.cell_pos.x as u32),
+        );
+        let max_height = self.available.height.saturating_sub(self.cell_pos.y as u32);
+        let measured_height = self
+            .measured
+            .as_ref()
+            .and_then(|measured| measured.row_heights.get(self.row).copied())
+            .filter(|height| *height != 0);
+        let measured_size = self
+            .measured
+            .as_ref()
+            .and_then(|measured| measured.cell_sizes.get(self.cell).copied());
+
+        // without knowing the row height, the content can only be aligned to the top of the row
+        let (height, align) = match measured_height {
+            Some(height) => (min(height, max_height), column.align),
+            None => (max_height, Align(column.align.0, VerticalAlign::Top)),
+        };
+
+        let mut bounds = Rectangle::new(self.cell_pos, Size::new(width, height));
+        bounds.top_left.add_assign(self.origin);
+        let placer = Placer::new(bounds.size, true, Align::default());
+
+        let (res, placer) = self.ui.child_ui(bounds, placer, |ui| match measured_size {
+            Some(size) => ui.with_block_align(align, size, &mut f),
+            // while measuring, the content is placed unaligned to find its size
+            None if ui.is_measuring() => ui.with_align(Align::default(), &mut f),
+            None => ui.with_align(align, &mut f),
+        });
+
+        let content_size = placer.used_size();
+        self.row_height = max(self.row_height, content_size.height);
+        if let Some(row_height) = self.measurement.row_heights.get_mut(self.row) {
+            *row_height = self.row_height;
+        }
+        if let Some(cell_size) = self.measurement.cell_sizes.get_mut(self.cell) {
+            *cell_size = content_size;
+        }
+        self.used = Size::new(
+            max(self.used.width, self.cell_pos.x as u32 + width),
+            max(
+                self.used.height,
+                self.cell_pos.y as u32 + content_size.height,
+            ),
+        );
+
+        self.col += 1;
+        self.cell += 1;
+        self.cell_pos.x += (width + self.ui.style().spacing.item_spacing.width) as i32;
+        if self.col == self.columns.len() {
+            self.next_row();
+        }
+
+        res
+    }
+
+    /// Leaves the next cell of the grid empty.
+    pub fn skip_cell(&mut self) {
+        self.cell(|_| Ok(())).ok();
+    }
+
+    /// Finishes the current row, leaving its remaining cells empty.
+    ///
+    /// Does nothing if no cell of the current row was filled yet.
+    pub fn end_row(&mut self) {
+        if self.col != 0 {
+            self.next_row();
+        }
+    }
+
+    fn next_row(&mut self) {
+        self.cell_pos = Point::new(
+            0,
+            self.cell_pos.y
+                + (self.row_height + self.ui.style().spacing.item_spacing.height) as i32,
+        );
+        self.col = 0;
+        self.row += 1;
+        self.row_height = 0;
+    }
+}
+
+// -- Grid layout --
+impl<COL, DRAW> Ui<'_, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Places widgets in a grid with the given columns, in the remaining space of the current row.
+    ///
+    /// Column widths are either fixed (in pixels), or a fraction of the available width, which is
+    /// [Ui::get_width] if the grid starts on a new row. Cells are filled in order (see
+    /// [Grid::add], [Grid::cell] and [Grid::skip_cell]), wrapping to the next row after the
+    /// last column. Each row is as high as its tallest cell.
+    ///
+    /// The content of each cell is aligned according to its column's [Align]. If any column
+    /// is aligned other than left / top, the closure is run in a measuring pass first to find
+    /// the heights of all rows and the sizes of all cells (see [Ui::with_align] for the caveats).
+    ///
+    /// Afterwards, the space used by the grid is allocated in this [Ui].
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] containing the rectangle used by the grid.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::widgets::{Button, Checkbox, Label};
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut wifi = false;
+    /// # let mut bluetooth = false;
+    /// let centered = Align(HorizontalAlign::Center, VerticalAlign::Center);
+    /// let columns = [
+    ///     GridColumn::fraction(1, 2).align(Align(HorizontalAlign::Left, VerticalAlign::Center)),
+    ///     GridColumn::fixed(40).align(centered),
+    ///     GridColumn::fraction(1, 2).align(Align(HorizontalAlign::Right, VerticalAlign::Center)),
+    /// ];
+    /// ui.grid(&columns, |grid| {
+    ///     grid.add(Label::new("WiFi"));
+    ///     grid.add(Checkbox::new(&mut wifi));
+    ///     if grid.add(Button::new("Scan")).clicked() {
+    ///         // ...
+    ///     }
+    ///
+    ///     grid.add(Label::new("Bluetooth"));
+    ///     grid.add(Checkbox::new(&mut bluetooth));
+    ///     grid.end_row();
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn grid<F>(&mut self, columns: &[GridColumn], mut f: F) -> GuiResult<Rectangle>
+    where
+        F: FnMut(&mut Grid<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.placer.align_line_start();
+
+        let area = Rectangle::new(
+            self.bounds.top_left + self.placer.pos,
+            self.placer.space_available(),
+        );
+
+        let mut measured = None;
+        if !self.measuring
+            && columns
+                .iter()
+                .any(|column| column.align != Align::default())
+        {
+            // measuring pass: find the row heights and cell sizes without drawing anything
+            self.measuring = true;
+            let mut grid = Grid::new(self, columns, area, None);
+            (f)(&mut grid).ok();
+            measured = Some(grid.measurement);
+            self.measuring = false;
+        }
+
+        let mut grid = Grid::new(self, columns, area, measured);
+        let res = (f)(&mut grid);
+        let used = grid.used;
+
+        let mut area = self.placer.next_fixed(used)?;
+        area.top_left.add_assign(self.bounds.top_left);
+        self.add_item_spacing();
+
+        res.map(|_| area)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use crate::ui::HorizontalAlign;
+    use crate::widgets::Spacer;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_grid_cells() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut style = medsize_rgb565_style();
+        style.spacing.item_spacing = Size::new(4, 2);
+        style.spacing.window_border_padding = Size::zero();
+        let mut ui = Ui::new_fullscreen(&mut display, style);
+
+        let columns = [
+            GridColumn::fixed(20),
+            GridColumn::fraction(1, 2).align(Align(HorizontalAlign::Right, VerticalAlign::Bottom)),
+        ];
+        let mut areas = [Rectangle::zero(); 3];
+        let grid = ui
+            .grid(&columns, |grid| {
+                areas[0] = grid.add(Spacer::new(Size::new(10, 10))).internal.area;
+                areas[1] = grid.add(Spacer::new(Size::new(10, 6))).internal.area;
+                areas[2] = grid.add(Spacer::new(Size::new(5, 5))).internal.area;
+                grid.skip_cell();
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(areas[0].top_left, Point::new(0, 0));
+        // second column: 30 wide (half of 64 - 4), starting at 24; the row is 10 high
+        assert_eq!(areas[1].top_left, Point::new(44, 4));
+        assert_eq!(areas[2].top_left, Point::new(0, 12));
+        assert_eq!(grid, Rectangle::new(Point::zero(), Size::new(54, 17)));
+    }
+
+    #[test]
+    fn test_grid_cell_measured_once() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut style = medsize_rgb565_style();
+        style.spacing.item_spacing = Size::new(4, 2);
+        style.spacing.window_border_padding = Size::zero();
+        let mut ui = Ui::new_fullscreen(&mut display, style);
+
+        let columns = [
+            GridColumn::fixed(20),
+            GridColumn::fixed(30).align(Align(HorizontalAlign::Right, VerticalAlign::Center)),
+        ];
+        let mut runs = 0;
+        let mut areas = [Rectangle::zero(); 2];
+        ui.grid(&columns, |grid| {
+            grid.add(Spacer::new(Size::new(10, 10)));
+            grid.cell(|ui| {
+                runs += 1;
+                areas[0] = ui
+                    .add_horizontal(Spacer::new(Size::new(10, 2)))
+                    .internal
+                    .area;
+                areas[1] = ui
+                    .add_horizontal(Spacer::new(Size::new(6, 2)))
+                    .internal
+                    .area;
+                Ok(())
+            })
+        })
+        .unwrap();
+
+        // once in the measuring pass of the grid, once for drawing
+        assert_eq!(runs, 2);
+        // the content (20x2 with spacing) is aligned as a block in the 30x10 cell
+        assert_eq!(areas[0].top_left, Point::new(34, 4));
+        assert_eq!(areas[1].top_left, Point::new(48, 4));
+    }
+}
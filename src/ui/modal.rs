@@ -0,0 +1,278 @@
+//! # Modal
+//!
+//! Modals are framed, titled panels in the center of the screen (e.g. confirmation prompts or
+//! error messages), that block the interaction with everything underneath while they are open.
+//! See [Ui::modal], and [MessageBox](crate::helpers::message_box::MessageBox) for ready-made
+//! message boxes.
+//!
+//! Like popups, modals need an [OverlayState](super::OverlayState) to capture interactions across
+//! frames, which also redraws the widgets below once a modal closes.
+
+use super::{Align, Placer};
+use crate::{GuiResult, Ui};
+use core::cmp::max;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Dimensions, Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{
+    PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, StrokeAlignment,
+};
+
+impl<DRAW, COL> Ui<'_, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Shows a modal panel with a title bar in the center of the screen, if `open` is `true`.
+    ///
+    /// The panel is sized to fit its content, which is added by the closure. To size the panel,
+    /// the closure is first run in a measuring pass (like in [Ui::with_align]). Call this at the end
+    /// of the frame, after adding all other widgets, so that the modal is drawn on top of them.
+    ///
+    /// While the modal is open, it captures all pointer interactions (which only its content
+    /// receives), and the focus only moves between the widgets of its content. Capturing the
+    /// pointer requires an [OverlayState](super::OverlayState) on the [Ui]. The content closes the
+    /// modal with [Ui::close_modal], which sets `open` to `false`. Once the modal closed, the
+    /// widgets below it are redrawn in the next frame (see [Ui::set_overlay_state]).
+    ///
+    /// The modal is drawn with [Ui::overlay]: the panel is only painted when the modal opens, the
+    /// background was cleared or a widget below it was painted in this frame, and its content is
+    /// [cleared](Ui::cleared) then, so the content can use smartstates. Widgets below the modal that
+    /// are redrawn in every frame make it repaint in every frame, so they should use smartstates
+    /// as well. Popups (e.g. of a [ComboBox](crate::widgets::ComboBox)) aren't supported in modals.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::ui::OverlayState;
+    /// # use kolibri_embedded_gui::widgets::{Button, Label};
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// let overlay = OverlayState::new();
+    /// let mut open = true;
+    /// let mut deleted = false;
+    /// // in the drawing loop:
+    /// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_overlay_state(&overlay);
+    /// // ... add widgets ...
+    /// ui.modal(&mut open, "Delete", |ui| {
+    ///     ui.add(Label::new("Really delete program?"));
+    ///     if ui.add_horizontal(Button::new("Delete")).clicked() {
+    ///         deleted = true;
+    ///         ui.close_modal();
+    ///     }
+    ///     if ui.add_horizontal(Button::new("Keep")).clicked() {
+    ///         ui.close_modal();
+    ///     }
+    ///     Ok(())
+    /// })
+    /// .ok();
+    /// ```
+    pub fn modal<F>(&mut self, open: &mut bool, title: &str, mut f: F) -> GuiResult<()>
+    where
+        F: FnMut(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        if !*open {
+            return Ok(());
+        }
+
+        let style = self.style;
+        let font = style.default_font;
+        let border = max(style.border_width, 1);
+        let padding = style.spacing.window_border_padding;
+        let title_padding = style.spacing.button_padding;
+        let title_height = font.line_height() + 2 * title_padding.height;
+        let frame = Size::new(
+            2 * (border + padding.width),
+            2 * (border + padding.height) + title_height,
+        );
+        let screen = self.painter.bounding_box();
+        let max_content = screen.size.saturating_sub(frame);
+
+        // measuring pass: size the panel to fit its content
+        let measuring = core::mem::replace(&mut self.measuring, true);
+        let bounds = Rectangle::new(screen.top_left, max_content);
+        let (_, placer) = self.child_ui(
+            bounds,
+            Placer::new(max_content, true, Align::default()),
+            &mut f,
+        );
+        self.measuring = measuring;
+        let content = placer.used_size();
+
+        let title_width = font.text_width(title) + 2 * title_padding.width;
+        let size = Size::new(max(content.width, title_width), content.height) + frame;
+        let size = size.component_min(screen.size);
+        let panel = Rectangle::new(screen.top_left + (screen.size - size) / 2, size);
+
+        // block the interaction with everything underneath
+        if let Some(focus) = self.focus {
+            focus.trap();
+        }
+
+        let title_bar = Rectangle::new(
+            panel.top_left + Point::new(border as i32, border as i32),
+            Size::new(panel.size.width.saturating_sub(2 * border), title_height),
+        );
+        let content_area = Rectangle::new(
+            title_bar.top_left
+                + Point::new(padding.width as i32, (title_height + padding.height) as i32),
+            size.saturating_sub(frame),
+        );
+        let closing = core::mem::replace(&mut self.closing, false);
+        let res = self.overlay_content(panel, |ui, repaint| {
+            // draw the panel, unless it is still there from the previous frame
+            if repaint {
+                ui.paint(&panel, |ui| {
+                    ui.draw(
+                        &panel.into_styled(
+                            PrimitiveStyleBuilder::new()
+                                .fill_color(style.background_color)
+                                .stroke_color(style.border_color)
+                                .stroke_width(border)
+                                .stroke_alignment(StrokeAlignment::Inside)
+                                .build(),
+                        ),
+                    )?;
+                    ui.draw(
+                        &title_bar.into_styled(PrimitiveStyle::with_fill(style.primary_color)),
+                    )?;
+                    ui.draw(&style.text(
+                        title,
+                        title_bar.top_left
+                            + Point::new(title_padding.width as i32, title_padding.height as i32),
+                        style.primary_color,
+                    ))
+                })?;
+            }
+
+            // the content is cleared along with the panel
+            let (res, _) = ui.child_ui(
+                content_area,
+                Placer::new(content_area.size, true, Align::default()),
+                f,
+            );
+            res
+        });
+
+        let closed = core::mem::replace(&mut self.closing, closing);
+        if closed || self.overlay_state.is_some_and(|overlay| !overlay.is_open()) {
+            *open = false;
+        }
+        res
+    }
+
+    /// Closes the modal whose content this [Ui] shows (see [Ui::modal]).
+    ///
+    /// The modal is still drawn in this frame, and the `open` flag of the modal is set to `false`
+    /// once its content was added (with or without an [OverlayState](super::OverlayState)).
+    pub fn close_modal(&mut self) {
+        self.closing = true;
+        self.close_overlay();
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::smartstate::Smartstate;
+    use crate::style::medsize_rgb565_style;
+    use crate::testing::{Harness, Script, TestDisplay};
+    use crate::widgets::Button;
+    use crate::{Interaction, Ui};
+    use embedded_graphics::geometry::{Point, Size};
+    use embedded_graphics::pixelcolor::{Rgb565, RgbColor};
+    use embedded_graphics::Drawable;
+    use embedded_graphics::Pixel;
+
+    #[test]
+    fn test_modal() {
+        let style = medsize_rgb565_style();
+        let mut harness = Harness::new(Size::new(128, 96), style);
+        let mut open = false;
+        let (mut below, mut inside) = (0, 0);
+        let mut smartstate = Smartstate::empty();
+        let mut run = |harness: &mut Harness<_>, script: Script, redraw_below: bool| {
+            if redraw_below {
+                smartstate.force_redraw();
+            }
+            harness.run(script, |ui| {
+                if ui
+                    .add(Button::new("Below").smartstate(&mut smartstate))
+                    .clicked()
+                {
+                    below += 1;
+                    open = true;
+                }
+                ui.modal(&mut open, "Title", |ui| {
+                    if ui.add(Button::new("Close")).clicked() {
+                        inside += 1;
+                        ui.close_modal();
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            });
+            (below, inside, open)
+        };
+
+        // the button below opens the modal
+        assert_eq!(
+            run(&mut harness, Script::new().tap(Point::new(10, 10)), false),
+            (1, 0, true)
+        );
+        assert!(harness.overlay().is_open());
+        // the released button below is redrawn once more
+        run(&mut harness, Script::new().idle(1), false);
+
+        // the panel is only painted when the modal opens, or a widget below it was painted
+        let panel = harness.overlay().area().unwrap();
+        let title = panel.top_left + Point::new(panel.size.width as i32 - 4, 3);
+        assert_eq!(harness.display().pixel(title), Some(style.primary_color));
+        Pixel(title, Rgb565::RED)
+            .draw(harness.display_mut())
+            .unwrap();
+        run(&mut harness, Script::new().idle(1), false);
+        assert_eq!(harness.display().pixel(title), Some(Rgb565::RED));
+        run(&mut harness, Script::new().idle(1), true);
+        assert_eq!(harness.display().pixel(title), Some(style.primary_color));
+
+        // the modal captures taps outside of it, and is closed from its content
+        let script = Script::new()
+            .tap(Point::new(10, 10))
+            .tap(Point::new(64, 58));
+        assert_eq!(run(&mut harness, script, false), (1, 1, false));
+        assert!(!harness.overlay().is_open());
+
+        // the background below the modal is cleared in the next frame
+        run(&mut harness, Script::new().idle(1), false);
+        assert!(harness.overlay().needs_redraw());
+        assert_eq!(harness.display().pixel(title), Some(style.background_color));
+    }
+
+    #[test]
+    fn test_close_modal_without_overlay_state() {
+        let style = medsize_rgb565_style();
+        let mut display = TestDisplay::new(Size::new(128, 96), style.background_color);
+        let mut open = true;
+        for interaction in [
+            Interaction::Click(Point::new(64, 58)),
+            Interaction::Release(Point::new(64, 58)),
+        ] {
+            let mut ui = Ui::new_fullscreen(&mut display, style);
+            ui.interact(interaction);
+            ui.modal(&mut open, "Title", |ui| {
+                if ui.add(Button::new("Close")).clicked() {
+                    ui.close_modal();
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+        assert!(!open);
+    }
+}
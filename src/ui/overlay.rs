@@ -0,0 +1,529 @@
+//! # Overlay
+//!
+//! Popups (e.g. the option list of a [ComboBox](crate::widgets::ComboBox)) have to be drawn on top of
+//! all other widgets, including the ones that are added after the widget that opened the popup.
+//! To achieve this, the popup is only registered while the widget is drawn (see [Ui::show_overlay]),
+//! and painted at the end of the frame with [Ui::draw_overlay]. Other content (e.g. a tooltip or a
+//! context menu) is drawn on top of all widgets with [Ui::overlay].
+//!
+//! While a popup is open, it captures all pointer interactions, so that taps on the popup (or next
+//! to it, which closes it) don't reach the widgets below. As the popup is only known once its widget
+//! was drawn, the open popup is remembered across frames in an [OverlayState]. The popup is only
+//! repainted when it opens, or when something below it was painted.
+
+use super::{Align, Placer};
+use crate::{GuiResult, Interaction, Ui};
+use core::cell::Cell;
+use core::cmp::min;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+
+/// Overlay state of the [Ui], which has to persist across frames.
+///
+/// Set it on the [Ui] with [Ui::set_overlay_state] each frame. Like the
+/// [FocusState](crate::focus::FocusState), it only consists of a few [Cell]s and is shared with all
+/// sub-[Ui]s by reference.
+///
+/// When a popup closes, the widgets below it have to be redrawn. In the next frame,
+/// [Ui::set_overlay_state] clears the background, so that the widgets redraw themselves despite
+/// their smartstates (see [Ui::force_redraw_if_cleared]).
+#[derive(Debug, Default)]
+pub struct OverlayState {
+    /// Area of the open popup, if any
+    area: Cell<Option<Rectangle>>,
+    /// Whether the open popup was shown in the current frame
+    shown: Cell<bool>,
+    /// Pointer interaction of the current frame, captured by the open popup
+    interaction: Cell<Interaction>,
+    /// Whether a popup was closed in the current frame
+    closed: Cell<bool>,
+    /// Whether a popup was closed in the previous frame
+    redraw: Cell<bool>,
+    /// Whether the open popup was opened (or moved) in the current frame
+    opened: Cell<bool>,
+    /// Whether something below the open popup was painted in the current frame
+    covered: Cell<bool>,
+    /// Whether the open popup is being painted
+    painting: Cell<bool>,
+    /// State of the [ListPopup] painted last, to repaint it when its items change
+    content: Cell<Option<u32>>,
+}
+
+impl OverlayState {
+    /// Creates a new overlay state, with no popup open.
+    pub const fn new() -> Self {
+        OverlayState {
+            area: Cell::new(None),
+            shown: Cell::new(false),
+            interaction: Cell::new(Interaction::None),
+            closed: Cell::new(false),
+            redraw: Cell::new(false),
+            opened: Cell::new(false),
+            covered: Cell::new(false),
+            painting: Cell::new(false),
+            content: Cell::new(None),
+        }
+    }
+
+    /// Returns whether a popup is open.
+    pub fn is_open(&self) -> bool {
+        self.area.get().is_some()
+    }
+
+    /// Returns the area of the open popup, if any.
+    pub fn area(&self) -> Option<Rectangle> {
+        self.area.get()
+    }
+
+    /// Returns whether a popup was closed in the previous frame, so that the area it covered
+    /// has to be redrawn ([Ui::set_overlay_state] clears the background then).
+    pub fn needs_redraw(&self) -> bool {
+        self.redraw.get()
+    }
+
+    /// Starts a new frame.
+    ///
+    /// A popup that wasn't shown in the previous frame (e.g. because its widget wasn't drawn)
+    /// is closed.
+    pub(crate) fn start_frame(&self) {
+        if !self.shown.get() {
+            self.close();
+        }
+        self.redraw.set(self.closed.get());
+        self.closed.set(false);
+        self.shown.set(false);
+        self.opened.set(false);
+        self.covered.set(false);
+        self.interaction.set(Interaction::None);
+    }
+
+    /// Captures pointer interactions while a popup is open.
+    ///
+    /// ## Returns
+    ///
+    /// The interaction to pass on to the widgets: [Interaction::None] for captured interactions.
+    pub(crate) fn capture(&self, interaction: Interaction) -> Interaction {
+        if self.is_open() && interaction.get_point().is_some() {
+            self.interaction.set(interaction);
+            Interaction::None
+        } else {
+            interaction
+        }
+    }
+
+    /// Returns the pointer interaction captured in the current frame.
+    pub(crate) fn interaction(&self) -> Interaction {
+        self.interaction.get()
+    }
+
+    /// Opens (or keeps open) a popup in the given area.
+    pub(crate) fn open(&self, area: Rectangle) {
+        if self.area.replace(Some(area)) != Some(area) {
+            self.opened.set(true);
+        }
+        self.shown.set(true);
+    }
+
+    /// Notes that the given area is painted, which covers the open popup if they overlap.
+    pub(crate) fn paint(&self, area: &Rectangle) {
+        if self.painting.get() {
+            return;
+        }
+        if let Some(popup) = self.area.get() {
+            if !popup.intersection(area).is_zero_sized() {
+                self.covered.set(true);
+            }
+        }
+    }
+
+    /// Returns whether the open popup has to be repainted in the current frame, as it was opened
+    /// or something below it was painted.
+    pub(crate) fn needs_repaint(&self) -> bool {
+        self.opened.get() || self.covered.get()
+    }
+
+    /// Closes the open popup, if any.
+    pub(crate) fn close(&self) {
+        if self.area.take().is_some() {
+            self.closed.set(true);
+        }
+    }
+}
+
+/// A list of options, drawn as a popup on top of the other widgets.
+///
+/// If the list doesn't fit into its area, only the items starting at [ListPopup::first] are shown.
+#[derive(Clone, Copy, Debug)]
+pub struct ListPopup<'a> {
+    area: Rectangle,
+    item_height: u32,
+    options: &'a [&'a str],
+    first: usize,
+    selected: Option<usize>,
+    highlighted: Option<usize>,
+}
+
+impl<'a> ListPopup<'a> {
+    /// Creates a new list popup in the given area, with items of the given height.
+    pub fn new(area: Rectangle, item_height: u32, options: &'a [&'a str]) -> Self {
+        ListPopup {
+            area,
+            item_height: item_height.max(1),
+            options,
+            first: 0,
+            selected: None,
+            highlighted: None,
+        }
+    }
+
+    /// Sets the index of the first shown item.
+    pub fn first(mut self, first: usize) -> Self {
+        self.first = first;
+        self
+    }
+
+    /// Sets the selected item, which is drawn with the highlight background color.
+    pub fn selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Sets the highlighted (e.g. pressed) item, which is drawn with the primary color.
+    pub fn highlighted(mut self, highlighted: Option<usize>) -> Self {
+        self.highlighted = highlighted;
+        self
+    }
+
+    /// Returns the area of the popup.
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Returns the number of items that fit into the popup.
+    pub fn visible_items(&self) -> usize {
+        min(
+            (self.area.size.height / self.item_height) as usize,
+            self.options.len().saturating_sub(self.first),
+        )
+    }
+
+    /// Returns the area of the item with the given index, if it is shown.
+    pub fn item_area(&self, index: usize) -> Option<Rectangle> {
+        if index < self.first || index >= self.first + self.visible_items() {
+            return None;
+        }
+        let offset = ((index - self.first) as u32 * self.item_height) as i32;
+        Some(Rectangle::new(
+            self.area.top_left + Point::new(0, offset),
+            Size::new(self.area.size.width, self.item_height),
+        ))
+    }
+
+    /// Returns the state of the shown items, if the indices are small enough to be stored.
+    fn state(&self) -> Option<u32> {
+        const BITS: u32 = 10;
+        let index = |index: usize| u32::try_from(index).ok().filter(|&i| i < 1 << BITS);
+        let first = index(self.first)?;
+        let selected = index(self.selected.map_or(0, |i| i.saturating_add(1)))?;
+        let highlighted = index(self.highlighted.map_or(0, |i| i.saturating_add(1)))?;
+        Some(first | selected << BITS | highlighted << (2 * BITS))
+    }
+
+    /// Returns the index of the item at the given point, if any.
+    pub fn item_at(&self, point: Point) -> Option<usize> {
+        if !self.area.contains(point) {
+            return None;
+        }
+        let index =
+            self.first + ((point.y - self.area.top_left.y) as u32 / self.item_height) as usize;
+        self.item_area(index).map(|_| index)
+    }
+}
+
+impl<'a, COL, DRAW> Ui<'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Sets the [OverlayState], which lets open popups capture the pointer interaction.
+    ///
+    /// Call this once per frame on the top-level [Ui], before [Ui::interact].
+    /// Without an overlay state, popups are still drawn on top of the other widgets, but taps on
+    /// them also reach the widgets below.
+    ///
+    /// If a popup or modal was closed in the previous frame (see [OverlayState::needs_redraw]),
+    /// the background is cleared, so that everything below it is redrawn.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::ui::OverlayState;
+    /// # use embedded_graphics::prelude::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// let overlay = OverlayState::new();
+    /// // in the drawing loop:
+    /// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_overlay_state(&overlay);
+    /// // ... add widgets ...
+    /// ui.draw_overlay().ok();
+    /// ```
+    pub fn set_overlay_state(&mut self, overlay: &'a OverlayState) {
+        overlay.start_frame();
+        self.overlay_state = Some(overlay);
+        if overlay.needs_redraw() {
+            // a failed clear shows up again when the widgets are drawn
+            self.clear_background().ok();
+        }
+    }
+
+    /// Notes that the given area is painted, so that an open popup over it is repainted
+    /// (see [Ui::overlay]).
+    pub(super) fn paint_below_overlay(&self, area: &Rectangle) {
+        if let Some(overlay) = self.overlay_state {
+            match self.painter.clip {
+                Some(clip) => overlay.paint(&clip.intersection(area)),
+                None => overlay.paint(area),
+            }
+        }
+    }
+
+    /// Returns the pointer interaction for an open popup.
+    ///
+    /// With an [OverlayState], this is the interaction captured by the popup (which the other widgets
+    /// don't receive). Otherwise, it is the current interaction of the [Ui].
+    pub fn overlay_interaction(&self) -> Interaction {
+        match self.overlay_state {
+            Some(overlay) => overlay.interaction(),
+            None => self.interact,
+        }
+    }
+
+    /// Shows a popup on top of all other widgets.
+    ///
+    /// The popup is drawn by [Ui::draw_overlay], and has to be shown again in each frame in which
+    /// it is open. Only one popup is shown at a time.
+    pub fn show_overlay(&mut self, popup: ListPopup<'a>) {
+        if self.measuring {
+            return;
+        }
+        if let Some(overlay) = self.overlay_state {
+            overlay.open(popup.area());
+        }
+        self.overlay = Some(popup);
+    }
+
+    /// Closes the open popup (if any), so that it no longer captures interactions.
+    pub fn close_overlay(&mut self) {
+        if let Some(overlay) = self.overlay_state {
+            overlay.close();
+        }
+        self.overlay = None;
+    }
+
+    /// Returns the area in which popups can be placed next to the given anchor (e.g. the widget
+    /// that opens the popup).
+    ///
+    /// The popup is placed below the anchor if there is enough space for its desired height
+    /// (or at least more space than above it), and above the anchor otherwise.
+    pub fn popup_area(&self, anchor: &Rectangle, desired_size: Size) -> Rectangle {
+        let screen = self.painter.bounding_box();
+        let screen_bottom = screen.top_left.y + screen.size.height as i32;
+        let anchor_bottom = anchor.top_left.y + anchor.size.height as i32;
+
+        let below = (screen_bottom - anchor_bottom).max(0) as u32;
+        let above = (anchor.top_left.y - screen.top_left.y).max(0) as u32;
+
+        let width = min(desired_size.width, screen.size.width);
+        let x = anchor
+            .top_left
+            .x
+            .min(screen.top_left.x + (screen.size.width - width) as i32);
+
+        if below >= desired_size.height || below >= above {
+            let height = min(desired_size.height, below);
+            Rectangle::new(Point::new(x, anchor_bottom), Size::new(width, height))
+        } else {
+            let height = min(desired_size.height, above);
+            Rectangle::new(
+                Point::new(x, anchor.top_left.y - height as i32),
+                Size::new(width, height),
+            )
+        }
+    }
+
+    /// Draws the popup shown in this frame (if any) on top of all other widgets.
+    ///
+    /// Call this at the end of each frame on the top-level [Ui], after adding all other widgets. Popups
+    /// shown in sub-[Ui]s (e.g. layouts, grid cells or scroll areas) are handed up to it.
+    /// The popup is drawn with [Ui::overlay], so it is only repainted when it opens, when its items
+    /// change or when something below it was painted in this frame.
+    pub fn draw_overlay(&mut self) -> GuiResult<()> {
+        let Some(popup) = self.overlay.take() else {
+            return Ok(());
+        };
+
+        let content = self.overlay_state.map(|overlay| &overlay.content);
+        self.overlay_content(popup.area(), |ui, repaint| {
+            let state = popup.state();
+            let prev = content.map(|content| content.replace(state));
+            if repaint || state.is_none() || prev != Some(state) {
+                ui.draw_list_popup(&popup)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Draws content on top of all other widgets in the given area, e.g. a tooltip or a
+    /// context menu.
+    ///
+    /// Call this at the end of the frame, after adding all other widgets, in each frame in which
+    /// the content is shown. The content is added by the closure, in a sub-[Ui] that covers the
+    /// area and isn't restricted to the area of e.g. a scroll area.
+    ///
+    /// With an [OverlayState], the content captures all pointer interactions (which only the
+    /// content receives), and the area is only repainted when the content is shown for the first
+    /// time, or when something below it was painted in this frame. The area is cleared then and the
+    /// sub-[Ui] is [cleared](Ui::cleared), so the content can use smartstates. Without an overlay
+    /// state, the area is repainted in every frame, and taps on the content also reach the widgets
+    /// below. Only one overlay is shown at a time, and it is closed in the frame after the first
+    /// frame in which it isn't shown (or right away with [Ui::close_overlay]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::Ui;
+    /// # use kolibri_embedded_gui::ui::OverlayState;
+    /// # use kolibri_embedded_gui::widgets::Label;
+    /// # use kolibri_embedded_gui::smartstate::SmartstateProvider;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let mut smartstates = SmartstateProvider::<20>::new();
+    /// let overlay = OverlayState::new();
+    /// let show_hint = true;
+    /// // in the drawing loop:
+    /// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// ui.set_overlay_state(&overlay);
+    /// // ... add widgets ...
+    /// if show_hint {
+    ///     let hint = Rectangle::new(Point::new(200, 10), Size::new(110, 30));
+    ///     ui.overlay(hint, |ui| {
+    ///         ui.add(Label::new("Hold to reset").smartstate(smartstates.nxt()));
+    ///         Ok(())
+    ///     })
+    ///     .ok();
+    /// }
+    /// ```
+    pub fn overlay<F>(&mut self, area: Rectangle, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.overlay_content(area, |ui, repaint| {
+            if repaint {
+                ui.paint(&area, |ui| ui.clear_area(area))?;
+            }
+            f(ui)
+        })
+    }
+
+    /// Adds the content of an overlay in the given area (see [Ui::overlay]), which paints the
+    /// whole area itself if it has to be repainted (the second argument of the closure).
+    pub(super) fn overlay_content<F>(&mut self, area: Rectangle, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>, bool) -> GuiResult<()>,
+    {
+        if self.measuring {
+            return Ok(());
+        }
+
+        // capture the interaction from the next frame on
+        let was_open = self.overlay_state.map(|overlay| overlay.is_open());
+        if let Some(overlay) = self.overlay_state {
+            overlay.open(area);
+        }
+        let repaint = self.cleared
+            || self
+                .overlay_state
+                .is_none_or(|overlay| overlay.needs_repaint());
+
+        // add the content with the captured interaction
+        let interaction = match (self.interact, was_open) {
+            (Interaction::None, _) => self.overlay_interaction(),
+            // the pointer interaction that opened the overlay belongs to the widgets underneath
+            (interaction, Some(false)) if interaction.get_point().is_some() => Interaction::None,
+            (interaction, _) => interaction,
+        };
+        let gesture = match was_open {
+            Some(true) => self.gestures.and_then(|gestures| gestures.gesture()),
+            _ => None,
+        };
+        let interact = core::mem::replace(&mut self.interact, interaction);
+        let gesture = core::mem::replace(&mut self.gesture, gesture);
+        let painting = self
+            .overlay_state
+            .map(|overlay| overlay.painting.replace(true));
+        let (res, _) = self.child_ui(area, Placer::new(area.size, true, Align::default()), |ui| {
+            ui.painter.clip = None;
+            ui.cleared = repaint;
+            f(ui, repaint)
+        });
+        if let (Some(overlay), Some(painting)) = (self.overlay_state, painting) {
+            overlay.painting.set(painting);
+        }
+        self.interact = interact;
+        self.gesture = gesture;
+        res
+    }
+
+    fn draw_list_popup(&mut self, popup: &ListPopup) -> GuiResult<()> {
+        let style = self.style;
+        let padding = style.spacing.button_padding;
+
+        self.paint(&popup.area, |ui| {
+            ui.draw(
+                &popup
+                    .area
+                    .into_styled(PrimitiveStyle::with_fill(style.item_background_color)),
+            )?;
+
+            for (index, option) in popup.options.iter().enumerate().skip(popup.first) {
+                let Some(area) = popup.item_area(index) else {
+                    break;
+                };
+                let fill = if popup.highlighted == Some(index) {
+                    Some(style.primary_color)
+                } else if popup.selected == Some(index) {
+                    Some(style.highlight_item_background_color)
+                } else {
+                    None
+                };
+                if let Some(fill) = fill {
+                    ui.draw(&area.into_styled(PrimitiveStyle::with_fill(fill)))?;
+                }
+                ui.draw(&style.text(
+                    option,
+                    area.top_left + Point::new(padding.width as i32, padding.height as i32),
+                    fill.unwrap_or(style.item_background_color),
+                ))?;
+            }
+
+            // outline on top of the items, so that popups stand out even in borderless styles
+            ui.draw(
+                &popup
+                    .area
+                    .into_styled(PrimitiveStyle::with_stroke(style.highlight_border_color, 1)),
+            )?;
+            Ok(())
+        })
+    }
+}
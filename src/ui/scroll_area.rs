@@ -0,0 +1,447 @@
+use super::{Align, Placer, Ui};
+use crate::{GuiResult, Interaction, Response};
+use core::cmp::{max, min};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+
+/// Distance in pixels a drag has to move before it scrolls the content
+/// (instead of interacting with the widget below it).
+const DRAG_THRESHOLD: u32 = 4;
+
+/// Default width of the scrollbar in pixels
+const DEFAULT_SCROLLBAR_WIDTH: u32 = 6;
+
+/// Minimum height of the scrollbar thumb in pixels
+const MIN_THUMB_HEIGHT: u32 = 8;
+
+/// Height of the virtual [Ui] inside a [ScrollArea]. Content placed below this is not shown.
+const MAX_CONTENT_HEIGHT: u32 = i16::MAX as u32;
+
+/// State of a [ScrollArea], which has to persist across frames.
+///
+/// This is owned by the caller (like a [Smartstate](crate::smartstate::Smartstate)),
+/// so no allocations are needed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScrollState {
+    /// Scroll offset in pixels from the top of the content
+    offset: u32,
+    /// Height of the content in the last frame
+    content_height: u32,
+    /// Pointer position (y) and scroll offset at the start of the current drag
+    drag_anchor: Option<(i32, u32)>,
+    /// Whether the current drag scrolls the content (instead of interacting with it)
+    scrolling: bool,
+    /// Offset the content was last drawn with
+    drawn_offset: Option<u32>,
+}
+
+impl ScrollState {
+    /// Creates a new scroll state, scrolled to the top.
+    pub const fn new() -> Self {
+        ScrollState {
+            offset: 0,
+            content_height: 0,
+            drag_anchor: None,
+            scrolling: false,
+            drawn_offset: None,
+        }
+    }
+
+    /// Returns the current scroll offset in pixels.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Sets the scroll offset in pixels.
+    ///
+    /// The offset is clamped to the content height when the [ScrollArea] is shown.
+    pub fn set_offset(&mut self, offset: u32) {
+        self.offset = offset;
+    }
+
+    /// Scrolls by the given amount of pixels (positive values scroll down).
+    ///
+    /// This is useful for scrolling with buttons or encoders.
+    pub fn scroll_by(&mut self, delta: i32) {
+        self.offset = self.offset.saturating_add_signed(delta);
+    }
+
+    /// Returns the height of the content, as measured in the last frame.
+    pub fn content_height(&self) -> u32 {
+        self.content_height
+    }
+
+    /// Forces the content to be redrawn (and the viewport to be cleared) the next time
+    /// the [ScrollArea] is shown.
+    pub fn force_redraw(&mut self) {
+        self.drawn_offset = None;
+    }
+}
+
+/// A vertically scrollable area, showing content that is taller than the available space.
+///
+/// The content is placed in a virtual [Ui] that is scrolled by dragging inside the area.
+/// Drawing is clipped to the visible area (the viewport), and widgets placed entirely outside
+/// of it are placed as usual, but not drawn (see [Ui::paint]).
+///
+/// ## Incremental Redrawing
+///
+/// Whenever the content is scrolled, the viewport is cleared and all content has to be
+/// redrawn. [Ui::cleared] returns `true` in the content's [Ui] in that case, so the widgets
+/// of the content redraw themselves despite their smartstates (see
+/// [Ui::force_redraw_if_cleared]).
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # use embedded_graphics::primitives::Rectangle;
+/// # use embedded_iconoir::prelude::*;
+/// # use kolibri_embedded_gui::ui::*;
+/// # use kolibri_embedded_gui::widgets::Label;
+/// # use kolibri_embedded_gui::smartstate::SmartstateProvider;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let output_settings = OutputSettingsBuilder::new().build();
+/// # let mut window = Window::new("Kolibri Example", &output_settings);
+/// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// // keep this across frames
+/// let mut scroll = ScrollState::new();
+/// let mut smartstates = SmartstateProvider::<50>::new();
+///
+/// // every frame:
+/// smartstates.restart_counter();
+/// ScrollArea::new(&mut scroll)
+///     .height(150)
+///     .show(&mut ui, |ui| {
+///         for _ in 0..50 {
+///             ui.add(Label::new("List item").smartstate(smartstates.nxt()));
+///         }
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+pub struct ScrollArea<'s> {
+    state: &'s mut ScrollState,
+    height: Option<u32>,
+    scrollbar: bool,
+    scrollbar_width: u32,
+}
+
+impl<'s> ScrollArea<'s> {
+    /// Creates a new scroll area using the given state.
+    ///
+    /// By default, the scroll area takes the remaining space of the [Ui] and shows a scrollbar.
+    pub fn new(state: &'s mut ScrollState) -> Self {
+        ScrollArea {
+            state,
+            height: None,
+            scrollbar: true,
+            scrollbar_width: DEFAULT_SCROLLBAR_WIDTH,
+        }
+    }
+
+    /// Sets the height of the visible area (limited to the remaining space of the [Ui]).
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Sets whether a scrollbar is shown on the right side of the area.
+    pub fn scrollbar(mut self, scrollbar: bool) -> Self {
+        self.scrollbar = scrollbar;
+        self
+    }
+
+    /// Sets the width of the scrollbar in pixels.
+    pub fn scrollbar_width(mut self, width: u32) -> Self {
+        self.scrollbar_width = width;
+        self
+    }
+
+    /// Shows the scroll area in the given [Ui], placing the content added in the closure.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] with a [Response] for the whole area, which is `changed()` if the
+    /// content was scrolled, and `down()` while the content is being dragged.
+    pub fn show<DRAW, COL, F>(self, ui: &mut Ui<DRAW, COL>, f: F) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let available = ui.space_available();
+        let height = min(self.height.unwrap_or(available.height), available.height);
+        let iresponse = ui.allocate_space(Size::new(available.width, height))?;
+        if ui.is_measuring() {
+            // the content is neither scrolled nor drawn while measuring
+            return Ok(Response::new(iresponse));
+        }
+        let area = iresponse.area;
+
+        let scrollbar_width = if self.scrollbar {
+            min(self.scrollbar_width, area.size.width)
+        } else {
+            0
+        };
+        let viewport = Rectangle::new(
+            area.top_left,
+            Size::new(area.size.width - scrollbar_width, area.size.height),
+        );
+
+        let state = self.state;
+        let prev_offset = state.offset;
+        let content_interaction =
+            state.handle_interaction(ui.interact, &viewport, ui.pointer_captured());
+
+        // the content height is only known after the content was placed once
+        if state.drawn_offset.is_some() {
+            let max_offset = state.content_height.saturating_sub(viewport.size.height);
+            state.offset = min(state.offset, max_offset);
+        }
+
+        let redraw = ui.cleared() || state.drawn_offset != Some(state.offset);
+        if redraw {
+            ui.clear_area(viewport)?;
+        }
+
+        let offset = state.offset;
+        let bounds = Rectangle::new(
+            viewport.top_left - Point::new(0, offset as i32),
+            Size::new(viewport.size.width, MAX_CONTENT_HEIGHT),
+        );
+        let placer = Placer::new(bounds.size, true, Align::default());
+        let (res, placer) = ui.child_ui(bounds, placer, |ui| {
+            ui.painter.set_clip(viewport);
+            ui.scroll_offset.y += offset as i32;
+            ui.interact = content_interaction;
+            ui.cleared = redraw;
+            (f)(ui)
+        });
+
+        let prev_content_height = state.content_height;
+        state.content_height = placer.used_size().height;
+        state.drawn_offset = Some(offset);
+
+        if scrollbar_width > 0 && (redraw || prev_content_height != state.content_height) {
+            let track = Rectangle::new(
+                viewport.top_left + Point::new(viewport.size.width as i32, 0),
+                Size::new(scrollbar_width, viewport.size.height),
+            );
+            ui.draw_scrollbar(track, offset, state.content_height)?;
+        }
+
+        res?;
+        Ok(Response::new(iresponse)
+            .set_changed(offset != prev_offset)
+            .set_down(state.scrolling)
+            .set_redraw(redraw))
+    }
+}
+
+impl ScrollState {
+    /// Updates the drag state and offset from the given interaction.
+    ///
+    /// If a widget owns the pointer (see [PointerCapture](super::PointerCapture)), pointer
+    /// interactions outside of the viewport are passed on as well, as the owner may be part of
+    /// the content.
+    ///
+    /// ## Returns
+    ///
+    /// The interaction to pass on to the content.
+    fn handle_interaction(
+        &mut self,
+        interaction: Interaction,
+        viewport: &Rectangle,
+        captured: bool,
+    ) -> Interaction {
+        let in_viewport = interaction
+            .get_point()
+            .map(|pt| viewport.contains(pt))
+            .unwrap_or(false);
+
+        match interaction {
+            Interaction::Click(pt) if in_viewport => {
+                self.drag_anchor = Some((pt.y, self.offset));
+                self.scrolling = false;
+            }
+            Interaction::Drag(pt) => {
+                if let Some((start, start_offset)) = self.drag_anchor {
+                    let delta = start - pt.y;
+                    if self.scrolling || delta.unsigned_abs() >= DRAG_THRESHOLD {
+                        self.scrolling = true;
+                        self.offset = start_offset.saturating_add_signed(delta);
+                    }
+                }
+            }
+            Interaction::Release(_) => {
+                self.drag_anchor = None;
+                // a release that ends scrolling must not click the widget below
+                if core::mem::take(&mut self.scrolling) {
+                    return Interaction::None;
+                }
+            }
+            _ => {
+                self.drag_anchor = None;
+                self.scrolling = false;
+            }
+        }
+
+        // non-pointer interactions (e.g. focus navigation) are always passed on
+        let pointer = interaction.get_point().is_some();
+        if self.scrolling || (pointer && !in_viewport && !captured) {
+            Interaction::None
+        } else {
+            interaction
+        }
+    }
+}
+
+impl<COL, DRAW> Ui<'_, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Draws a vertical scrollbar in the given track for the given offset and content height.
+    fn draw_scrollbar(
+        &mut self,
+        track: Rectangle,
+        offset: u32,
+        content_height: u32,
+    ) -> GuiResult<()> {
+        let visible = track.size.height;
+        let thumb_height = if content_height > visible {
+            max(
+                (visible as u64 * visible as u64 / content_height as u64) as u32,
+                min(MIN_THUMB_HEIGHT, visible),
+            )
+        } else {
+            visible
+        };
+        let max_offset = content_height.saturating_sub(visible);
+        let thumb_pos = if max_offset > 0 {
+            ((visible - thumb_height) as u64 * min(offset, max_offset) as u64 / max_offset as u64)
+                as u32
+        } else {
+            0
+        };
+
+        self.paint(&track, |ui| {
+            ui.draw(
+                &track.into_styled(PrimitiveStyle::with_fill(ui.style().item_background_color)),
+            )?;
+            ui.draw(
+                &Rectangle::new(
+                    track.top_left + Point::new(0, thumb_pos as i32),
+                    Size::new(track.size.width, thumb_height),
+                )
+                .into_styled(PrimitiveStyle::with_fill(ui.style().primary_color)),
+            )?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use crate::widgets::Spacer;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_drag_to_scroll() {
+        let viewport = Rectangle::new(Point::zero(), Size::new(50, 50));
+        let mut state = ScrollState::new();
+
+        let click = Interaction::Click(Point::new(10, 40));
+        assert_eq!(state.handle_interaction(click, &viewport, false), click);
+
+        // small movements still interact with the content
+        let drag = Interaction::Drag(Point::new(10, 38));
+        assert_eq!(state.handle_interaction(drag, &viewport, false), drag);
+        assert_eq!(state.offset(), 0);
+
+        let drag = Interaction::Drag(Point::new(10, 20));
+        assert_eq!(
+            state.handle_interaction(drag, &viewport, false),
+            Interaction::None
+        );
+        assert_eq!(state.offset(), 20);
+
+        // releasing after scrolling doesn't click anything
+        let release = Interaction::Release(Point::new(10, 20));
+        assert_eq!(
+            state.handle_interaction(release, &viewport, false),
+            Interaction::None
+        );
+        assert_eq!(state.offset(), 20);
+    }
+
+    #[test]
+    fn test_scroll_area_clipping() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut style = medsize_rgb565_style();
+        style.spacing.item_spacing = Size::zero();
+        style.spacing.window_border_padding = Size::zero();
+        style.default_widget_height = 0;
+        let mut ui = Ui::new_fullscreen(&mut display, style);
+
+        let mut state = ScrollState::new();
+        state.set_offset(10);
+        let mut placed = 0;
+        ScrollArea::new(&mut state)
+            .height(20)
+            .show(&mut ui, |ui| {
+                for _ in 0..4 {
+                    ui.add_raw(Spacer::new(Size::new(10, 10)))?;
+                    ui.new_row();
+                    placed += 1;
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        // widgets outside of the viewport are placed as well, so the content height is known
+        assert_eq!(placed, 4);
+        assert_eq!(state.content_height(), 40);
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_pointer_capture_outside_of_viewport() {
+        use crate::testing::{Harness, Script};
+        use crate::widgets::Slider;
+
+        let mut harness = Harness::new(Size::new(320, 120), medsize_rgb565_style());
+        let mut state = ScrollState::new();
+        let mut value = 0i32;
+        let mut slider = Rectangle::zero();
+        let mut run = |harness: &mut Harness<_>, script: Script| {
+            harness.run(script, |ui| {
+                ScrollArea::new(&mut state)
+                    .height(60)
+                    .show(ui, |ui| {
+                        slider = ui.add(Slider::new(&mut value, 0..=100)).internal.area;
+                        Ok(())
+                    })
+                    .unwrap();
+            });
+            (value, slider, state.offset())
+        };
+        let (_, slider, _) = run(&mut harness, Script::new().idle(1));
+
+        // the slider keeps following the pointer onto the scrollbar, outside of the viewport
+        let start = slider.center();
+        let script = Script::new().drag(start, Point::new(318, start.y), 1);
+        assert_eq!(run(&mut harness, script), (100, slider, 0));
+    }
+}
@@ -2,17 +2,16 @@
 //!
 //! See [Button] for more info.
 
+use crate::font::FontText;
 use crate::smartstate::{Container, Smartstate};
 use crate::{GuiResult, Interaction, Response, Ui, Widget};
 use core::cmp::max;
 use core::ops::Add;
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::{Point, Size};
-use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::PixelColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::{PrimitiveStyleBuilder, Rectangle};
-use embedded_graphics::text::{Baseline, Text};
 
 /// # Button Widget
 ///
@@ -78,6 +77,7 @@ use embedded_graphics::text::{Baseline, Text};
 pub struct Button<'a> {
     label: &'a str,
     smartstate: Container<'a, Smartstate>,
+    enabled: bool,
 }
 
 impl<'a> Button<'a> {
@@ -92,6 +92,7 @@ impl<'a> Button<'a> {
         Button {
             label,
             smartstate: Container::empty(),
+            enabled: true,
         }
     }
 
@@ -109,21 +110,40 @@ impl<'a> Button<'a> {
         self.smartstate.set(smartstate);
         self
     }
+
+    /// Enables or disables the button (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled button is greyed out, and can't be clicked or focused.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
 }
 
 impl Widget for Button<'_> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
         ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |button| button.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl Button<'_> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response> {
         // get size
         let font = ui.style().default_font;
 
-        let mut text = Text::new(
-            self.label,
-            Point::new(0, 0),
-            MonoTextStyle::new(&font, ui.style().text_color),
-        );
+        let mut text = FontText::new(self.label, Point::zero(), font, ui.style().text_color);
 
         let height = ui.style().default_widget_height;
         let size = text.bounding_box();
@@ -131,7 +151,7 @@ impl Widget for Button<'_> {
         let border = ui.style().border_width;
 
         // allocate space
-        let iresponse = ui.allocate_space(Size::new(
+        let iresponse = ui.allocate_focusable_space(Size::new(
             size.size.width + 2 * padding.width + 2 * border,
             max(size.size.height + 2 * padding.height + 2 * border, height),
         ))?;
@@ -142,10 +162,8 @@ impl Widget for Button<'_> {
             (padding.height + border) as i32,
         )));
 
-        text.text_style.baseline = Baseline::Top;
-
         // check for click
-        let click = matches!(iresponse.interaction, Interaction::Release(_));
+        let click = iresponse.released();
         let down = matches!(
             iresponse.interaction,
             Interaction::Click(_) | Interaction::Drag(_)
@@ -186,16 +204,20 @@ impl Widget for Button<'_> {
         };
 
         if !self.smartstate.eq_option(&prevstate) {
-            ui.start_drawing(&iresponse.area);
-
-            ui.draw(
-                &Rectangle::new(iresponse.area.top_left, iresponse.area.size)
-                    .into_styled(rect_style),
-            )
-            .ok();
-            ui.draw(&text).ok();
-
-            ui.finalize()?;
+            let background = rect_style
+                .fill_color
+                .unwrap_or(ui.style().item_background_color);
+            let text = text.blend(background, ui.style().text_blend);
+
+            ui.paint(&iresponse.area, |ui| {
+                ui.draw(
+                    &Rectangle::new(iresponse.area.top_left, iresponse.area.size)
+                        .into_styled(rect_style),
+                )
+                .ok();
+                ui.draw(&text).ok();
+                Ok(())
+            })?;
         }
 
         Ok(Response::new(iresponse).set_clicked(click).set_down(down))
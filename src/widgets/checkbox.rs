@@ -74,6 +74,7 @@ use embedded_iconoir::{size12px, size18px, size24px, size32px};
 pub struct Checkbox<'a> {
     checked: &'a mut bool,
     smartstate: Container<'a, Smartstate>,
+    enabled: bool,
 }
 
 impl<'a> Checkbox<'a> {
@@ -81,6 +82,7 @@ impl<'a> Checkbox<'a> {
         Checkbox {
             checked,
             smartstate: Container::empty(),
+            enabled: true,
         }
     }
 
@@ -95,6 +97,14 @@ impl<'a> Checkbox<'a> {
         self.smartstate.set(smartstate);
         self
     }
+
+    /// Enables or disables the checkbox (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled checkbox is greyed out, and can't be toggled or focused.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
 }
 
 impl Checkbox<'_> {
@@ -128,6 +138,21 @@ impl Widget for Checkbox<'_> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
         ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |checkbox| checkbox.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl Checkbox<'_> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response> {
         // allocate space
 
@@ -138,12 +163,12 @@ impl Widget for Checkbox<'_> {
             let biggest_pad = max(pad.width, pad.height);
             Size::new(biggest_pad, biggest_pad)
         };
-        let iresponse = ui.allocate_space(Size::new(size, size))?;
+        let iresponse = ui.allocate_focusable_space(Size::new(size, size))?;
 
         // check interaction
 
         let mut changed = false;
-        if let Interaction::Release(_) = iresponse.interaction {
+        if iresponse.released() {
             *self.checked = !*self.checked;
             changed = true;
         }
@@ -180,51 +205,50 @@ impl Widget for Checkbox<'_> {
         let redraw = !self.smartstate.eq_option(&prevstate) || changed;
 
         if redraw {
-            ui.start_drawing(&iresponse.area);
+            ui.paint(&iresponse.area, |ui| {
+                // clear background if needed
 
-            // clear background if needed
+                if !ui.cleared() && size - padding.height < 12 {
+                    ui.clear_area(iresponse.area)?;
+                }
 
-            if !ui.cleared() && size - padding.height < 12 {
-                ui.clear_area(iresponse.area)?;
-            }
+                // draw
 
-            // draw
-
-            let rect = Rectangle::new(iresponse.area.top_left, iresponse.area.size);
-
-            ui.draw(&rect.into_styled(style.build()))
-                .map_err(|_| GuiError::DrawError(Some("Couldn't draw Checkbox")))?;
-
-            if *self.checked {
-                match size - padding.width {
-                    0..=18 => self.draw_icon(
-                        ui,
-                        size12px::actions::Check::new(ui.style().text_color),
-                        &iresponse.area,
-                        Point::new(6, 6),
-                    ),
-                    19..=23 => self.draw_icon(
-                        ui,
-                        size18px::actions::Check::new(ui.style().text_color),
-                        &iresponse.area,
-                        Point::new(9, 9),
-                    ),
-                    24..=32 => self.draw_icon(
-                        ui,
-                        size24px::actions::Check::new(ui.style().text_color),
-                        &iresponse.area,
-                        Point::new(12, 12),
-                    ),
-                    _ => self.draw_icon(
-                        ui,
-                        size32px::actions::Check::new(ui.style().text_color),
-                        &iresponse.area,
-                        Point::new(16, 16),
-                    ),
-                }?;
-            }
+                let rect = Rectangle::new(iresponse.area.top_left, iresponse.area.size);
+
+                ui.draw(&rect.into_styled(style.build()))
+                    .map_err(|_| GuiError::DrawError(Some("Couldn't draw Checkbox")))?;
 
-            ui.finalize()?;
+                if *self.checked {
+                    match size - padding.width {
+                        0..=18 => self.draw_icon(
+                            ui,
+                            size12px::actions::Check::new(ui.style().text_color),
+                            &iresponse.area,
+                            Point::new(6, 6),
+                        ),
+                        19..=23 => self.draw_icon(
+                            ui,
+                            size18px::actions::Check::new(ui.style().text_color),
+                            &iresponse.area,
+                            Point::new(9, 9),
+                        ),
+                        24..=32 => self.draw_icon(
+                            ui,
+                            size24px::actions::Check::new(ui.style().text_color),
+                            &iresponse.area,
+                            Point::new(12, 12),
+                        ),
+                        _ => self.draw_icon(
+                            ui,
+                            size32px::actions::Check::new(ui.style().text_color),
+                            &iresponse.area,
+                            Point::new(16, 16),
+                        ),
+                    }?;
+                }
+                Ok(())
+            })?;
         }
 
         Ok(Response::new(iresponse).set_changed(changed))
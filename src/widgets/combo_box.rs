@@ -0,0 +1,554 @@
+//! # ComboBox Widget
+//!
+//! A dropdown to pick one of many options, see [ComboBox] for more info.
+
+use crate::smartstate::{Container, Smartstate};
+use crate::ui::ListPopup;
+use crate::{GuiResult, Interaction, Response, Ui, Widget};
+use core::cmp::{max, min};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, PrimitiveStyleBuilder, Triangle};
+
+/// State of a [ComboBox], which has to persist across frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComboBoxState {
+    /// Whether the popup is open
+    open: bool,
+    /// Whether the popup was opened with focus navigation
+    editing: bool,
+    /// Index of the first item shown in the popup
+    first: usize,
+    /// Highlighted (pressed or focused) item
+    highlighted: Option<usize>,
+}
+
+impl ComboBoxState {
+    /// Creates a new combo box state, with the popup closed.
+    pub const fn new() -> Self {
+        ComboBoxState {
+            open: false,
+            editing: false,
+            first: 0,
+            highlighted: None,
+        }
+    }
+
+    /// Returns whether the popup is open.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Closes the popup.
+    pub fn close(&mut self) {
+        self.open = false;
+        self.editing = false;
+        self.highlighted = None;
+    }
+
+    /// Scrolls the popup so that the given item is shown.
+    fn scroll_to(&mut self, index: usize, visible: usize) {
+        if index < self.first {
+            self.first = index;
+        } else if visible > 0 && index >= self.first + visible {
+            self.first = index + 1 - visible;
+        }
+    }
+}
+
+/// # ComboBox Widget
+///
+/// A dropdown to pick one of many options. The collapsed combo box is drawn as a button showing the
+/// selected option. Tapping it opens a popup list of all options on top of the other widgets, which
+/// closes again when an option is picked, or when tapping next to the popup.
+///
+/// As the popup has to be drawn after all other widgets, the combo box is shown with
+/// [ComboBox::show] instead of [Ui::add] (it isn't a [Widget]), and [Ui::draw_overlay] has to be
+/// called at the end of the frame. To keep taps on the popup from reaching the widgets below, set an
+/// [OverlayState](crate::ui::OverlayState) on the [Ui].
+///
+/// With focus navigation, activating the combo box opens the popup, [Interaction::Next] and
+/// [Interaction::Previous] move through the options, and activating it again picks the option.
+/// [Interaction::Back] closes the popup without changing the selection.
+///
+/// The [Response] is `changed()` when a different option was picked.
+///
+/// # Example
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::{OverlayState, Ui};
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::smartstate::*;
+/// # use kolibri_embedded_gui::widgets::Button;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut smartstates = SmartstateProvider::<20>::new();
+/// use kolibri_embedded_gui::widgets::{ComboBox, ComboBoxState};
+///
+/// const MODES: [&str; 3] = ["Off", "Heat", "Cool"];
+///
+/// // keep these across frames
+/// let overlay = OverlayState::new();
+/// let mut mode_state = ComboBoxState::new();
+/// let mut mode = 0;
+///
+/// // every frame:
+/// let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// ui.set_overlay_state(&overlay);
+///
+/// if ComboBox::new(&MODES, &mut mode, &mut mode_state)
+///     .smartstate(smartstates.nxt())
+///     .show(&mut ui)
+///     .changed()
+/// {
+///     // mode changed
+/// }
+/// // the popup is drawn on top of this button
+/// ui.add(Button::new("Start").smartstate(smartstates.nxt()));
+///
+/// ui.draw_overlay().ok();
+/// ```
+pub struct ComboBox<'a, 'o> {
+    options: &'o [&'o str],
+    selected: &'a mut usize,
+    state: &'a mut ComboBoxState,
+    width: Option<u32>,
+    smartstate: Container<'a, Smartstate>,
+    enabled: bool,
+    /// Popup to show after drawing the collapsed combo box
+    popup: Option<ListPopup<'o>>,
+    /// Whether the popup was closed while drawing the combo box
+    closed: bool,
+}
+
+impl<'a, 'o> ComboBox<'a, 'o> {
+    /// Creates a new combo box with the given options and selected option (index into `options`).
+    pub fn new(
+        options: &'o [&'o str],
+        selected: &'a mut usize,
+        state: &'a mut ComboBoxState,
+    ) -> Self {
+        ComboBox {
+            options,
+            selected,
+            state,
+            width: None,
+            smartstate: Container::empty(),
+            enabled: true,
+            popup: None,
+            closed: false,
+        }
+    }
+
+    /// Sets the width of the combo box (and its popup).
+    ///
+    /// By default, the combo box is wide enough for the longest option.
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Adds a smartstate to the collapsed combo box for incremental redrawing.
+    pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
+        self.smartstate.set(smartstate);
+        self
+    }
+
+    /// Enables or disables the combo box (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled combo box shows the selected option greyed out, and can't be opened. An open popup
+    /// is closed when the combo box is disabled.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Shows the combo box in the given [Ui], like [Ui::add], and shows its popup if it is open.
+    ///
+    /// As the popup is drawn at the end of the frame (see [Ui::draw_overlay]), the options have to
+    /// outlive the [Ui].
+    pub fn show<'u, DRAW, COL>(mut self, ui: &mut Ui<'u, DRAW, COL>) -> Response
+    where
+        'o: 'u,
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        let response = ui.add(Collapsed(&mut self));
+        if let Some(popup) = self.popup {
+            ui.show_overlay(popup);
+        } else if self.closed {
+            ui.close_overlay();
+        }
+        response
+    }
+
+    fn select(&mut self, index: usize) -> bool {
+        let changed = *self.selected != index;
+        *self.selected = index;
+        changed
+    }
+}
+
+/// The collapsed combo box, which is added to the [Ui] like any other widget by [ComboBox::show].
+///
+/// [ComboBox] itself isn't a [Widget], as adding it with [Ui::add] wouldn't show its popup.
+struct Collapsed<'c, 'a, 'o>(&'c mut ComboBox<'a, 'o>);
+
+impl Widget for Collapsed<'_, '_, '_> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.0.enabled;
+        ui.draw_enabled(
+            self.0,
+            enabled,
+            |combo| combo.smartstate.as_mut_slice(),
+            ComboBox::draw_widget,
+        )
+    }
+}
+
+impl ComboBox<'_, '_> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        // get size
+        let style = *ui.style();
+        let font = style.default_font;
+        let padding = style.spacing.button_padding;
+        let border = style.border_width;
+
+        let text_width = self
+            .options
+            .iter()
+            .map(|option| font.text_width(option))
+            .max()
+            .unwrap_or(0);
+        let arrow_size = font.line_height() / 2;
+        let item_height = font.line_height() + 2 * padding.height;
+
+        let width = self
+            .width
+            .unwrap_or(text_width + arrow_size + 3 * padding.width + 2 * border);
+        let height = max(item_height + 2 * border, style.default_widget_height);
+
+        // allocate space
+        let focus = ui.register_focus(true);
+        let iresponse = ui.allocate_space(Size::new(width, height))?;
+
+        let desired_popup = Size::new(width, self.options.len() as u32 * item_height);
+        let popup_area = ui.popup_area(&iresponse.area, desired_popup);
+        let visible = min(
+            (popup_area.size.height / item_height) as usize,
+            self.options.len(),
+        );
+
+        // handle interaction
+        let mut changed = false;
+        let was_open = self.state.open;
+        if !ui.is_enabled() {
+            self.state.close();
+        }
+        if self.state.open {
+            let popup =
+                ListPopup::new(popup_area, item_height, self.options).first(self.state.first);
+            match ui.overlay_interaction() {
+                Interaction::Click(pt) | Interaction::Drag(pt) | Interaction::Hover(pt) => {
+                    self.state.highlighted = popup.item_at(pt);
+                }
+                Interaction::Release(pt) => {
+                    if let Some(index) = popup.item_at(pt) {
+                        changed = self.select(index);
+                    }
+                    self.state.close();
+                }
+                _ => {}
+            }
+
+            if self.state.open && focus.activated {
+                if let Some(index) = self.state.highlighted {
+                    changed = self.select(index);
+                }
+                self.state.close();
+            } else if self.state.open && focus.editing && focus.adjust != 0 {
+                let last = self.options.len().saturating_sub(1);
+                let index = self.state.highlighted.unwrap_or(*self.selected);
+                let index = index.saturating_add_signed(focus.adjust as isize).min(last);
+                self.state.highlighted = Some(index);
+                self.state.scroll_to(index, visible);
+            } else if self.state.editing && !focus.editing {
+                // left with Interaction::Back
+                self.state.close();
+            }
+        } else if iresponse.released() || focus.activated {
+            self.state.open = true;
+            self.state.editing = focus.editing;
+            self.state.first = 0;
+            self.state.highlighted = focus.editing.then_some(*self.selected);
+            self.state.scroll_to(*self.selected, visible);
+        }
+
+        if self.state.open {
+            self.popup = Some(
+                ListPopup::new(popup_area, item_height, self.options)
+                    .first(self.state.first)
+                    .selected(Some(*self.selected))
+                    .highlighted(self.state.highlighted),
+            );
+        }
+        self.closed = was_open && !self.state.open;
+
+        // styles and smartstate
+        let interaction = if was_open || self.state.open {
+            Interaction::None
+        } else {
+            focus.as_pointer_interaction(&iresponse.area, iresponse.interaction)
+        };
+        let prevstate = self.smartstate.clone_inner();
+        let look = match interaction {
+            _ if self.state.open => 3,
+            Interaction::None => 0,
+            Interaction::Hover(_) => 1,
+            _ => 2,
+        };
+        self.smartstate
+            .modify(|st| *st = Smartstate::state(look | (*self.selected as u32) << 2));
+
+        if !self.smartstate.eq_option(&prevstate) {
+            let rect_style = match look {
+                0 => PrimitiveStyleBuilder::new()
+                    .stroke_color(style.border_color)
+                    .stroke_width(style.border_width)
+                    .fill_color(style.item_background_color)
+                    .build(),
+                1 | 3 => PrimitiveStyleBuilder::new()
+                    .stroke_color(style.highlight_border_color)
+                    .stroke_width(style.highlight_border_width)
+                    .fill_color(style.highlight_item_background_color)
+                    .build(),
+                _ => PrimitiveStyleBuilder::new()
+                    .stroke_color(style.highlight_border_color)
+                    .stroke_width(style.highlight_border_width)
+                    .fill_color(style.primary_color)
+                    .build(),
+            };
+
+            let area = iresponse.area;
+            ui.paint(&area, |ui| {
+                ui.draw(&area.into_styled(rect_style))?;
+
+                if let Some(option) = self.options.get(*self.selected) {
+                    ui.draw(&style.text(
+                        option,
+                        area.top_left
+                            + Point::new(
+                                (padding.width + border) as i32,
+                                (area.size.height - font.line_height()) as i32 / 2,
+                            ),
+                        rect_style.fill_color.unwrap_or(style.item_background_color),
+                    ))?;
+                }
+
+                // arrow pointing down
+                let arrow_left = area.top_left
+                    + Point::new(
+                        (area.size.width - padding.width - border - arrow_size) as i32,
+                        (area.size.height - arrow_size / 2) as i32 / 2,
+                    );
+                ui.draw(
+                    &Triangle::new(
+                        arrow_left,
+                        arrow_left + Point::new(arrow_size as i32, 0),
+                        arrow_left + Point::new(arrow_size as i32 / 2, arrow_size as i32 / 2),
+                    )
+                    .into_styled(PrimitiveStyle::with_fill(style.text_color)),
+                )?;
+                Ok(())
+            })?;
+        }
+
+        Ok(Response::new(iresponse)
+            .set_clicked(!was_open && self.state.open)
+            .set_changed(changed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use crate::ui::OverlayState;
+    use crate::widgets::Button;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    const OPTIONS: [&str; 2] = ["A", "B"];
+
+    /// Draws a frame with a combo box and a button below it, and returns whether the button
+    /// was clicked.
+    fn frame(
+        overlay: &OverlayState,
+        selected: &mut usize,
+        state: &mut ComboBoxState,
+        interaction: Interaction,
+    ) -> (Response, bool) {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut style = medsize_rgb565_style();
+        style.spacing.button_padding = Size::new(1, 1);
+        let mut ui = Ui::new_fullscreen(&mut display, style);
+        ui.set_overlay_state(overlay);
+        ui.interact(interaction);
+        let response = ComboBox::new(&OPTIONS, selected, state)
+            .width(40)
+            .show(&mut ui);
+        let clicked = ui.add(Button::new("X")).clicked();
+        ui.draw_overlay().unwrap();
+        (response, clicked)
+    }
+
+    #[test]
+    fn test_combo_box_select() {
+        let overlay = OverlayState::new();
+        let mut state = ComboBoxState::new();
+        let mut selected = 0;
+
+        // the combo box spans y = 3..20, the popup y = 20..54 with items of height 17
+        let (response, _) = frame(
+            &overlay,
+            &mut selected,
+            &mut state,
+            Interaction::Release(Point::new(10, 10)),
+        );
+        assert!(response.clicked());
+        assert!(state.is_open());
+        assert!(overlay.is_open());
+
+        // the tap on the second item doesn't reach the button below it
+        let (response, clicked) = frame(
+            &overlay,
+            &mut selected,
+            &mut state,
+            Interaction::Release(Point::new(10, 40)),
+        );
+        assert!(response.changed());
+        assert!(!clicked);
+        assert_eq!(selected, 1);
+        assert!(!state.is_open());
+
+        frame(&overlay, &mut selected, &mut state, Interaction::None);
+        assert!(!overlay.is_open());
+        assert!(overlay.needs_redraw());
+    }
+
+    #[test]
+    fn test_combo_box_outside_tap() {
+        let overlay = OverlayState::new();
+        let mut state = ComboBoxState::new();
+        let mut selected = 1;
+
+        frame(
+            &overlay,
+            &mut selected,
+            &mut state,
+            Interaction::Release(Point::new(10, 10)),
+        );
+        let (response, clicked) = frame(
+            &overlay,
+            &mut selected,
+            &mut state,
+            Interaction::Release(Point::new(60, 60)),
+        );
+        assert!(!response.changed());
+        assert!(!clicked);
+        assert_eq!(selected, 1);
+        assert!(!state.is_open());
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_combo_box_in_sub_layout() {
+        use crate::testing::{Harness, Script};
+        use embedded_graphics::primitives::Rectangle;
+
+        let style = medsize_rgb565_style();
+        let mut harness = Harness::new(Size::new(128, 96), style);
+        let mut state = ComboBoxState::new();
+        let mut selected = 0;
+        let mut button = Rectangle::zero();
+        let mut run = |harness: &mut Harness<_>, script: Script| {
+            harness.run(script, |ui| {
+                ui.vertical(|ui| {
+                    ComboBox::new(&OPTIONS, &mut selected, &mut state)
+                        .width(60)
+                        .show(ui);
+                    Ok(())
+                })
+                .unwrap();
+                button = ui.add(Button::new("Below")).internal.area;
+            });
+        };
+        run(&mut harness, Script::new().tap(Point::new(10, 10)).idle(1));
+        let popup = harness.overlay().area().unwrap();
+
+        // the popup is drawn on top of the button added after the layout, the selected item is
+        // drawn with the highlight background color
+        let point = button.top_left + Point::new(button.size.width as i32 - 4, 3);
+        assert!(popup.contains(point));
+        assert_eq!(
+            harness.display().pixel(point),
+            Some(style.highlight_item_background_color)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_combo_box_popup_repaint() {
+        use crate::smartstate::SmartstateProvider;
+        use crate::testing::{Harness, Script};
+        use embedded_graphics::pixelcolor::RgbColor;
+
+        let style = medsize_rgb565_style();
+        let mut harness = Harness::new(Size::new(128, 96), style);
+        let mut smartstates = SmartstateProvider::<2>::new();
+        let mut state = ComboBoxState::new();
+        let mut selected = 0;
+        let mut run = |harness: &mut Harness<_>, script: Script, redraw_below: bool| {
+            harness.run(script, |ui| {
+                smartstates.restart_counter();
+                ComboBox::new(&OPTIONS, &mut selected, &mut state)
+                    .smartstate(smartstates.nxt())
+                    .width(60)
+                    .show(ui);
+                if redraw_below {
+                    smartstates.peek().force_redraw();
+                }
+                ui.add(Button::new("Below").smartstate(smartstates.nxt()));
+            });
+        };
+        run(&mut harness, Script::new().tap(Point::new(10, 10)), false);
+        let popup = harness.overlay().area().unwrap();
+        let point = popup.top_left + Point::new(2, 2);
+
+        // the open popup isn't repainted while nothing below it changes...
+        harness.display_mut().clear(Rgb565::MAGENTA).unwrap();
+        run(&mut harness, Script::new().idle(1), false);
+        assert!(harness
+            .display()
+            .pixels()
+            .iter()
+            .all(|color| *color == Rgb565::MAGENTA));
+
+        // ...but after the button below it was redrawn
+        run(&mut harness, Script::new().idle(1), true);
+        assert_eq!(
+            harness.display().pixel(point),
+            Some(style.highlight_item_background_color)
+        );
+    }
+}
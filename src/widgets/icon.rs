@@ -128,29 +128,29 @@ impl<Ico: IconoirIcon> Widget for IconWidget<'_, Ico> {
         let icon = Ico::new(ui.style().icon_color);
         let iresponse = ui.allocate_space(icon.size())?;
 
+        ui.force_redraw_if_cleared(self.smartstate.as_mut_slice());
         let prevstate = self.smartstate.clone_inner();
         self.smartstate.modify(|sm| *sm = Smartstate::state(1));
 
         // draw icon
 
         if !self.smartstate.eq_option(&prevstate) {
-            ui.start_drawing(&iresponse.area);
+            ui.paint(&iresponse.area, |ui| {
+                if !ui.cleared() {
+                    ui.clear_area(iresponse.area)?;
+                }
 
-            if !ui.cleared() {
-                ui.clear_area(iresponse.area)?;
-            }
-
-            let img = Image::new(
-                &icon,
-                iresponse.area.top_left.add(Point::new(
-                    0, // center vertically
-                    (iresponse.area.size.height - icon.size().height) as i32 / 2,
-                )),
-            );
-            ui.draw(&img)
-                .map_err(|_| GuiError::DrawError(Some("Couldn't draw Icon")))?;
-
-            ui.finalize()?;
+                let img = Image::new(
+                    &icon,
+                    iresponse.area.top_left.add(Point::new(
+                        0, // center vertically
+                        (iresponse.area.size.height - icon.size().height) as i32 / 2,
+                    )),
+                );
+                ui.draw(&img)
+                    .map_err(|_| GuiError::DrawError(Some("Couldn't draw Icon")))?;
+                Ok(())
+            })?;
         }
 
         Ok(Response::new(iresponse))
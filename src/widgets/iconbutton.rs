@@ -58,6 +58,7 @@
 //! - Hover: Highlighted background and border for visual feedback
 //! - Pressed/Active: Primary color background with highlighted border
 //!
+use crate::font::FontText;
 use crate::smartstate::{Container, Smartstate};
 use crate::{GuiResult, Interaction, Response, Ui, Widget};
 use core::cmp::max;
@@ -65,11 +66,9 @@ use core::marker::PhantomData;
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::{Point, Size};
 use embedded_graphics::image::Image;
-use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::PixelColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::{PrimitiveStyleBuilder, Rectangle};
-use embedded_graphics::text::{Alignment, Baseline, Text};
 use embedded_iconoir::prelude::{IconoirIcon, IconoirNewIcon};
 
 /// A button widget that displays an icon with optional text label.
@@ -81,6 +80,7 @@ pub struct IconButton<'a, ICON: IconoirIcon> {
     icon: PhantomData<ICON>,
     label: Option<&'a str>,
     smartstate: Container<'a, Smartstate>,
+    enabled: bool,
 }
 
 impl<'a, ICON: IconoirIcon> IconButton<'a, ICON> {
@@ -118,6 +118,7 @@ impl<'a, ICON: IconoirIcon> IconButton<'a, ICON> {
         Self {
             icon: PhantomData,
             smartstate: Container::empty(),
+            enabled: true,
             label: None,
         }
     }
@@ -184,6 +185,7 @@ impl<'a, ICON: IconoirIcon> IconButton<'a, ICON> {
         Self {
             icon: PhantomData,
             smartstate: Container::empty(),
+            enabled: true,
             label: None,
         }
     }
@@ -221,9 +223,33 @@ impl<'a, ICON: IconoirIcon> IconButton<'a, ICON> {
         self.smartstate.set(smartstate);
         self
     }
+
+    /// Enables or disables the icon button (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled icon button is drawn with the disabled colors of the style (including the icon), and
+    /// can't be clicked or focused.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
 }
 
 impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |button| button.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl<ICON: IconoirIcon> IconButton<'_, ICON> {
     /// Draws the icon button within the UI.
     ///
     /// This method:
@@ -234,7 +260,7 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
     /// 5. Manages visual appearance based on interaction state
     /// 6. Updates the smartstate and draws when necessary
     /// 7. Returns a response that includes click information
-    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
         ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response> {
@@ -251,13 +277,7 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
         let font = ui.style().default_font;
 
         let mut text = if let Some(label) = self.label {
-            let mut text = Text::new(
-                label,
-                Point::new(0, 0),
-                MonoTextStyle::new(&font, ui.style().text_color),
-            );
-            text.text_style.alignment = Alignment::Center;
-            text.text_style.baseline = Baseline::Top;
+            let text = FontText::new(label, Point::zero(), font, ui.style().text_color);
             min_height += padding.height + text.bounding_box().size.height;
             width = width.max(text.bounding_box().size.width + 2 * padding.width + 2 * border);
             Some(text)
@@ -281,7 +301,8 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
          */
 
         // allocate space
-        let iresponse = ui.allocate_space(Size::new(size.width, max(size.height, height)))?;
+        let iresponse =
+            ui.allocate_focusable_space(Size::new(size.width, max(size.height, height)))?;
 
         // translate icon
         let size = icon.bounding_box();
@@ -304,7 +325,7 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
         if let Some(text) = text.as_mut() {
             let center_offset = iresponse.area.top_left
                 + Point::new(
-                    (iresponse.area.size.width / 2) as i32,
+                    (iresponse.area.size.width as i32 - text.bounding_box().size.width as i32) / 2,
                     (iresponse.area.size.height
                         - text.bounding_box().size.height
                         - padding.height
@@ -314,7 +335,7 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
         }
 
         // check for click
-        let click = matches!(iresponse.interaction, Interaction::Release(_));
+        let click = iresponse.released();
         let down = matches!(
             iresponse.interaction,
             Interaction::Click(_) | Interaction::Drag(_)
@@ -354,19 +375,22 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
         };
 
         if !self.smartstate.eq_option(&prevstate) {
-            ui.start_drawing(&iresponse.area);
-
-            ui.draw(
-                &Rectangle::new(iresponse.area.top_left, iresponse.area.size)
-                    .into_styled(rect_style),
-            )
-            .ok();
-            ui.draw(&icon_img).ok();
-            if let Some(text) = text.as_mut() {
-                ui.draw(text).unwrap();
-            }
-
-            ui.finalize()?;
+            ui.paint(&iresponse.area, |ui| {
+                ui.draw(
+                    &Rectangle::new(iresponse.area.top_left, iresponse.area.size)
+                        .into_styled(rect_style),
+                )
+                .ok();
+                ui.draw(&icon_img).ok();
+                if let Some(text) = text {
+                    let background = rect_style
+                        .fill_color
+                        .unwrap_or(ui.style().item_background_color);
+                    ui.draw(&text.blend(background, ui.style().text_blend))
+                        .unwrap();
+                }
+                Ok(())
+            })?;
         }
 
         Ok(Response::new(iresponse).set_clicked(click).set_down(down))
@@ -3,10 +3,12 @@
 //! Labels are basic building blocks for displaying text content. They support both static
 //! and dynamic text with features like:
 //!
-//! - Custom fonts and styling
+//! - Custom fonts (any [Font], e.g. proportional or anti-aliased) and styling
 //! - Automatic vertical centering
 //! - Integration with the smartstate system for efficient redraws
 //! - HashLabel variant for auto-refreshing on content changes
+//! - FmtLabel variant for formatted text (e.g. live values), without allocations
+//! - Multi-line text with word wrapping, alignment and truncation (without allocations)
 //!
 //! # Examples
 //!
@@ -38,19 +40,18 @@
 //! ui.add(HashLabel::new("Dynamic content", smartstate.nxt(), &hasher));
 //! ```
 
+use crate::font::{Font, FontText};
 use crate::smartstate::{Container, Smartstate};
+use crate::ui::HorizontalAlign;
 use crate::{GuiError, GuiResult, Response, Ui, Widget};
+use core::fmt;
 use core::hash::BuildHasher;
-use core::hash::Hash;
-use core::ops::Add;
+use core::hash::{Hash, Hasher as _};
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::{Point, Size};
-use embedded_graphics::mono_font::MonoFont;
-use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::PixelColor;
-use embedded_graphics::prelude::*;
-use embedded_graphics::text::{Baseline, Text};
-use foldhash::fast::RandomState;
+use foldhash::fast::{FixedState, FoldHasher, RandomState};
+use heapless::String;
 
 /// A widget for displaying text in the UI.
 ///
@@ -62,6 +63,8 @@ use foldhash::fast::RandomState;
 /// - Basic text display with customizable fonts
 /// - Smartstate integration for incremental redrawing
 /// - Automatic vertical centering in allocated space
+/// - Word wrapping ([Label::wrap]), line limits ([Label::max_lines]), fixed widths ([Label::width])
+///   with horizontal alignment ([Label::align]), and truncation with an ellipsis
 ///
 /// # Examples
 ///
@@ -88,11 +91,18 @@ use foldhash::fast::RandomState;
 /// ui.add(Label::new("Basic text"));
 ///
 /// // Label with custom font and smartstate
-/// ui.add(Label::new("Custom font").with_font(ascii::FONT_10X20).smartstate(smartstateProvider.nxt()));
+/// ui.add(Label::new("Custom font").with_font(&ascii::FONT_10X20).smartstate(smartstateProvider.nxt()));
+///
+/// // Long message, wrapped to the available width and limited to two lines
+/// ui.add(Label::new("Sensor 3 is not responding, check the wiring").wrap(true).max_lines(2));
+///
+/// // Centered in a fixed width
+/// ui.add(Label::new("Idle").width(100).align(HorizontalAlign::Center));
 /// ```
 pub struct Label<'a> {
     text: &'a str,
-    font: Option<MonoFont<'a>>,
+    font: Option<&'a dyn Font>,
+    layout: TextLayout,
     smartstate: Container<'a, Smartstate>,
 }
 
@@ -124,12 +134,16 @@ impl<'a> Label<'a> {
         Label {
             text,
             font: None,
+            layout: TextLayout::default(),
             smartstate: Container::empty(),
         }
     }
 
     /// Sets a custom font for the label.
     ///
+    /// Any [Font] can be used, e.g. a [MonoFont](embedded_graphics::mono_font::MonoFont) or a
+    /// proportional [BitmapFont](crate::font::BitmapFont).
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -150,13 +164,49 @@ impl<'a> Label<'a> {
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let hasher = Hasher::new();
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
-    /// ui.add(Label::new("Custom Font").with_font(ascii::FONT_10X20));
+    /// ui.add(Label::new("Custom Font").with_font(&ascii::FONT_10X20));
     /// ```
-    pub fn with_font(mut self, font: MonoFont<'a>) -> Self {
+    pub fn with_font(mut self, font: &'a dyn Font) -> Self {
         self.font = Some(font);
         self
     }
 
+    /// Sets the line layout of the label at once (instead of the single options below).
+    pub fn layout(mut self, layout: TextLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Wraps the text at word boundaries, see [TextLayout::wrap].
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.layout = self.layout.wrap(wrap);
+        self
+    }
+
+    /// Limits the number of lines, see [TextLayout::max_lines].
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.layout = self.layout.max_lines(max_lines);
+        self
+    }
+
+    /// Sets a fixed width for the label, see [TextLayout::width].
+    pub fn width(mut self, width: u32) -> Self {
+        self.layout = self.layout.width(width);
+        self
+    }
+
+    /// Sets the horizontal alignment of the lines, see [TextLayout::align].
+    pub fn align(mut self, align: HorizontalAlign) -> Self {
+        self.layout = self.layout.align(align);
+        self
+    }
+
+    /// Sets whether truncated text ends with an ellipsis, see [TextLayout::ellipsis].
+    pub fn ellipsis(mut self, ellipsis: bool) -> Self {
+        self.layout = self.layout.ellipsis(ellipsis);
+        self
+    }
+
     /// Adds a [Smartstate] to the label for incremental redrawing.
     ///
     /// When using smartstate, the label will only redraw when the smartstate is
@@ -197,54 +247,15 @@ impl Widget for Label<'_> {
         &mut self,
         ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response> {
-        // get size
-
-        let font = if let Some(font) = self.font {
-            font
-        } else {
-            ui.style().default_font
-        };
-
-        let mut text = Text::new(
+        // the smartstate only has one state (a bool would work, but this is consistent with other widgets)
+        draw_label(
+            ui,
             self.text,
-            Point::new(0, 0),
-            MonoTextStyle::new(&font, ui.style().text_color),
-        );
-
-        let size = text.bounding_box();
-
-        // allocate space
-
-        let iresponse = ui.allocate_space(Size::new(size.size.width, size.size.height))?;
-
-        // move text (center vertically)
-
-        text.translate_mut(iresponse.area.top_left.add(Point::new(
+            self.font,
+            self.layout,
             0,
-            (iresponse.area.size.height - size.size.height) as i32 / 2,
-        )));
-        text.text_style.baseline = Baseline::Top;
-
-        // check smartstate (a bool would work, but this is consistent with other widgets)
-        let redraw = !self.smartstate.eq_option(&Some(Smartstate::state(0)));
-        self.smartstate.modify(|st| *st = Smartstate::state(0));
-
-        // draw
-
-        if redraw {
-            ui.start_drawing(&iresponse.area);
-            // clear background if necessary
-            if !ui.cleared() {
-                ui.clear_area(iresponse.area)?;
-            }
-
-            ui.draw(&text)
-                .map_err(|_| GuiError::DrawError(Some("Couldn't draw text")))?;
-
-            ui.finalize()?;
-        }
-
-        Ok(Response::new(iresponse))
+            &mut self.smartstate,
+        )
     }
 }
 
@@ -319,7 +330,8 @@ impl Default for Hasher {
 /// ```
 pub struct HashLabel<'a> {
     text: &'a str,
-    font: Option<MonoFont<'a>>,
+    font: Option<&'a dyn Font>,
+    layout: TextLayout,
     smartstate: Container<'a, Smartstate>,
     hasher: &'a Hasher,
 }
@@ -361,6 +373,7 @@ impl<'a> HashLabel<'a> {
         Self {
             text,
             font: None,
+            layout: TextLayout::default(),
             smartstate: Container::new(smartstate),
             hasher,
         }
@@ -391,12 +404,48 @@ impl<'a> HashLabel<'a> {
     /// # let mut smartstateProvider = SmartstateProvider::<20>::new();
     /// let mut text = "Some dynamically changed text";
     ///
-    /// ui.add(HashLabel::new(text, smartstateProvider.nxt(), &hasher).with_font(ascii::FONT_10X20));
+    /// ui.add(HashLabel::new(text, smartstateProvider.nxt(), &hasher).with_font(&ascii::FONT_10X20));
     /// ```
-    pub fn with_font(mut self, font: MonoFont<'a>) -> Self {
+    pub fn with_font(mut self, font: &'a dyn Font) -> Self {
         self.font = Some(font);
         self
     }
+
+    /// Sets the line layout of the label at once (instead of the single options below).
+    pub fn layout(mut self, layout: TextLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Wraps the text at word boundaries, see [TextLayout::wrap].
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.layout = self.layout.wrap(wrap);
+        self
+    }
+
+    /// Limits the number of lines, see [TextLayout::max_lines].
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.layout = self.layout.max_lines(max_lines);
+        self
+    }
+
+    /// Sets a fixed width for the label, see [TextLayout::width].
+    pub fn width(mut self, width: u32) -> Self {
+        self.layout = self.layout.width(width);
+        self
+    }
+
+    /// Sets the horizontal alignment of the lines, see [TextLayout::align].
+    pub fn align(mut self, align: HorizontalAlign) -> Self {
+        self.layout = self.layout.align(align);
+        self
+    }
+
+    /// Sets whether truncated text ends with an ellipsis, see [TextLayout::ellipsis].
+    pub fn ellipsis(mut self, ellipsis: bool) -> Self {
+        self.layout = self.layout.ellipsis(ellipsis);
+        self
+    }
 }
 
 impl Widget for HashLabel<'_> {
@@ -404,56 +453,510 @@ impl Widget for HashLabel<'_> {
         &mut self,
         ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response> {
-        // get size
+        let hash = self.hasher.hash(self.text) as u32;
+        draw_label(
+            ui,
+            self.text,
+            self.font,
+            self.layout,
+            hash,
+            &mut self.smartstate,
+        )
+    }
+}
 
-        let font = if let Some(font) = self.font {
-            font
-        } else {
-            ui.style().default_font
+/// A [Label] variant that formats its text with [core::fmt], without allocations.
+///
+/// The text is written into an internal buffer with a capacity of `N` bytes, and its hash is
+/// computed while formatting. With a smartstate, the label is only redrawn when the hash changes,
+/// like a [HashLabel] (but without the need for a [Hasher]).
+///
+/// If the formatted text doesn't fit into the buffer, the beginning of it is drawn and the
+/// [Response] carries a [GuiError::Truncated] error.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::ui::*;
+/// # use kolibri_embedded_gui::widgets::FmtLabel;
+/// # use kolibri_embedded_gui::smartstate::*;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// # let mut smartstateProvider = SmartstateProvider::<20>::new();
+/// let temperature = 23.4;
+///
+/// // only redrawn when the temperature changes
+/// ui.add(
+///     FmtLabel::<24>::new(format_args!("Temp: {:.1} C", temperature))
+///         .width(120)
+///         .smartstate(smartstateProvider.nxt()),
+/// );
+/// ```
+pub struct FmtLabel<'a, const N: usize = 32> {
+    args: fmt::Arguments<'a>,
+    font: Option<&'a dyn Font>,
+    layout: TextLayout,
+    smartstate: Container<'a, Smartstate>,
+}
+
+impl<'a, const N: usize> FmtLabel<'a, N> {
+    /// Creates a new label from the given format arguments (see [format_args]).
+    pub fn new(args: fmt::Arguments<'a>) -> Self {
+        Self {
+            args,
+            font: None,
+            layout: TextLayout::default(),
+            smartstate: Container::empty(),
+        }
+    }
+
+    /// Sets a custom font for the label.
+    pub fn with_font(mut self, font: &'a dyn Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets a fixed width for the label, so that it doesn't leave parts of longer previous texts
+    /// behind (see [TextLayout::width]).
+    pub fn width(mut self, width: u32) -> Self {
+        self.layout = self.layout.width(width);
+        self
+    }
+
+    /// Sets the horizontal alignment of the text within the [fixed width](Self::width),
+    /// see [TextLayout::align].
+    pub fn align(mut self, align: HorizontalAlign) -> Self {
+        self.layout = self.layout.align(align);
+        self
+    }
+
+    /// Adds a [Smartstate] to the label, so that it is only redrawn when its text changes.
+    pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
+        self.smartstate.set(smartstate);
+        self
+    }
+}
+
+/// Writes formatted text into a fixed-size buffer, hashing it on the way.
+struct HashingWriter<'b, const N: usize> {
+    buf: &'b mut String<N>,
+    hasher: FoldHasher,
+    truncated: bool,
+}
+
+impl<const N: usize> fmt::Write for HashingWriter<'_, N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // hash everything, so that changes after the truncation are still noticed
+        self.hasher.write(s.as_bytes());
+        if !self.truncated && self.buf.push_str(s).is_err() {
+            self.truncated = true;
+            for c in s.chars() {
+                if self.buf.push(c).is_err() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> Widget for FmtLabel<'_, N> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let mut buf = String::<N>::new();
+        let mut writer = HashingWriter {
+            buf: &mut buf,
+            hasher: FixedState::default().build_hasher(),
+            truncated: false,
         };
+        fmt::write(&mut writer, self.args)
+            .map_err(|_| GuiError::DrawError(Some("Couldn't format text")))?;
+        let hash = writer.hasher.finish() as u32;
+        let truncated = writer.truncated;
+
+        let response = draw_label(ui, &buf, self.font, self.layout, hash, &mut self.smartstate)?;
+        Ok(if truncated {
+            response.set_error(GuiError::Truncated)
+        } else {
+            response
+        })
+    }
+}
 
-        let mut text = Text::new(
-            self.text,
-            Point::new(0, 0),
-            MonoTextStyle::new(&font, ui.style().text_color),
-        );
+/// Line layout of a [Label], [HashLabel] or [FmtLabel]: word wrapping, line limit, fixed width,
+/// alignment and truncation.
+///
+/// The labels have builder methods for each option (e.g. [Label::wrap]), which set it here.
+/// By default, the text is drawn on a single line (or one line per newline), left-aligned and as
+/// wide as it is.
+#[derive(Clone, Copy, Debug)]
+pub struct TextLayout {
+    width: Option<u32>,
+    wrap: bool,
+    max_lines: Option<usize>,
+    align: HorizontalAlign,
+    ellipsis: bool,
+}
 
-        let size = text.bounding_box();
+impl TextLayout {
+    /// Creates the default layout.
+    pub const fn new() -> Self {
+        TextLayout {
+            width: None,
+            wrap: false,
+            max_lines: None,
+            align: HorizontalAlign::Left,
+            ellipsis: true,
+        }
+    }
 
-        // allocate space
+    /// Wraps the text at word boundaries to fit the available width of the [Ui]
+    /// (or the [fixed width](Self::width), if set), instead of drawing it on a single line.
+    ///
+    /// Newlines in the text always start a new line.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
 
-        let iresponse = ui.allocate_space(Size::new(size.size.width, size.size.height))?;
+    /// Limits the number of lines. If the text has more lines, the last shown line is
+    /// truncated (see [Self::ellipsis]).
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
 
-        let hash = self.hasher.hash(self.text) as u32;
+    /// Sets a fixed width for the label. Lines that don't fit into it are truncated
+    /// (see [Self::ellipsis]), or wrapped if [wrapping](Self::wrap) is enabled.
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets the horizontal alignment of the lines within the label.
+    pub fn align(mut self, align: HorizontalAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets whether truncated text ends with an ellipsis (`...`). Enabled by default.
+    pub fn ellipsis(mut self, ellipsis: bool) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        let redraw = !self.smartstate.eq_option(&Some(Smartstate::state(hash)));
-        self.smartstate.modify(|st| *st = Smartstate::state(hash));
+/// Appended to truncated lines
+const ELLIPSIS: &str = "...";
 
-        if redraw {
-            // move text (center vertically)
+/// Iterator over the lines of a text, split at newlines and (optionally) wrapped at word
+/// boundaries, without copying the text.
+struct Lines<'t> {
+    rest: Option<&'t str>,
+    font: &'t dyn Font,
+    /// Maximum width of a line, if wrapping
+    wrap: Option<u32>,
+}
 
-            text.translate_mut(iresponse.area.top_left.add(Point::new(
-                0,
-                (iresponse.area.size.height - size.size.height) as i32 / 2,
-            )));
-            text.text_style.baseline = Baseline::Top;
+impl<'t> Lines<'t> {
+    fn new(text: &'t str, font: &'t dyn Font, wrap: Option<u32>) -> Self {
+        Lines {
+            rest: Some(text),
+            font,
+            wrap,
+        }
+    }
+}
 
-            // check smartstate (a bool would work, but this is consistent with other widgets)
+impl<'t> Iterator for Lines<'t> {
+    type Item = &'t str;
 
-            // draw
+    fn next(&mut self) -> Option<&'t str> {
+        let rest = self.rest?;
+        let (end, next) = match rest.find('\n') {
+            Some(index) => (index, Some(&rest[index + 1..])),
+            None => (rest.len(), None),
+        };
+
+        if let Some(max) = self.wrap {
+            // at least one char per line
+            let cut = match self.font.fit(&rest[..end], max) {
+                0 => rest[..end].chars().next().map_or(0, char::len_utf8),
+                cut => cut,
+            };
+            if cut < end {
+                let (line, next) = if rest[cut..].starts_with(' ') {
+                    // break at the space right after the last fitting char
+                    (&rest[..cut], &rest[cut + 1..])
+                } else {
+                    match rest[..cut].rfind(' ') {
+                        Some(space) if space > 0 => (&rest[..space], &rest[space + 1..]),
+                        // words longer than a line are broken anywhere
+                        _ => (&rest[..cut], &rest[cut..]),
+                    }
+                };
+                self.rest = Some(next);
+                return Some(line.trim_end());
+            }
+        }
+
+        self.rest = next;
+        Some(&rest[..end])
+    }
+}
+
+/// A line as it is drawn: the shown part of the line, and whether it ends with an ellipsis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct VisibleLine<'t> {
+    text: &'t str,
+    ellipsis: bool,
+}
+
+impl VisibleLine<'_> {
+    /// Returns the drawn width of the line.
+    fn width(&self, font: &dyn Font) -> u32 {
+        font.text_width(self.text)
+            + if self.ellipsis {
+                font.text_width(ELLIPSIS)
+            } else {
+                0
+            }
+    }
+}
+
+/// Iterator over the drawn lines of a text, limited to the maximum number of lines,
+/// and truncated to the maximum width.
+struct VisibleLines<'t> {
+    lines: Lines<'t>,
+    max_width: Option<u32>,
+    remaining: usize,
+    ellipsis: bool,
+}
+
+impl<'t> VisibleLines<'t> {
+    fn new(text: &'t str, font: &'t dyn Font, layout: &TextLayout, max_width: Option<u32>) -> Self {
+        VisibleLines {
+            lines: Lines::new(text, font, if layout.wrap { max_width } else { None }),
+            max_width,
+            remaining: layout.max_lines.unwrap_or(usize::MAX).max(1),
+            ellipsis: layout.ellipsis,
+        }
+    }
+}
 
-            ui.start_drawing(&iresponse.area);
+impl<'t> Iterator for VisibleLines<'t> {
+    type Item = VisibleLine<'t>;
+
+    fn next(&mut self) -> Option<VisibleLine<'t>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let line = self.lines.next()?;
+        self.remaining -= 1;
+
+        let font = self.lines.font;
+        let width = font.text_width(line);
+        let ellipsis_width = font.text_width(ELLIPSIS);
+        let max = self.max_width.unwrap_or(u32::MAX);
+        let more = self.remaining == 0 && self.lines.rest.is_some();
+
+        let keep = if !self.ellipsis {
+            font.fit(line, max)
+        } else if width > max || (more && width + ellipsis_width > max) {
+            font.fit(line, max.saturating_sub(ellipsis_width))
+        } else {
+            line.len()
+        };
+        Some(VisibleLine {
+            text: &line[..keep],
+            ellipsis: self.ellipsis && (keep < line.len() || more),
+        })
+    }
+}
+
+/// Lays out, allocates and (if the smartstate differs from `state`) draws a label.
+fn draw_label<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+    ui: &mut Ui<DRAW, COL>,
+    text: &str,
+    font: Option<&dyn Font>,
+    layout: TextLayout,
+    state: u32,
+    smartstate: &mut Container<Smartstate>,
+) -> GuiResult<Response> {
+    // get size
+
+    let font = font.unwrap_or(ui.style().default_font);
+    let max_width = match layout.width {
+        None if layout.wrap => Some(ui.space_available().width),
+        width => width,
+    };
+
+    let (lines, text_size) = VisibleLines::new(text, font, &layout, max_width)
+        .fold((0, 0), |(lines, widest), line| {
+            (lines + 1, widest.max(line.width(font)))
+        });
+    let line_height = font.line_height();
+    let size = Size::new(layout.width.unwrap_or(text_size), lines * line_height);
+
+    // allocate space
+
+    let iresponse = ui.allocate_space(size)?;
+
+    ui.force_redraw_if_cleared(smartstate.as_mut_slice());
+    let redraw = !smartstate.eq_option(&Some(Smartstate::state(state)));
+    smartstate.modify(|st| *st = Smartstate::state(state));
+
+    if redraw {
+        ui.paint(&iresponse.area, |ui| {
             // clear background if necessary
             if !ui.cleared() {
                 ui.clear_area(iresponse.area)?;
             }
 
-            ui.draw(&text)
-                .map_err(|_| GuiError::DrawError(Some("Couldn't draw text")))?;
+            // center vertically
+            let top_left = iresponse.area.top_left
+                + Point::new(0, (iresponse.area.size.height - size.height) as i32 / 2);
+            let style = *ui.style();
+
+            for (index, line) in VisibleLines::new(text, font, &layout, max_width).enumerate() {
+                let free = size.width.saturating_sub(line.width(font));
+                let pos = top_left
+                    + Point::new(
+                        layout.align.offset(free) as i32,
+                        (index as u32 * line_height) as i32,
+                    );
+                let text = |text, pos| {
+                    FontText::new(text, pos, font, style.text_color)
+                        .blend(style.background_color, style.text_blend)
+                };
+                ui.draw(&text(line.text, pos))
+                    .map_err(|_| GuiError::DrawError(Some("Couldn't draw text")))?;
+                if line.ellipsis {
+                    let pos = pos + Point::new(font.text_width(line.text) as i32, 0);
+                    ui.draw(&text(ELLIPSIS, pos))
+                        .map_err(|_| GuiError::DrawError(Some("Couldn't draw text")))?;
+                }
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(Response::new(iresponse))
+}
 
-            ui.finalize()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::mono_font::ascii::FONT_6X10;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    fn lines<const N: usize>(text: &str, layout: TextLayout, max_chars: u32) -> [&str; N] {
+        let mut lines = [""; N];
+        let mut visible = VisibleLines::new(text, &FONT_6X10, &layout, Some(max_chars * 6));
+        for line in lines.iter_mut() {
+            *line = visible.next().map(|line| line.text).unwrap_or_default();
         }
+        assert_eq!(visible.next(), None);
+        lines
+    }
+
+    #[test]
+    fn test_wrap_lines() {
+        let layout = TextLayout {
+            wrap: true,
+            ..TextLayout::default()
+        };
+        assert_eq!(
+            lines("the quick brown fox", layout, 10),
+            ["the quick", "brown fox"]
+        );
+        // newlines, and words longer than a line
+        assert_eq!(
+            lines("a\nabcdefghijkl b", layout, 5),
+            ["a", "abcde", "fghij", "kl b"]
+        );
+        assert_eq!(lines::<1>("", layout, 5), [""]);
+    }
+
+    #[test]
+    fn test_truncate_lines() {
+        let layout = TextLayout {
+            wrap: true,
+            max_lines: Some(2),
+            ..TextLayout::default()
+        };
+        let mut visible =
+            VisibleLines::new("the quick brown fox jumps", &FONT_6X10, &layout, Some(60));
+        assert_eq!(visible.next().unwrap().text, "the quick");
+        let last = visible.next().unwrap();
+        assert_eq!(last.text, "brown f");
+        assert!(last.ellipsis);
+        assert_eq!(last.width(&FONT_6X10), 60);
+
+        // without wrapping, each line is cut off on its own
+        let layout = TextLayout::default();
+        let mut visible = VisibleLines::new("abcdefgh\nab", &FONT_6X10, &layout, Some(30));
+        assert_eq!(
+            visible.next(),
+            Some(VisibleLine {
+                text: "ab",
+                ellipsis: true
+            })
+        );
+        assert_eq!(
+            visible.next(),
+            Some(VisibleLine {
+                text: "ab",
+                ellipsis: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_wrapped_label_size() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        // 58px leave space for 6 chars of 9px
+        let response = ui.add(Label::new("abc de fgh").wrap(true));
+        assert_eq!(response.internal.area.size, Size::new(54, 30));
+    }
 
-        Ok(Response::new(iresponse))
+    #[test]
+    fn test_fmt_label() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        // the beginning of the text is drawn
+        let response = ui.add_horizontal(FmtLabel::<4>::new(format_args!("{}", 12345)));
+        assert_eq!(response.error(), Some(GuiError::Truncated));
+        assert_eq!(response.internal.area.size.width, 4 * 9);
+
+        let mut smartstate = Smartstate::empty();
+        let response = ui
+            .add_horizontal(FmtLabel::<4>::new(format_args!("{}", 42)).smartstate(&mut smartstate));
+        assert_eq!(response.error(), None);
+        let state = smartstate;
+
+        ui.add_horizontal(FmtLabel::<4>::new(format_args!("{}", 42)).smartstate(&mut smartstate));
+        assert_eq!(smartstate, state);
+        ui.add_horizontal(FmtLabel::<4>::new(format_args!("{}", 43)).smartstate(&mut smartstate));
+        assert_ne!(smartstate, state);
     }
 }
@@ -1,19 +1,31 @@
 pub mod button;
 pub mod checkbox;
+pub mod combo_box;
 pub mod icon;
 pub mod iconbutton;
 pub mod label;
+pub mod progress_bar;
+pub mod radio_button;
 pub mod slider;
 pub mod spacer;
+pub mod spinner;
+pub mod text_input;
 pub mod toggle_button;
 pub mod toggle_switch;
 
 pub use button::Button;
 pub use checkbox::Checkbox;
+pub use combo_box::{ComboBox, ComboBoxState};
 pub use icon::IconWidget;
 pub use iconbutton::IconButton;
-pub use label::{HashLabel, Hasher, Label};
-pub use slider::Slider;
+pub use label::{FmtLabel, HashLabel, Hasher, Label, TextLayout};
+pub use progress_bar::ProgressBar;
+pub use radio_button::{RadioButton, RadioGroup};
+pub use slider::{
+    Orientation, RangeBound, RangeSlider, RangeSliderState, Readout, Slider, SliderValue,
+};
 pub use spacer::Spacer;
+pub use spinner::Spinner;
+pub use text_input::{TextCursor, TextInput};
 pub use toggle_button::ToggleButton;
 pub use toggle_switch::ToggleSwitch;
@@ -0,0 +1,390 @@
+//! # Progress Bar Widget
+//!
+//! A widget that shows the progress of a task, like a firmware update or a calibration.
+//!
+//! The bar is filled with the [primary color](crate::style::Style::primary_color) according to
+//! the value, and framed with the [border color](crate::style::Style::border_color).
+//! With a smartstate, only the part of the fill that changed is redrawn.
+//!
+//! See [ProgressBar] for more info.
+
+use crate::smartstate::{Container, Smartstate};
+use crate::widgets::slider::Orientation;
+use crate::{GuiError, GuiResult, Response, Ui, Widget};
+use core::cmp::{max, min};
+use core::fmt::Write;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, PrimitiveStyleBuilder, Rectangle};
+
+/// Text of the percentage
+type PercentageText = heapless::String<5>;
+
+/// Number of bits of the smartstate used for the percentage (`0..=100`),
+/// the fill length is stored in the remaining bits above it.
+const PERCENTAGE_BITS: u32 = 7;
+
+/// # Progress Bar Widget
+///
+/// A determinate progress bar, showing a `value` in `0..=max`.
+///
+/// The bar can optionally show a label and the percentage next to it (right of a horizontal bar,
+/// or below a vertical bar). It can be horizontal (filling from left to right) or
+/// vertical (filling from bottom to top).
+///
+/// With a smartstate, only the region of the bar whose fill changed (and the percentage, if it
+/// changed) is redrawn, which makes frequent updates cheap even on slow displays.
+///
+/// # Example
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::smartstate::*;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// # let mut smartstates = SmartstateProvider::<20>::new();
+/// # let (written, total) = (1024u32, 4096u32);
+/// use kolibri_embedded_gui::widgets::ProgressBar;
+/// use kolibri_embedded_gui::widgets::slider::Orientation;
+///
+/// // firmware update progress, with label and percentage
+/// ui.add(
+///     ProgressBar::new(written, total)
+///         .label("Updating")
+///         .show_percentage(true)
+///         .smartstate(smartstates.nxt()),
+/// );
+///
+/// // vertical level meter
+/// ui.add(
+///     ProgressBar::new(75, 100)
+///         .orientation(Orientation::Vertical)
+///         .width(80),
+/// );
+/// ```
+pub struct ProgressBar<'a> {
+    value: u32,
+    max: u32,
+    label: Option<&'a str>,
+    show_percentage: bool,
+    orientation: Orientation,
+    width: u32,
+    thickness: Option<u32>,
+    smartstate: Container<'a, Smartstate>,
+}
+
+impl<'a> ProgressBar<'a> {
+    /// Creates a new progress bar showing `value` of `max` (values above `max` show a full bar).
+    ///
+    /// By default, the bar is horizontal, 200 pixels long and as thick as the
+    /// [default widget height](crate::style::Style::default_widget_height), without any text.
+    pub fn new(value: u32, max: u32) -> Self {
+        Self {
+            value,
+            max,
+            label: None,
+            show_percentage: false,
+            orientation: Orientation::Horizontal,
+            width: 200,
+            thickness: None,
+            smartstate: Container::empty(),
+        }
+    }
+
+    /// Sets a label that is displayed next to the bar.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Shows the percentage next to the bar (after the label, if any).
+    pub fn show_percentage(mut self, show_percentage: bool) -> Self {
+        self.show_percentage = show_percentage;
+        self
+    }
+
+    /// Sets the orientation of the bar (horizontal by default).
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets the length of the bar in pixels (the height for vertical bars).
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the thickness of the bar in pixels (the width for vertical bars).
+    pub fn thickness(mut self, thickness: u32) -> Self {
+        self.thickness = Some(thickness);
+        self
+    }
+
+    /// Adds a smartstate to the progress bar for incremental redrawing.
+    ///
+    /// With a smartstate, only the changed region of the fill and the percentage are redrawn.
+    pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
+        self.smartstate.set(smartstate);
+        self
+    }
+
+    /// Returns the progress in percent (`0..=100`).
+    fn percentage(&self) -> u32 {
+        if self.max == 0 {
+            return 100;
+        }
+        (min(self.value, self.max) as u64 * 100 / self.max as u64) as u32
+    }
+
+    /// Returns the filled length (in pixels) of a bar with the given inner length.
+    fn fill_length(&self, length: u32) -> u32 {
+        if self.max == 0 {
+            return length;
+        }
+        (min(self.value, self.max) as u64 * length as u64 / self.max as u64) as u32
+    }
+
+    /// Returns the part of the inner bar between the fill lengths `from` and `to`.
+    fn fill_rect(&self, inner: &Rectangle, from: u32, to: u32) -> Rectangle {
+        let (start, end) = (min(from, to), max(from, to));
+        match self.orientation {
+            Orientation::Horizontal => Rectangle::new(
+                inner.top_left + Point::new(start as i32, 0),
+                Size::new(end - start, inner.size.height),
+            ),
+            Orientation::Vertical => Rectangle::new(
+                inner.top_left + Point::new(0, (inner.size.height - end) as i32),
+                Size::new(inner.size.width, end - start),
+            ),
+        }
+    }
+}
+
+impl Widget for ProgressBar<'_> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        // get size
+        let style = ui.style();
+        let padding = style.spacing.button_padding;
+        let border = max(style.border_width, 1);
+        let thickness = self.thickness.unwrap_or(style.default_widget_height);
+        let font = style.default_font;
+
+        let mut percentage = PercentageText::new();
+        if self.show_percentage {
+            write!(percentage, "{}%", self.percentage()).ok();
+        }
+
+        // text: label, followed by the percentage (sized for "100%")
+        let label_size = self
+            .label
+            .map(|label| Size::new(font.text_width(label), font.line_height()))
+            .unwrap_or_default();
+        let percentage_size = if self.show_percentage {
+            Size::new(font.text_width("100%"), font.line_height())
+        } else {
+            Size::zero()
+        };
+        let label_gap = if self.label.is_some() && self.show_percentage {
+            font.char_width(' ')
+        } else {
+            0
+        };
+        let text_size = Size::new(
+            label_size.width + label_gap + percentage_size.width,
+            max(label_size.height, percentage_size.height),
+        );
+        let text_gap = if text_size.width > 0 {
+            padding.width
+        } else {
+            0
+        };
+
+        let (bar_size, size) = match self.orientation {
+            Orientation::Horizontal => {
+                let bar = Size::new(self.width, thickness);
+                (
+                    bar,
+                    Size::new(
+                        bar.width + text_gap + text_size.width,
+                        max(bar.height, text_size.height),
+                    ),
+                )
+            }
+            Orientation::Vertical => {
+                let bar = Size::new(thickness, self.width);
+                (
+                    bar,
+                    Size::new(
+                        max(bar.width, text_size.width),
+                        bar.height + text_gap + text_size.height,
+                    ),
+                )
+            }
+        };
+
+        // allocate space
+        let iresponse = ui.allocate_space(size)?;
+        let area = iresponse.area;
+
+        let (bar, text_pos) = match self.orientation {
+            Orientation::Horizontal => (
+                Rectangle::new(
+                    area.top_left + Point::new(0, (size.height - bar_size.height) as i32 / 2),
+                    bar_size,
+                ),
+                area.top_left
+                    + Point::new(
+                        (bar_size.width + text_gap) as i32,
+                        (size.height - text_size.height) as i32 / 2,
+                    ),
+            ),
+            Orientation::Vertical => (
+                Rectangle::new(
+                    area.top_left + Point::new((size.width - bar_size.width) as i32 / 2, 0),
+                    bar_size,
+                ),
+                area.top_left
+                    + Point::new(
+                        (size.width - text_size.width) as i32 / 2,
+                        (bar_size.height + text_gap) as i32,
+                    ),
+            ),
+        };
+        let inner = bar.offset(-(border as i32));
+        let inner_length = match self.orientation {
+            Orientation::Horizontal => inner.size.width,
+            Orientation::Vertical => inner.size.height,
+        };
+        let percentage_area = Rectangle::new(
+            text_pos + Point::new((label_size.width + label_gap) as i32, 0),
+            percentage_size,
+        );
+
+        // smartstate: fill length and percentage
+        // (a bar too long to store its fill length is always fully redrawn)
+        ui.force_redraw_if_cleared(self.smartstate.as_mut_slice());
+        let fill = self.fill_length(inner_length);
+        let state = (fill < 1 << (u32::BITS - PERCENTAGE_BITS))
+            .then(|| fill << PERCENTAGE_BITS | self.percentage());
+        let prev = self
+            .smartstate
+            .clone_inner()
+            .and_then(|state| state.value());
+        self.smartstate
+            .modify(|st| *st = state.map_or(Smartstate::empty(), Smartstate::state));
+
+        let style = ui.style();
+        let fill_style = PrimitiveStyle::with_fill(style.primary_color);
+        let empty_style = PrimitiveStyle::with_fill(style.item_background_color);
+        let bar_style = PrimitiveStyleBuilder::new()
+            .stroke_color(style.border_color)
+            .stroke_width(border)
+            .fill_color(style.item_background_color)
+            .build();
+        let percentage_text = style.text(
+            &percentage,
+            percentage_area.top_left,
+            style.background_color,
+        );
+
+        match (prev, state) {
+            (Some(prev), Some(state)) if prev == state => {}
+            (Some(prev), Some(_)) => {
+                // only redraw the changed part of the fill
+                let prev_fill = prev >> PERCENTAGE_BITS;
+                if prev_fill != fill {
+                    let changed = self.fill_rect(&inner, prev_fill, fill);
+                    ui.paint(&changed, |ui| {
+                        ui.draw(&changed.into_styled(if fill > prev_fill {
+                            fill_style
+                        } else {
+                            empty_style
+                        }))?;
+                        Ok(())
+                    })?;
+                }
+
+                // ...and the percentage
+                let prev_percentage = prev & ((1 << PERCENTAGE_BITS) - 1);
+                if self.show_percentage && prev_percentage != self.percentage() {
+                    ui.paint(&percentage_area, |ui| {
+                        ui.clear_area(percentage_area)?;
+                        ui.draw(&percentage_text)
+                            .map_err(|_| GuiError::DrawError(Some("Couldn't draw percentage")))?;
+                        Ok(())
+                    })?;
+                }
+            }
+            _ => {
+                ui.paint(&area, |ui| {
+                    if !ui.cleared() {
+                        ui.clear_area(area)?;
+                    }
+
+                    ui.draw(&bar.into_styled(bar_style))?;
+                    ui.draw(&self.fill_rect(&inner, 0, fill).into_styled(fill_style))?;
+
+                    if let Some(label) = self.label {
+                        let label = ui
+                            .style()
+                            .text(label, text_pos, ui.style().background_color);
+                        ui.draw(&label)
+                            .map_err(|_| GuiError::DrawError(Some("Couldn't draw label")))?;
+                    }
+                    if self.show_percentage {
+                        ui.draw(&percentage_text)
+                            .map_err(|_| GuiError::DrawError(Some("Couldn't draw percentage")))?;
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(Response::new(iresponse))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_progress_bar_partial_redraw() {
+        let mut smartstate = Smartstate::empty();
+        let mut frame = |value: u32| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.add(
+                ProgressBar::new(value, 100)
+                    .width(52)
+                    .smartstate(&mut smartstate),
+            );
+            display.affected_area()
+        };
+
+        // the inner bar is 50px long, starting at x = 3 (border padding) + 1 (border)
+        assert_eq!(frame(50).size, Size::new(52, 16));
+        assert_eq!(
+            frame(60),
+            Rectangle::new(Point::new(4 + 25, 4), Size::new(5, 14))
+        );
+        assert_eq!(frame(60), Rectangle::zero());
+        assert_eq!(
+            frame(40),
+            Rectangle::new(Point::new(4 + 20, 4), Size::new(10, 14))
+        );
+    }
+}
@@ -0,0 +1,531 @@
+//! # Radio Button Widgets
+//!
+//! Radio buttons pick one of several exclusive options (e.g. a mode: Auto / Manual / Off).
+//! Each option has a value, and the option whose value equals the bound value is selected.
+//!
+//! - [RadioButton] is a single option, to be placed freely in the [Ui].
+//! - [RadioGroup] lays out a whole set of options horizontally or vertically.
+
+use crate::smartstate::{Container, Smartstate};
+use crate::widgets::Orientation;
+use crate::{GuiResult, Interaction, InternalResponse, Response, Ui, Widget};
+use core::cmp::max;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle};
+
+/// Returns the diameter of the radio circle (like the size of a [Checkbox](crate::widgets::Checkbox)).
+fn circle_size<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(ui: &Ui<DRAW, COL>) -> u32 {
+    ui.style().default_widget_height.max(ui.get_row_height())
+}
+
+/// Returns the size of a radio option with the given label.
+fn option_size<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+    ui: &Ui<DRAW, COL>,
+    size: u32,
+    label: Option<&str>,
+) -> Size {
+    match label {
+        Some(label) => {
+            let style = ui.style();
+            let font = style.default_font;
+            Size::new(
+                size + style.spacing.item_spacing.width + font.text_width(label),
+                max(size, font.line_height()),
+            )
+        }
+        None => Size::new(size, size),
+    }
+}
+
+/// Returns the look of a radio option for its interaction (0: none, 1: hovered, 2: pressed).
+fn look(interaction: Interaction) -> u32 {
+    match interaction {
+        Interaction::Click(_) | Interaction::Drag(_) | Interaction::Release(_) => 2,
+        Interaction::Hover(_) => 1,
+        _ => 0,
+    }
+}
+
+/// Draws a radio option (circle and label) in the given area, if its smartstate changed.
+fn draw_option<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+    ui: &mut Ui<DRAW, COL>,
+    area: Rectangle,
+    size: u32,
+    label: Option<&str>,
+    selected: bool,
+    interaction: Interaction,
+    smartstate: &mut Container<Smartstate>,
+) -> GuiResult<()> {
+    let look = look(interaction);
+
+    let prevstate = smartstate.clone_inner();
+    smartstate.modify(|st| *st = Smartstate::state(look | (selected as u32) << 2));
+    if smartstate.eq_option(&prevstate) {
+        return Ok(());
+    }
+
+    let style = *ui.style();
+    let circle_style = match look {
+        2 => PrimitiveStyleBuilder::new()
+            .fill_color(style.primary_color)
+            .stroke_color(style.highlight_border_color)
+            .stroke_width(style.highlight_border_width),
+        1 => PrimitiveStyleBuilder::new()
+            .fill_color(style.highlight_item_background_color)
+            .stroke_color(style.highlight_border_color)
+            .stroke_width(style.highlight_border_width),
+        _ => PrimitiveStyleBuilder::new()
+            .fill_color(style.item_background_color)
+            .stroke_color(style.border_color)
+            .stroke_width(style.border_width),
+    }
+    .build();
+
+    let circle = Circle::new(
+        area.top_left + Point::new(0, (area.size.height - size) as i32 / 2),
+        size,
+    );
+
+    ui.paint(&area, |ui| {
+        if !ui.cleared() {
+            // the circle doesn't cover its corners
+            ui.clear_area(area)?;
+        }
+        ui.draw(&circle.into_styled(circle_style))?;
+        if selected {
+            ui.draw(
+                &Circle::with_center(circle.center(), size / 2)
+                    .into_styled(PrimitiveStyle::with_fill(style.text_color)),
+            )?;
+        }
+        if let Some(label) = label {
+            let text_height = style.default_font.line_height();
+            ui.draw(&style.text(
+                label,
+                area.top_left
+                    + Point::new(
+                        (size + style.spacing.item_spacing.width) as i32,
+                        (area.size.height.saturating_sub(text_height)) as i32 / 2,
+                    ),
+                style.background_color,
+            ))?;
+        }
+        Ok(())
+    })
+}
+
+/// # Radio Button Widget
+///
+/// A single option of a set of exclusive options, drawn as a circle (in the style of a
+/// [Checkbox](crate::widgets::Checkbox)) that is filled with a dot while the option is selected.
+///
+/// The radio button binds a value that is shared by all options, and selects its own `value`
+/// when tapped. Only the radio button that changed the value reports `changed()`.
+/// The other radio buttons of the set are redrawn once they are drawn again, so if several
+/// radio buttons share a smartstate-less value, prefer a [RadioGroup], which draws all
+/// options in the same frame as the change.
+///
+/// # Example
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::smartstate::*;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// # let mut smartstates = SmartstateProvider::<20>::new();
+/// use kolibri_embedded_gui::widgets::RadioButton;
+///
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Mode {
+///     Auto,
+///     Manual,
+/// }
+///
+/// let mut mode = Mode::Auto;
+///
+/// ui.add_horizontal(RadioButton::new(&mut mode, Mode::Auto).label("Auto"));
+/// if ui
+///     .add(
+///         RadioButton::new(&mut mode, Mode::Manual)
+///             .label("Manual")
+///             .smartstate(smartstates.nxt()),
+///     )
+///     .changed()
+/// {
+///     // switched to manual mode
+/// }
+/// ```
+pub struct RadioButton<'a, T: PartialEq + Copy> {
+    current: &'a mut T,
+    value: T,
+    label: Option<&'a str>,
+    smartstate: Container<'a, Smartstate>,
+    enabled: bool,
+}
+
+impl<'a, T: PartialEq + Copy> RadioButton<'a, T> {
+    /// Creates a new radio button, which selects `value` when tapped.
+    pub fn new(current: &'a mut T, value: T) -> Self {
+        RadioButton {
+            current,
+            value,
+            label: None,
+            smartstate: Container::empty(),
+            enabled: true,
+        }
+    }
+
+    /// Adds a label to the right of the radio button. Tapping the label also selects the option.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Adds a smartstate to the radio button for incremental redrawing.
+    pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
+        self.smartstate.set(smartstate);
+        self
+    }
+
+    /// Enables or disables the radio button (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled radio button is greyed out, and can't be selected or focused.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+impl<T: PartialEq + Copy> Widget for RadioButton<'_, T> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |radio| radio.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl<T: PartialEq + Copy> RadioButton<'_, T> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        // allocate space
+        let circle = circle_size(ui);
+        let size = option_size(ui, circle, self.label);
+        let iresponse = ui.allocate_focusable_space(size)?;
+
+        // check interaction
+        let mut changed = false;
+        if iresponse.released() {
+            changed = *self.current != self.value;
+            *self.current = self.value;
+        }
+
+        draw_option(
+            ui,
+            iresponse.area,
+            circle,
+            self.label,
+            *self.current == self.value,
+            iresponse.interaction,
+            &mut self.smartstate,
+        )?;
+
+        Ok(Response::new(iresponse).set_changed(changed))
+    }
+}
+
+/// # Radio Group Widget
+///
+/// A set of exclusive options, laid out horizontally (default) or vertically, each drawn like a
+/// [RadioButton].
+///
+/// The group handles the interaction before drawing its options, so both the previously and the
+/// newly selected option are redrawn in the frame of the change. Each option can have its own
+/// smartstate (see [RadioGroup::smartstates]), so only the options that changed are redrawn.
+///
+/// The [Response] is `changed()` if the selection changed, and
+/// [changed_part()](Response::changed_part) returns the index of the newly selected option.
+///
+/// # Example
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::smartstate::*;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// # let mut smartstates = SmartstateProvider::<20>::new();
+/// use kolibri_embedded_gui::widgets::{Orientation, RadioGroup};
+///
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Mode {
+///     Auto,
+///     Manual,
+///     Off,
+/// }
+///
+/// let mut mode = Mode::Auto;
+/// let options = [(Mode::Auto, "Auto"), (Mode::Manual, "Manual"), (Mode::Off, "Off")];
+///
+/// if ui
+///     .add(
+///         RadioGroup::new(&mut mode, &options)
+///             .orientation(Orientation::Vertical)
+///             .smartstates(smartstates.nxt_n(options.len())),
+///     )
+///     .changed()
+/// {
+///     // mode changed
+/// }
+/// ```
+pub struct RadioGroup<'a, T: PartialEq + Copy> {
+    current: &'a mut T,
+    options: &'a [(T, &'a str)],
+    orientation: Orientation,
+    smartstates: &'a mut [Smartstate],
+    enabled: bool,
+}
+
+impl<'a, T: PartialEq + Copy> RadioGroup<'a, T> {
+    /// Creates a new radio group with the given options (value and label).
+    pub fn new(current: &'a mut T, options: &'a [(T, &'a str)]) -> Self {
+        RadioGroup {
+            current,
+            options,
+            orientation: Orientation::Horizontal,
+            smartstates: &mut [],
+            enabled: true,
+        }
+    }
+
+    /// Sets whether the options are laid out next to each other (default) or below each other.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Adds smartstates for incremental redrawing, one per option (in the order of the options).
+    ///
+    /// Options without a smartstate are always redrawn.
+    /// See [SmartstateProvider::nxt_n](crate::smartstate::SmartstateProvider::nxt_n) and
+    /// [SmartstateMap::get_n](crate::smartstate::SmartstateMap::get_n).
+    pub fn smartstates(mut self, smartstates: &'a mut [Smartstate]) -> Self {
+        self.smartstates = smartstates;
+        self
+    }
+
+    /// Enables or disables the whole group (enabled by default, see [Ui::enabled]).
+    ///
+    /// The options of a disabled group are greyed out, and can't be selected or focused.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Returns the offset of the next option after an option of the given size.
+    fn advance(&self, size: Size, spacing: Size) -> Point {
+        match self.orientation {
+            Orientation::Horizontal => Point::new((size.width + spacing.width) as i32, 0),
+            Orientation::Vertical => Point::new(0, (size.height + spacing.height) as i32),
+        }
+    }
+
+    /// Returns the area of the option with the given index, relative to the top left corner of
+    /// the group.
+    fn option_area<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &self,
+        ui: &Ui<DRAW, COL>,
+        circle: u32,
+        index: usize,
+    ) -> Rectangle {
+        let spacing = ui.style().spacing.item_spacing;
+        let pos = self.options[..index]
+            .iter()
+            .fold(Point::zero(), |pos, (_, label)| {
+                pos + self.advance(option_size(ui, circle, Some(label)), spacing)
+            });
+        Rectangle::new(pos, option_size(ui, circle, Some(self.options[index].1)))
+    }
+}
+
+impl<T: PartialEq + Copy> Widget for RadioGroup<'_, T> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |group| &mut *group.smartstates,
+            Self::draw_widget,
+        )
+    }
+}
+
+impl<T: PartialEq + Copy> RadioGroup<'_, T> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        // get size
+        let circle = circle_size(ui);
+        let size = (0..self.options.len()).fold(Size::zero(), |size, index| {
+            let area = self.option_area(ui, circle, index);
+            let bottom_right = area.top_left + area.size;
+            Size::new(
+                max(size.width, bottom_right.x as u32),
+                max(size.height, bottom_right.y as u32),
+            )
+        });
+
+        // register focus for each option (at most one is focused)
+        let mut focus = None;
+        for index in 0..self.options.len() {
+            let option_focus = ui.register_focus(false);
+            if option_focus.focused {
+                focus = Some((index, option_focus));
+            }
+        }
+
+        // allocate space
+        let iresponse = ui.allocate_space(size)?;
+        let offset = iresponse.area.top_left;
+        let interaction = |index: usize, area: &Rectangle| {
+            let pointer = match iresponse.interaction.get_point() {
+                Some(pt) if area.contains(pt) => iresponse.interaction,
+                _ => Interaction::None,
+            };
+            match focus {
+                Some((focused, focus)) if focused == index => {
+                    focus.as_pointer_interaction(area, pointer)
+                }
+                _ => pointer,
+            }
+        };
+
+        // check interaction before drawing, so that all options are up to date
+        let mut changed = None;
+        for index in 0..self.options.len() {
+            let area = self.option_area(ui, circle, index).translate(offset);
+            let value = self.options[index].0;
+            if let Interaction::Release(_) = interaction(index, &area) {
+                if *self.current != value {
+                    changed = Some(index);
+                }
+                *self.current = value;
+            }
+        }
+
+        // draw options
+        for (index, (value, label)) in self.options.iter().enumerate() {
+            let area = self.option_area(ui, circle, index).translate(offset);
+
+            let mut smartstate = match self.smartstates.get_mut(index) {
+                Some(smartstate) => Container::new(smartstate),
+                None => Container::empty(),
+            };
+            draw_option(
+                ui,
+                area,
+                circle,
+                Some(label),
+                *self.current == *value,
+                interaction(index, &area),
+                &mut smartstate,
+            )?;
+        }
+
+        let response = Response::new(InternalResponse::new(iresponse.area, iresponse.interaction));
+        Ok(match changed {
+            Some(index) => response.set_changed_part(index),
+            None => response,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smartstate::SmartstateProvider;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    const OPTIONS: [(u8, &str); 3] = [(1, "A"), (2, "B"), (3, "C")];
+
+    fn frame(
+        current: &mut u8,
+        smartstates: &mut SmartstateProvider<3>,
+        interaction: Interaction,
+    ) -> Response {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(interaction);
+        smartstates.restart_counter();
+        ui.add(
+            RadioGroup::new(current, &OPTIONS)
+                .orientation(Orientation::Vertical)
+                .smartstates(smartstates.nxt_n(3)),
+        )
+    }
+
+    #[test]
+    fn test_radio_group() {
+        let mut current = 1;
+        let mut smartstates = SmartstateProvider::<3>::new();
+        frame(&mut current, &mut smartstates, Interaction::None);
+        assert!(smartstates.get(0).is_state(1 << 2));
+        assert!(smartstates.get(1).is_state(0));
+
+        // options are 16px high with 4px spacing, starting at y = 3
+        let response = frame(
+            &mut current,
+            &mut smartstates,
+            Interaction::Release(Point::new(10, 30)),
+        );
+        assert_eq!(response.changed_part(), Some(1));
+        assert_eq!(current, 2);
+        // both the old and the new option are redrawn in the same frame
+        assert!(smartstates.get(0).is_state(0));
+        assert!(smartstates.get(1).is_state(2 | 1 << 2));
+
+        // tapping the selected option doesn't change anything
+        let response = frame(
+            &mut current,
+            &mut smartstates,
+            Interaction::Release(Point::new(10, 30)),
+        );
+        assert!(!response.changed());
+        assert_eq!(current, 2);
+    }
+
+    #[test]
+    fn test_radio_button() {
+        let mut current = 1u8;
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(Interaction::Release(Point::new(10, 10)));
+        assert!(ui.add(RadioButton::new(&mut current, 2)).changed());
+        assert!(!ui.add(RadioButton::new(&mut current, 1)).changed());
+        assert_eq!(current, 2);
+    }
+}
@@ -5,8 +5,12 @@
 //! # Core Features
 //!
 //! - Interactive value selection within inclusive ranges using fixed-point arithmetic
+//! - Generic over the value type (`u8`..`u32`, `i8`..`i32` and `f32`, see [SliderValue])
 //! - Step-based value adjustments for precise control
 //! - Optional text labels for clear identification
+//! - Optional value readout next to the track or under the knob
+//! - Horizontal and vertical orientation (see [Orientation])
+//! - [RangeSlider] with two knobs for selecting a range
 //! - Customizable width to fit various layouts
 //! - Visual feedback for different interaction states (normal, hover, active)
 //!
@@ -51,7 +55,8 @@
 //! The slider consists of several visual elements:
 //! - A horizontal track line representing the full value range
 //! - A movable knob indicating the current value position
-//! - Optional label text displayed above the slider
+//! - Optional label text displayed below the slider
+//! - Optional value readout (see [Readout])
 //! - Visual states (normal, hover, active) with appropriate color changes
 //!
 //! # Interaction Details
@@ -62,17 +67,21 @@
 //! - Visual feedback through dynamic styling
 //! - Efficient fixed-point arithmetic for smooth value interpolation
 //!
+use crate::focus::Focus;
+use crate::font::FontText;
 use crate::smartstate::{Container, Smartstate};
-use crate::{GuiResult, Interaction, Response, Ui, Widget};
+use crate::style::Style;
+use crate::{GuiError, GuiResult, Interaction, Response, Ui, Widget};
 use core::cmp::max;
+use core::fmt::{self, Write};
 use core::ops::RangeInclusive;
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::{Point, Size};
-use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::PixelColor;
 use embedded_graphics::prelude::*;
-use embedded_graphics::primitives::{Circle, Line, PrimitiveStyleBuilder};
-use embedded_graphics::text::{Alignment, Baseline, Text};
+use embedded_graphics::primitives::{
+    Circle, Line, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle,
+};
 
 /// Performs linear interpolation using fixed-point arithmetic for embedded systems.
 ///
@@ -80,17 +89,11 @@ use embedded_graphics::text::{Alignment, Baseline, Text};
 /// within the range `[min_t, max_t]`. It uses only integer arithmetic to maintain performance
 /// and predictability on embedded systems.
 ///
+/// The calculations are done in `i64`, so they can't overflow as long as the product of the
+/// two range spans fits into 63 bits (e.g. the full `u32` range times a 16-bit pixel position).
+///
 /// Returns the interpolated value clamped within the valid range.
-fn lerp_fixed(start: i16, end: i16, t: i16, min_t: i16, max_t: i16) -> i16 {
-    // Convert to i32 to prevent overflow during calculations
-    let (start, end, t, min_t, max_t) = (
-        start as i32,
-        end as i32,
-        t as i32,
-        min_t as i32,
-        max_t as i32,
-    );
-
+fn lerp_fixed(start: i64, end: i64, t: i64, min_t: i64, max_t: i64) -> i64 {
     // Clamp `t` between `min_t` and `max_t`
     let clamped_t = if t < min_t {
         min_t
@@ -103,33 +106,430 @@ fn lerp_fixed(start: i16, end: i16, t: i16, min_t: i16, max_t: i16) -> i16 {
     // Calculate the range
     let range = max_t - min_t;
     if range == 0 {
-        return start as i16;
+        return start;
     }
 
     // Perform linear interpolation using only integer arithmetic
-    let interpolated = start + ((end - start) * (clamped_t - min_t) + (range / 2)) / range;
+    start + ((end - start) * (clamped_t - min_t) + (range / 2)) / range
+}
+
+/// Snaps the value to the nearest multiple of `step`, clamped to `start..=end`.
+fn snap_fixed(value: i64, step: i64, start: i64, end: i64) -> i64 {
+    let step = step.max(1);
+    let to_prev = value.rem_euclid(step);
+    let to_next = step - to_prev;
+    if to_prev < to_next {
+        (value - to_prev).clamp(start, end)
+    } else {
+        (value + to_next).clamp(start, end)
+    }
+}
+
+/// Rounds to the nearest integer (`f32::round` is not available in `core`).
+fn round_f32(value: f32) -> i64 {
+    if value >= 0.0 {
+        (value + 0.5) as i64
+    } else {
+        (value - 0.5) as i64
+    }
+}
+
+/// Numeric types that can be controlled by a [Slider].
+///
+/// This is implemented for `u8`, `u16`, `u32`, `i8`, `i16`, `i32` and `f32`. Integers are
+/// interpolated with fixed-point arithmetic (in `i64`, so even the full `u32` range can't overflow),
+/// floats are interpolated directly.
+pub trait SliderValue: Copy + PartialOrd {
+    /// The default step size of a slider (`1` for integers, `0.0` (continuous) for floats).
+    const DEFAULT_STEP: Self;
+
+    /// Maps the value from `range` onto a position in `min_t..=max_t`.
+    fn to_position(self, range: &RangeInclusive<Self>, min_t: i32, max_t: i32) -> i32;
+
+    /// Maps a position in `min_t..=max_t` onto `range`, snapped to the nearest multiple of `step`.
+    fn from_position(
+        t: i32,
+        range: &RangeInclusive<Self>,
+        min_t: i32,
+        max_t: i32,
+        step: Self,
+    ) -> Self;
+
+    /// Adds `steps` times `step` to the value, clamped to `range`.
+    fn add_steps(self, steps: i32, step: Self, range: &RangeInclusive<Self>) -> Self;
+
+    /// Writes the value for the readout of the slider. `decimals` is only used by floats.
+    fn write_value<W: Write>(self, w: &mut W, decimals: usize) -> fmt::Result;
+}
+
+macro_rules! impl_slider_value_int {
+    ($($t:ty),*) => {$(
+        impl SliderValue for $t {
+            const DEFAULT_STEP: Self = 1;
+
+            fn to_position(self, range: &RangeInclusive<Self>, min_t: i32, max_t: i32) -> i32 {
+                lerp_fixed(
+                    min_t as i64,
+                    max_t as i64,
+                    self as i64,
+                    *range.start() as i64,
+                    *range.end() as i64,
+                ) as i32
+            }
+
+            fn from_position(
+                t: i32,
+                range: &RangeInclusive<Self>,
+                min_t: i32,
+                max_t: i32,
+                step: Self,
+            ) -> Self {
+                let (start, end) = (*range.start() as i64, *range.end() as i64);
+                let value = lerp_fixed(start, end, t as i64, min_t as i64, max_t as i64);
+                snap_fixed(value, step as i64, start, end) as Self
+            }
+
+            fn add_steps(self, steps: i32, step: Self, range: &RangeInclusive<Self>) -> Self {
+                (self as i64 + steps as i64 * (step as i64).max(1))
+                    .clamp(*range.start() as i64, *range.end() as i64) as Self
+            }
+
+            fn write_value<W: Write>(self, w: &mut W, _decimals: usize) -> fmt::Result {
+                write!(w, "{}", self)
+            }
+        }
+    )*};
+}
+
+impl_slider_value_int!(u8, u16, u32, i8, i16, i32);
+
+impl SliderValue for f32 {
+    const DEFAULT_STEP: Self = 0.0;
+
+    fn to_position(self, range: &RangeInclusive<Self>, min_t: i32, max_t: i32) -> i32 {
+        let span = *range.end() - *range.start();
+        if span <= 0.0 {
+            return min_t;
+        }
+        let fraction = ((self - *range.start()) / span).clamp(0.0, 1.0);
+        min_t + round_f32(fraction * (max_t - min_t) as f32) as i32
+    }
+
+    fn from_position(
+        t: i32,
+        range: &RangeInclusive<Self>,
+        min_t: i32,
+        max_t: i32,
+        step: Self,
+    ) -> Self {
+        if max_t <= min_t {
+            return *range.start();
+        }
+        let fraction = (t.clamp(min_t, max_t) - min_t) as f32 / (max_t - min_t) as f32;
+        let value = *range.start() + fraction * (*range.end() - *range.start());
+        if step > 0.0 {
+            (round_f32(value / step) as f32 * step).clamp(*range.start(), *range.end())
+        } else {
+            value
+        }
+    }
+
+    fn add_steps(self, steps: i32, step: Self, range: &RangeInclusive<Self>) -> Self {
+        // continuous sliders are adjusted in 1% steps
+        let step = if step > 0.0 {
+            step
+        } else {
+            (*range.end() - *range.start()) / 100.0
+        };
+        (self + steps as f32 * step).clamp(*range.start(), *range.end())
+    }
+
+    fn write_value<W: Write>(self, w: &mut W, decimals: usize) -> fmt::Result {
+        write!(w, "{:.*}", decimals, self)
+    }
+}
+
+/// Placement of the value readout of a [Slider].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Readout {
+    /// Don't show the value (default)
+    None,
+    /// Show the value next to (right of) the slider track
+    Beside,
+    /// Show the value under the knob (or right of it for vertical sliders), moving with it
+    UnderKnob,
+}
+
+/// Orientation of a [Slider] or [RangeSlider] (also used by e.g. a
+/// [ProgressBar](crate::widgets::ProgressBar) or [RadioGroup](crate::widgets::RadioGroup)).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Orientation {
+    /// The track goes from left (minimum) to right (maximum)
+    #[default]
+    Horizontal,
+    /// The track goes from bottom (minimum) to top (maximum)
+    Vertical,
+}
+
+/// Text buffer for the readout of a slider.
+type ReadoutText = heapless::String<24>;
+
+/// Thickness of the slider track line
+const TRACK_THICKNESS: u32 = 2;
+/// Diameter of the slider knobs
+const KNOB_DIAMETER: u32 = 10;
+
+/// Geometry of a slider track, shared by [Slider] and [RangeSlider].
+///
+/// Positions along the track (`t`) are relative to the widget, and grow towards the maximum
+/// (i.e. to the right, or upwards for vertical sliders).
+struct Track {
+    orientation: Orientation,
+    /// Top left corner of the widget
+    origin: Point,
+    /// Knob center position (along the track) of the minimum value
+    knob_min: i32,
+    /// Knob center position (along the track) of the maximum value
+    knob_max: i32,
+    /// Position of the track line across the track (relative to the widget)
+    cross: i32,
+}
+
+/// Bits of a knob position in a smartstate value (see [Track::knob_state])
+const POSITION_BITS: u32 = 12;
+/// Bits of the interaction value of a knob in a smartstate value (see [knob_style])
+const INTERACT_BITS: u32 = 3;
+/// Bits of the smartstate value of a knob
+const KNOB_STATE_BITS: u32 = POSITION_BITS + INTERACT_BITS;
+
+impl Track {
+    /// Returns the smartstate value of a knob at position `t`, with the interaction value of
+    /// its look (see [knob_style]).
+    ///
+    /// The position is counted in pixels from the minimum, so the state only changes if the knob
+    /// moves by at least a pixel or its look changes. Positions beyond 4095 pixels are clamped.
+    fn knob_state(&self, t: i32, interact_val: u16) -> u32 {
+        let position = (t - self.knob_min).clamp(0, (1 << POSITION_BITS) - 1) as u32;
+        position | (interact_val as u32 & ((1 << INTERACT_BITS) - 1)) << POSITION_BITS
+    }
+
+    /// Lays out a track of the given length (excluding padding) in a widget of the given size.
+    ///
+    /// `reserved` is the space after the track (horizontal) or next to it (vertical), e.g. for a readout.
+    fn new(
+        orientation: Orientation,
+        area: &Rectangle,
+        length: u32,
+        padding: Size,
+        reserved: u32,
+    ) -> Self {
+        let radius = (KNOB_DIAMETER / 2) as i32;
+        match orientation {
+            Orientation::Horizontal => Track {
+                orientation,
+                origin: area.top_left,
+                knob_min: padding.width as i32 + radius,
+                knob_max: (area.size.width - padding.width - reserved) as i32 - radius,
+                cross: padding.height as i32 + radius,
+            },
+            Orientation::Vertical => {
+                // center the track (and the reserved space) if the widget is wider, e.g. due to a label
+                let used = KNOB_DIAMETER + 2 * padding.width + reserved;
+                Track {
+                    orientation,
+                    origin: area.top_left,
+                    knob_min: padding.height as i32 + radius,
+                    knob_max: (length + padding.height) as i32 - radius,
+                    cross: (area.size.width.saturating_sub(used) / 2 + padding.width) as i32
+                        + radius,
+                }
+            }
+        }
+    }
+
+    /// Returns the absolute point on the track line at position `t`.
+    fn point(&self, t: i32) -> Point {
+        match self.orientation {
+            Orientation::Horizontal => self.origin + Point::new(t, self.cross),
+            Orientation::Vertical => {
+                self.origin + Point::new(self.cross, self.knob_min + self.knob_max - t)
+            }
+        }
+    }
+
+    /// Returns the position along the track of an (absolute) point.
+    fn position_of(&self, point: Point) -> i32 {
+        match self.orientation {
+            Orientation::Horizontal => point.x - self.origin.x,
+            Orientation::Vertical => self.knob_min + self.knob_max - (point.y - self.origin.y),
+        }
+    }
+
+    /// Returns the track line.
+    fn line(&self) -> Line {
+        Line::new(self.point(self.knob_min), self.point(self.knob_max))
+    }
+
+    /// Returns a knob at position `t` with the given diameter.
+    fn knob(&self, t: i32, diameter: u32) -> Circle {
+        Circle::with_center(self.point(t), diameter)
+    }
+}
 
-    interpolated as i16
+/// Returns the size of a slider widget with the given track length (excluding padding).
+///
+/// `reserved` is the space after the track (horizontal) or next to it (vertical), e.g. for a readout.
+fn slider_size<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+    ui: &Ui<DRAW, COL>,
+    orientation: Orientation,
+    length: u32,
+    reserved: u32,
+) -> Size {
+    let padding = ui.style().spacing.button_padding;
+    match orientation {
+        Orientation::Horizontal => Size::new(
+            length + 2 * padding.width + reserved,
+            max(
+                max(ui.style().default_widget_height, ui.get_row_height()),
+                KNOB_DIAMETER + padding.height * 2,
+            ),
+        ),
+        Orientation::Vertical => Size::new(
+            KNOB_DIAMETER + 2 * padding.width + reserved,
+            length + 2 * padding.height,
+        ),
+    }
+}
+
+/// Returns the style of a slider knob and its smartstate value.
+///
+/// `active` is whether the knob is being dragged.
+fn knob_style<COL: PixelColor>(
+    style: &Style<COL>,
+    interaction: Interaction,
+    focus: Focus,
+    active: bool,
+) -> (PrimitiveStyle<COL>, u16) {
+    let mut knob_style = PrimitiveStyleBuilder::new()
+        .stroke_color(style.border_color)
+        .stroke_width(1.max(style.border_width))
+        .fill_color(style.background_color)
+        .build();
+
+    let interact_val = match interaction {
+        _ if active => {
+            knob_style.fill_color = Some(style.primary_color);
+            2
+        }
+        _ if focus.editing => {
+            knob_style.fill_color = Some(style.primary_color);
+            knob_style.stroke_color = Some(style.highlight_border_color);
+            knob_style.stroke_width = style.highlight_border_width;
+            3
+        }
+        _ if focus.focused => {
+            knob_style.fill_color = Some(style.highlight_item_background_color);
+            knob_style.stroke_color = Some(style.highlight_border_color);
+            knob_style.stroke_width = style.highlight_border_width;
+            4
+        }
+        Interaction::Hover(_) => {
+            knob_style.fill_color = Some(style.highlight_item_background_color);
+            1
+        }
+        _ => {
+            knob_style.fill_color = Some(style.item_background_color);
+            0
+        }
+    };
+    (knob_style, interact_val)
+}
+
+/// Returns the centered label text of a slider, and grows the size to fit it below the track.
+fn slider_label<'t, COL: PixelColor>(
+    label: Option<&'t str>,
+    style: &Style<COL>,
+    size: &mut Size,
+) -> Option<FontText<'t, COL>> {
+    let padding = style.spacing.button_padding;
+    label.map(|label| {
+        let text = style.text(label, Point::zero(), style.background_color);
+        size.height += padding.height + text.bounding_box().size.height;
+        size.width = size
+            .width
+            .max(text.bounding_box().size.width + 2 * padding.width);
+        text
+    })
+}
+
+/// Moves the label text to the bottom center of the widget.
+fn place_label<COL: PixelColor>(text: &mut FontText<'_, COL>, area: &Rectangle, padding: Size) {
+    let center_offset = area.top_left
+        + Point::new(
+            (area.size.width as i32 - text.bounding_box().size.width as i32) / 2,
+            (area.size.height - text.bounding_box().size.height - padding.height) as i32,
+        );
+    text.translate_mut(center_offset);
 }
 
 /// An interactive slider widget for selecting numeric values.
 ///
 /// The Slider widget provides a graphical way to select values within an inclusive range.
-/// It supports features like step-based value adjustments, optional labels, and visual
-/// feedback for user interactions.
+/// It supports features like step-based value adjustments, optional labels, an optional value
+/// readout, a vertical orientation, and visual feedback for user interactions.
 ///
-/// The widget uses fixed-point arithmetic for smooth value interpolation while maintaining
+/// The slider is generic over the controlled value (see [SliderValue]), so it can directly
+/// control e.g. a `u8` PWM duty cycle, a `u32` frequency or an `f32` setpoint.
+/// Integers use fixed-point arithmetic for smooth value interpolation while maintaining
 /// performance on embedded systems.
-pub struct Slider<'a> {
-    value: &'a mut i16,
-    range: RangeInclusive<i16>,
-    step_size: u16,
+///
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// use kolibri_embedded_gui::widgets::slider::{Orientation, Readout, Slider};
+///
+/// let mut duty = 128u8;
+/// ui.add(Slider::new(&mut duty, 0..=255).readout(Readout::Beside));
+///
+/// let mut frequency = 440_000u32;
+/// ui.add(Slider::new(&mut frequency, 20..=4_000_000_000).step_size(1000));
+///
+/// let mut setpoint = 21.5f32;
+/// ui.add(
+///     Slider::new(&mut setpoint, 15.0..=30.0)
+///         .step_size(0.5)
+///         .readout(Readout::UnderKnob)
+///         .decimals(1),
+/// );
+///
+/// // a vertical fader, 100px high
+/// let mut gain = 0i8;
+/// ui.add(
+///     Slider::new(&mut gain, -12..=12)
+///         .orientation(Orientation::Vertical)
+///         .width(100)
+///         .label("Gain"),
+/// );
+/// ```
+pub struct Slider<'a, T: SliderValue = i16> {
+    value: &'a mut T,
+    range: RangeInclusive<T>,
+    step_size: T,
     label: Option<&'a str>,
     width: u32,
+    orientation: Orientation,
+    readout: Readout,
+    decimals: usize,
     smartstate: Container<'a, Smartstate>,
+    enabled: bool,
 }
 
-impl<'a> Slider<'a> {
+impl<'a, T: SliderValue> Slider<'a, T> {
     /// Creates a new slider with the given value and range.
     ///
     /// # Arguments
@@ -137,22 +537,27 @@ impl<'a> Slider<'a> {
     /// * `value` - A mutable reference to the value to be controlled
     /// * `range` - An inclusive range specifying the minimum and maximum allowed values
     ///
-    /// By default, the slider uses a step size of 1, no label, and a width of 200 pixels.
+    /// By default, the slider is horizontal, and uses a step size of [SliderValue::DEFAULT_STEP],
+    /// no label, no readout and a width of 200 pixels.
     /// These can be customized using the builder methods.
-    pub fn new(value: &'a mut i16, range: RangeInclusive<i16>) -> Self {
+    pub fn new(value: &'a mut T, range: RangeInclusive<T>) -> Self {
         Self {
             value,
             range,
-            step_size: 1,
+            step_size: T::DEFAULT_STEP,
             smartstate: Container::empty(),
+            enabled: true,
             label: None,
             width: 200,
+            orientation: Orientation::Horizontal,
+            readout: Readout::None,
+            decimals: 1,
         }
     }
 
     /// Sets the label text for the slider.
     ///
-    /// The label is displayed above the slider track and helps identify its purpose.
+    /// The label is displayed below the slider track and helps identify its purpose.
     pub fn label(mut self, label: &'a str) -> Self {
         self.label = Some(label);
         self
@@ -160,247 +565,650 @@ impl<'a> Slider<'a> {
 
     /// Adds a smartstate to the slider for incremental redrawing.
     ///
-    /// The smartstate tracks the slider's value and interaction state to minimize
-    /// unnecessary redraws.
+    /// The smartstate tracks the position of the knob and the interaction state to minimize
+    /// unnecessary redraws. The readout is also redrawn whenever the slider changes the value,
+    /// but a value that is changed elsewhere without moving the knob requires
+    /// [Smartstate::force_redraw] to update the readout.
     pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
         self.smartstate.set(smartstate);
         self
     }
 
-    /// Sets the width of the slider in pixels.
+    /// Enables or disables the slider (enabled by default, see [Ui::enabled]).
     ///
-    /// This affects only the track and interaction area width. The total width
-    /// may be larger if the label text exceeds this value.
+    /// A disabled slider still shows its value, but is greyed out and can't be dragged or adjusted.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets the width (the length of the track) of the slider in pixels.
+    ///
+    /// This affects only the track and interaction area. For vertical sliders, this is the height
+    /// of the track. The total size may be larger if the label text exceeds this value,
+    /// or if the readout is placed beside the track.
     pub fn width(mut self, width: u32) -> Self {
         self.width = width;
         self
     }
 
+    /// Sets the orientation of the slider (horizontal by default).
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
     /// Sets the step size for value adjustments.
     ///
     /// The step size determines how the value snaps during interaction:
     /// - Values will snap to the nearest multiple of the step size
-    /// - Must be at least 1 and at most the range span
+    /// - Integer step sizes smaller than 1 are treated as 1
+    /// - A float step size of `0.0` allows continuous values
     ///
     /// # Arguments
     ///
     /// * `step_size` - The size of each discrete step
-    pub fn step_size(mut self, step_size: u16) -> Self {
-        let range_span = (*self.range.end() - *self.range.start()).abs();
-        self.step_size = step_size.clamp(1, range_span as u16);
+    pub fn step_size(mut self, step_size: T) -> Self {
+        self.step_size = step_size;
+        self
+    }
+
+    /// Shows the current value next to the track or under the knob (see [Readout]).
+    pub fn readout(mut self, readout: Readout) -> Self {
+        self.readout = readout;
         self
     }
+
+    /// Sets the number of decimals of the readout for float values (default: 1).
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Formats the given value for the readout.
+    fn format_value(&self, value: T) -> ReadoutText {
+        let mut text = ReadoutText::new();
+        // values that don't fit are cut off
+        value.write_value(&mut text, self.decimals).ok();
+        text
+    }
 }
 
-impl Widget for Slider<'_> {
+impl<T: SliderValue> Widget for Slider<'_, T> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
         ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |slider| slider.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl<T: SliderValue> Slider<'_, T> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response> {
         // get size
 
         let padding = ui.style().spacing.button_padding;
-
-        let slider_thickness = 2;
-        let slider_knob_diameter = 10;
-
-        let mut height = max(
-            max(ui.style().default_widget_height, ui.get_row_height()),
-            slider_knob_diameter + padding.height * 2,
-        );
-
-        let mut width = self.width + 2 * padding.width;
-
         let font = ui.style().default_font;
-        let mut text = if let Some(label) = self.label {
-            let mut text = Text::new(
-                label,
-                Point::new(0, 0),
-                MonoTextStyle::new(&font, ui.style().text_color),
-            );
-            text.text_style.alignment = Alignment::Center;
-            text.text_style.baseline = Baseline::Top;
-            height += padding.height + text.bounding_box().size.height;
-            width = width.max(text.bounding_box().size.width + 2 * padding.width);
-            Some(text)
+        let vertical = self.orientation == Orientation::Vertical;
+
+        // readout (sized for the widest value of the range)
+        let readout_size = if self.readout != Readout::None {
+            let start = self.format_value(*self.range.start());
+            let end = self.format_value(*self.range.end());
+            let widest = if start.len() > end.len() { start } else { end };
+            Size::new(font.text_width(&widest), font.line_height())
         } else {
-            None
+            Size::zero()
         };
-
-        let size = Size::new(width, height);
-
-        /*
-        let icon = match size.width - 2 * padding.width {
-            0..=17 => 12,
-            18..=24 => 18,
-            24..=32 => 24,
-            _ => 32,
+        // space next to the track (the readout under the knob of horizontal sliders gets its own row)
+        let readout_space = match self.readout {
+            Readout::Beside => readout_size.width + padding.width,
+            Readout::UnderKnob if vertical => readout_size.width + padding.width,
+            _ => 0,
         };
-         */
+
+        let mut size = slider_size(ui, self.orientation, self.width, readout_space);
+        if self.readout == Readout::UnderKnob && !vertical {
+            size.height += readout_size.height + padding.height;
+        }
+        let mut text = slider_label(self.label, ui.style(), &mut size);
 
         // allocate space
-        let iresponse = ui.allocate_space(Size::new(size.width, max(size.height, height)))?;
+        let focus = ui.register_focus(true);
+        let iresponse = ui.allocate_space(size)?;
 
-        // slider main line
-        let slider_line = Line::new(
-            Point::new(
-                iresponse.area.top_left.x + padding.width as i32 + slider_knob_diameter as i32 / 2,
-                iresponse.area.top_left.y + (padding.height + slider_knob_diameter / 2) as i32,
-            ),
-            Point::new(
-                iresponse.area.top_left.x
-                    + (size.width - padding.width - slider_knob_diameter / 2) as i32,
-                iresponse.area.top_left.y + (padding.height + slider_knob_diameter / 2) as i32,
-            ),
+        let track = Track::new(
+            self.orientation,
+            &iresponse.area,
+            self.width,
+            padding,
+            readout_space,
         );
 
         let style = ui.style();
         let line_style = PrimitiveStyleBuilder::new()
             .stroke_color(style.border_color)
-            .stroke_width(slider_thickness)
+            .stroke_width(TRACK_THICKNESS)
             .fill_color(style.primary_color)
             .build();
-        let mut slider_knob_style = PrimitiveStyleBuilder::new()
-            .stroke_color(style.border_color)
-            .stroke_width(1.max(style.border_width))
-            .fill_color(style.background_color)
-            .build();
         let old_slider_knob_style = PrimitiveStyleBuilder::new()
             .stroke_color(style.background_color)
             .stroke_width(0)
             .fill_color(style.background_color)
             .build();
 
-        // previous slider knob circle for clearing it
-
         // center text (if it exists)
         if let Some(text) = text.as_mut() {
-            let center_offset = iresponse.area.top_left
-                + Point::new(
-                    (iresponse.area.size.width / 2) as i32,
-                    (iresponse.area.size.height - text.bounding_box().size.height - padding.height)
-                        as i32,
-                );
-            text.translate_mut(center_offset);
+            place_label(text, &iresponse.area, padding);
         }
 
-        // check for click
-        // let click = matches!(iresponse.interaction, Interaction::Release(_));
-        // let down = matches!(
-        //     iresponse.interaction,
-        //     Interaction::Click(_) | Interaction::Drag(_)
-        // );
-
         // find user input
-        // TODO
         let old_val = *self.value;
-        match iresponse.interaction {
-            Interaction::Click(point) | Interaction::Drag(point) => {
-                let slider_val = lerp_fixed(
-                    *self.range.start(),
-                    *self.range.end(),
-                    point.x as i16 - iresponse.area.top_left.x as i16,
-                    // + (slider_knob_diameter / 2) as i16,
-                    padding.width as i16 + slider_knob_diameter as i16 / 2,
-                    width as i16 - padding.width as i16 - slider_knob_diameter as i16 / 2,
-                );
-                let range_span = (*self.range.end() - *self.range.start()).abs();
-                let step_size = self.step_size.clamp(1, range_span as u16) as i16;
-                let to_next = slider_val.rem_euclid(step_size);
-                let to_prev = step_size - to_next;
-                if to_next < to_prev {
-                    *self.value = (slider_val - to_next).max(*self.range.start());
-                } else {
-                    *self.value = (slider_val + to_prev).min(*self.range.end());
-                }
-            }
-            _ => {}
+        if let Interaction::Click(point) | Interaction::Drag(point) = iresponse.interaction {
+            *self.value = T::from_position(
+                track.position_of(point),
+                &self.range,
+                track.knob_min,
+                track.knob_max,
+                self.step_size,
+            );
         }
 
-        let slider_knob_pos = lerp_fixed(
-            // padding.width as i16,
-            padding.width as i16 + slider_knob_diameter as i16 / 2,
-            width as i16 - padding.width as i16 - slider_knob_diameter as i16 / 2,
-            *self.value,
-            *self.range.start(),
-            *self.range.end(),
-        );
+        // adjust with focus navigation (e.g. a rotary encoder)
+        if focus.adjust != 0 {
+            *self.value = self
+                .value
+                .add_steps(focus.adjust as i32, self.step_size, &self.range);
+        }
 
-        let slider_knob = Circle::with_center(
-            Point::new(
-                iresponse.area.top_left.x + slider_knob_pos as i32,
-                iresponse.area.top_left.y
-                    + padding.height as i32
-                    + (slider_knob_diameter / 2) as i32,
-            ),
-            slider_knob_diameter,
-        );
+        let slider_knob_pos = self
+            .value
+            .to_position(&self.range, track.knob_min, track.knob_max);
+        let slider_knob = track.knob(slider_knob_pos, KNOB_DIAMETER);
 
         // old slider knob (for clearing)
-        let old_slider_knob_pos = lerp_fixed(
-            // padding.width as i16,
-            padding.width as i16 + slider_knob_diameter as i16 / 2,
-            width as i16 - padding.width as i16 - slider_knob_diameter as i16 / 2,
-            old_val,
-            *self.range.start(),
-            *self.range.end(),
-        );
+        let old_slider_knob_pos = old_val.to_position(&self.range, track.knob_min, track.knob_max);
+        let old_slider_knob = track.knob(old_slider_knob_pos, KNOB_DIAMETER + 4);
 
-        let old_slider_knob = Circle::with_center(
-            Point::new(
-                iresponse.area.top_left.x + old_slider_knob_pos as i32,
-                iresponse.area.top_left.y
-                    + padding.height as i32
-                    + (slider_knob_diameter / 2) as i32,
+        // styles and smartstate
+        let (slider_knob_style, interact_val) = knob_style(
+            style,
+            iresponse.interaction,
+            focus,
+            matches!(
+                iresponse.interaction,
+                Interaction::Click(_) | Interaction::Drag(_)
             ),
-            slider_knob_diameter + 4,
         );
+        let state_val = track.knob_state(slider_knob_pos, interact_val);
+        // the readout can change without the knob moving
+        let readout_changed = self.readout != Readout::None && old_val != *self.value;
 
-        // styles and smartstate
+        if readout_changed || !self.smartstate.eq_inner(&Smartstate::state(state_val)) {
+            ui.paint(&iresponse.area, |ui| {
+                if old_slider_knob_pos != slider_knob_pos {
+                    ui.draw(&old_slider_knob.into_styled(old_slider_knob_style))
+                        .ok();
+                }
+                ui.draw(&track.line().into_styled(line_style)).ok();
+                ui.draw(&slider_knob.into_styled(slider_knob_style)).ok();
+                if let Some(text) = text.as_mut() {
+                    ui.draw(text).unwrap();
+                }
 
-        let interact_val: u16 = match iresponse.interaction {
-            Interaction::Click(_) | Interaction::Drag(_) => {
-                slider_knob_style.fill_color = Some(style.primary_color);
-                2
-            }
-            Interaction::Hover(_) => {
-                slider_knob_style.fill_color = Some(style.highlight_item_background_color);
-                1
-            }
-            _ => {
-                slider_knob_style.fill_color = Some(style.item_background_color);
-                0
-            }
-        };
-        let state_val = (*self.value as u16) as u32 | ((interact_val as u32) << 16);
+                // readout
+                if self.readout != Readout::None {
+                    let value = self.format_value(*self.value);
+                    let style = ui.style();
+                    let mut readout = style.text(&value, Point::zero(), style.background_color);
+                    let text_size = readout.bounding_box().size;
+                    let area = &iresponse.area;
+                    let knob = track.point(slider_knob_pos);
+                    let side_x =
+                        track.origin.x + track.cross + (KNOB_DIAMETER / 2 + padding.width) as i32;
 
-        if !self.smartstate.eq_inner(&Smartstate::state(state_val)) {
-            ui.start_drawing(&iresponse.area);
+                    // area to clear, and the position of the text
+                    let (readout_area, position) = match (self.readout, vertical) {
+                        (Readout::UnderKnob, false) => {
+                            // center under the knob, but stay inside the widget
+                            let y = track.point(0).y + (KNOB_DIAMETER / 2 + padding.height) as i32;
+                            // a readout wider than the widget starts at its left edge
+                            let x = (knob.x - text_size.width as i32 / 2)
+                                .min(
+                                    area.top_left.x + area.size.width as i32
+                                        - text_size.width as i32,
+                                )
+                                .max(area.top_left.x);
+                            (
+                                Rectangle::new(
+                                    Point::new(area.top_left.x, y),
+                                    Size::new(area.size.width, readout_size.height),
+                                ),
+                                Point::new(x, y),
+                            )
+                        }
+                        (Readout::UnderKnob, true) => {
+                            // next to the knob, but stay next to the track
+                            let track_height = self.width + 2 * padding.height;
+                            let y = (knob.y - text_size.height as i32 / 2)
+                                .min(
+                                    area.top_left.y + track_height as i32 - text_size.height as i32,
+                                )
+                                .max(area.top_left.y);
+                            (
+                                Rectangle::new(
+                                    Point::new(side_x, area.top_left.y),
+                                    Size::new(readout_size.width, track_height),
+                                ),
+                                Point::new(side_x, y),
+                            )
+                        }
+                        (_, false) => {
+                            let position = Point::new(
+                                track.point(track.knob_max).x
+                                    + (KNOB_DIAMETER / 2 + padding.width) as i32,
+                                knob.y - readout_size.height as i32 / 2,
+                            );
+                            (Rectangle::new(position, readout_size), position)
+                        }
+                        (_, true) => {
+                            let center = track.point((track.knob_min + track.knob_max) / 2);
+                            let position =
+                                Point::new(side_x, center.y - readout_size.height as i32 / 2);
+                            (Rectangle::new(position, readout_size), position)
+                        }
+                    };
+                    ui.clear_area(readout_area)?;
+
+                    readout.translate_mut(position);
+                    ui.draw(&readout)
+                        .map_err(|_| GuiError::DrawError(Some("Couldn't draw slider readout")))?;
+                }
+                Ok(())
+            })?;
+        }
+
+        self.smartstate
+            .modify(|s| *s = Smartstate::state(state_val));
+
+        Ok(Response::new(iresponse).set_changed(old_val != *self.value))
+    }
+}
+
+/// A bound of a [RangeSlider].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RangeBound {
+    /// The lower bound (minimum) of the selected range
+    Lower,
+    /// The upper bound (maximum) of the selected range
+    Upper,
+}
+
+impl RangeBound {
+    /// Returns which bounds were changed according to the [Response] of a [RangeSlider],
+    /// as `(lower, upper)`.
+    pub fn changed(response: &Response) -> (bool, bool) {
+        let parts = response.changed_part().unwrap_or(0);
+        (
+            parts & RangeBound::Lower.bit() != 0,
+            parts & RangeBound::Upper.bit() != 0,
+        )
+    }
+
+    /// Returns the bit of the bound in the changed part mask (see [Response::set_changed_part]).
+    fn bit(self) -> usize {
+        match self {
+            RangeBound::Lower => 0b01,
+            RangeBound::Upper => 0b10,
+        }
+    }
+}
+
+/// State of a [RangeSlider], which has to persist across frames.
+///
+/// It remembers which knob was grabbed, so that the knob keeps tracking the pointer
+/// until it is released (even if it reaches the other knob).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RangeSliderState {
+    grabbed: Option<RangeBound>,
+}
+
+impl RangeSliderState {
+    /// Creates a new state, with no knob grabbed.
+    pub const fn new() -> Self {
+        Self { grabbed: None }
+    }
+
+    /// Returns the knob that is currently grabbed, if any.
+    pub fn grabbed(&self) -> Option<RangeBound> {
+        self.grabbed
+    }
+}
+
+/// A slider with two knobs for selecting a range (e.g. a min / max band).
+///
+/// The knobs can't cross each other: the lower knob stops at the upper one and vice versa.
+/// The knob that is closest to the pointer is grabbed on [Interaction::Click], and keeps
+/// tracking the pointer through [Interaction::Drag] until [Interaction::Release].
+///
+/// [Response::changed] is `true` if one of the bounds changed, and [RangeBound::changed]
+/// returns which ones ([Response::changed_part] is a bitmask of the changed bounds).
+///
+/// With focus navigation, both knobs are separately focusable (lower knob first).
+///
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// use kolibri_embedded_gui::widgets::slider::{RangeBound, RangeSlider, RangeSliderState};
+///
+/// // create the state outside of the drawing loop
+/// let mut state = RangeSliderState::new();
+/// let (mut min_temp, mut max_temp) = (19.0f32, 23.0f32);
+///
+/// loop {
+///     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+///     let response = ui.add(
+///         RangeSlider::new(&mut min_temp, &mut max_temp, 10.0..=30.0, &mut state)
+///             .step_size(0.5)
+///             .label("Comfort band"),
+///     );
+///     let (lower_changed, upper_changed) = RangeBound::changed(&response);
+///     if lower_changed { /* heating threshold changed */ }
+///     if upper_changed { /* cooling threshold changed */ }
+/// }
+/// ```
+pub struct RangeSlider<'a, T: SliderValue = i16> {
+    lower: &'a mut T,
+    upper: &'a mut T,
+    range: RangeInclusive<T>,
+    state: &'a mut RangeSliderState,
+    step_size: T,
+    label: Option<&'a str>,
+    width: u32,
+    orientation: Orientation,
+    smartstate: Container<'a, Smartstate>,
+    enabled: bool,
+}
+
+impl<'a, T: SliderValue> RangeSlider<'a, T> {
+    /// Creates a new range slider for the given bounds and range.
+    ///
+    /// # Arguments
+    ///
+    /// * `lower` - The lower bound of the selected range
+    /// * `upper` - The upper bound of the selected range
+    /// * `range` - An inclusive range specifying the minimum and maximum allowed values
+    /// * `state` - The state of the slider, which has to persist across frames
+    ///
+    /// Like the [Slider], the range slider is horizontal and 200 pixels wide by default.
+    pub fn new(
+        lower: &'a mut T,
+        upper: &'a mut T,
+        range: RangeInclusive<T>,
+        state: &'a mut RangeSliderState,
+    ) -> Self {
+        Self {
+            lower,
+            upper,
+            range,
+            state,
+            step_size: T::DEFAULT_STEP,
+            label: None,
+            width: 200,
+            orientation: Orientation::Horizontal,
+            smartstate: Container::empty(),
+            enabled: true,
+        }
+    }
+
+    /// Sets the label text for the slider, which is displayed below the track.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Adds a smartstate to the slider for incremental redrawing.
+    pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
+        self.smartstate.set(smartstate);
+        self
+    }
+
+    /// Enables or disables the range slider (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled range slider still shows its range, but is greyed out and can't be dragged or
+    /// adjusted.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets the width (the length of the track) of the slider in pixels.
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the orientation of the slider (horizontal by default).
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets the step size for value adjustments, see [Slider::step_size].
+    pub fn step_size(mut self, step_size: T) -> Self {
+        self.step_size = step_size;
+        self
+    }
 
-            if old_slider_knob_pos != slider_knob_pos {
-                ui.draw(&old_slider_knob.into_styled(old_slider_knob_style))
-                    .ok();
+    /// Sets the given bound to the value, without crossing the other bound.
+    fn set_bound(&mut self, bound: RangeBound, value: T) {
+        match bound {
+            RangeBound::Lower if value > *self.upper => *self.lower = *self.upper,
+            RangeBound::Lower => *self.lower = value,
+            RangeBound::Upper if value < *self.lower => *self.upper = *self.lower,
+            RangeBound::Upper => *self.upper = value,
+        }
+    }
+}
+
+impl<T: SliderValue> Widget for RangeSlider<'_, T> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |slider| slider.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl<T: SliderValue> RangeSlider<'_, T> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        // get size
+        let padding = ui.style().spacing.button_padding;
+
+        let mut size = slider_size(ui, self.orientation, self.width, 0);
+        let mut text = slider_label(self.label, ui.style(), &mut size);
+
+        // allocate space
+        let focus_lower = ui.register_focus(true);
+        let focus_upper = ui.register_focus(true);
+        let iresponse = ui.allocate_space(size)?;
+
+        let track = Track::new(self.orientation, &iresponse.area, self.width, padding, 0);
+        if let Some(text) = text.as_mut() {
+            place_label(text, &iresponse.area, padding);
+        }
+
+        // find user input
+        let (old_lower, old_upper) = (*self.lower, *self.upper);
+        let range = self.range.clone();
+        let position = |value: T| value.to_position(&range, track.knob_min, track.knob_max);
+
+        match iresponse.interaction {
+            Interaction::Click(point) | Interaction::Drag(point) => {
+                let t = track.position_of(point);
+                let grabbed = match self.state.grabbed {
+                    Some(grabbed) if matches!(iresponse.interaction, Interaction::Drag(_)) => {
+                        grabbed
+                    }
+                    // grab the closest knob, or the one in the direction of the pointer
+                    _ => {
+                        let (lower, upper) = (position(*self.lower), position(*self.upper));
+                        if (t - lower).abs() < (t - upper).abs() || (lower == upper && t < lower) {
+                            RangeBound::Lower
+                        } else {
+                            RangeBound::Upper
+                        }
+                    }
+                };
+                self.state.grabbed = Some(grabbed);
+
+                let value = T::from_position(
+                    t,
+                    &self.range,
+                    track.knob_min,
+                    track.knob_max,
+                    self.step_size,
+                );
+                self.set_bound(grabbed, value);
             }
-            ui.draw(&slider_line.into_styled(line_style)).ok();
-            ui.draw(&slider_knob.into_styled(slider_knob_style)).ok();
-            // ui.draw(&icon_img).ok();
-            if let Some(text) = text.as_mut() {
-                ui.draw(text).unwrap();
+            Interaction::Release(_) | Interaction::Hover(_) => self.state.grabbed = None,
+            _ => {}
+        }
+
+        // adjust with focus navigation (e.g. a rotary encoder)
+        for (bound, focus) in [
+            (RangeBound::Lower, focus_lower),
+            (RangeBound::Upper, focus_upper),
+        ] {
+            if focus.adjust != 0 {
+                let value = match bound {
+                    RangeBound::Lower => *self.lower,
+                    RangeBound::Upper => *self.upper,
+                };
+                let value = value.add_steps(focus.adjust as i32, self.step_size, &self.range);
+                self.set_bound(bound, value);
             }
+        }
+
+        let (lower_pos, upper_pos) = (position(*self.lower), position(*self.upper));
+        let (old_lower_pos, old_upper_pos) = (position(old_lower), position(old_upper));
+
+        // styles and smartstate
+        let style = ui.style();
+        let line_style = PrimitiveStyleBuilder::new()
+            .stroke_color(style.border_color)
+            .stroke_width(TRACK_THICKNESS)
+            .build();
+        let band_style = PrimitiveStyleBuilder::new()
+            .stroke_color(style.primary_color)
+            .stroke_width(TRACK_THICKNESS)
+            .build();
+        let clear_style = PrimitiveStyleBuilder::new()
+            .fill_color(style.background_color)
+            .build();
 
-            ui.finalize()?;
+        let down = matches!(
+            iresponse.interaction,
+            Interaction::Click(_) | Interaction::Drag(_)
+        );
+        let (lower_style, lower_val) = knob_style(
+            style,
+            iresponse.interaction,
+            focus_lower,
+            down && self.state.grabbed == Some(RangeBound::Lower),
+        );
+        let (upper_style, upper_val) = knob_style(
+            style,
+            iresponse.interaction,
+            focus_upper,
+            down && self.state.grabbed == Some(RangeBound::Upper),
+        );
+        let state_val = track.knob_state(lower_pos, lower_val)
+            | track.knob_state(upper_pos, upper_val) << KNOB_STATE_BITS;
+
+        if !self.smartstate.eq_inner(&Smartstate::state(state_val)) {
+            ui.paint(&iresponse.area, |ui| {
+                // clear the old knobs
+                for (old, new) in [(old_lower_pos, lower_pos), (old_upper_pos, upper_pos)] {
+                    if old != new {
+                        ui.draw(&track.knob(old, KNOB_DIAMETER + 4).into_styled(clear_style))
+                            .ok();
+                    }
+                }
+
+                ui.draw(&track.line().into_styled(line_style)).ok();
+                ui.draw(
+                    &Line::new(track.point(lower_pos), track.point(upper_pos))
+                        .into_styled(band_style),
+                )
+                .ok();
+                ui.draw(
+                    &track
+                        .knob(lower_pos, KNOB_DIAMETER)
+                        .into_styled(lower_style),
+                )
+                .ok();
+                ui.draw(
+                    &track
+                        .knob(upper_pos, KNOB_DIAMETER)
+                        .into_styled(upper_style),
+                )
+                .ok();
+                if let Some(text) = text.as_mut() {
+                    ui.draw(text)
+                        .map_err(|_| GuiError::DrawError(Some("Couldn't draw slider label")))?;
+                }
+                Ok(())
+            })?;
         }
 
         self.smartstate
             .modify(|s| *s = Smartstate::state(state_val));
 
-        Ok(Response::new(iresponse).set_changed(old_val != *self.value)) //.set_clicked(click).set_down(down))
+        let mut changed = 0;
+        if old_lower != *self.lower {
+            changed |= RangeBound::Lower.bit();
+        }
+        if old_upper != *self.upper {
+            changed |= RangeBound::Upper.bit();
+        }
+
+        let mut response = Response::new(iresponse).set_down(down);
+        if changed != 0 {
+            response = response.set_changed_part(changed);
+        }
+        Ok(response)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
 
     #[test]
     fn test_lerp_fixed_basic() {
@@ -496,4 +1304,98 @@ mod tests {
         let max_t = 32767;
         assert_eq!(lerp_fixed(start, end, t, min_t, max_t), 0);
     }
+
+    #[test]
+    fn test_slider_value_wide_range() {
+        let range = 0u32..=u32::MAX;
+        assert_eq!(u32::MAX.to_position(&range, 0, 200), 200);
+        assert_eq!(u32::from_position(100, &range, 0, 200, 1), u32::MAX / 2 + 1);
+        assert_eq!(u32::from_position(300, &range, 0, 200, 1), u32::MAX);
+
+        let range = i32::MIN..=i32::MAX;
+        assert_eq!(0i32.to_position(&range, 0, 200), 100);
+        assert_eq!(i32::MAX.add_steps(1, 10, &range), i32::MAX);
+    }
+
+    #[test]
+    fn test_slider_value_float() {
+        let range = 15.0f32..=30.0;
+        assert_eq!(22.5f32.to_position(&range, 0, 100), 50);
+        assert_eq!(f32::from_position(52, &range, 0, 100, 0.5), 23.0);
+        assert_eq!(30.0f32.add_steps(1, 0.5, &range), 30.0);
+        assert_eq!(15.0f32.add_steps(2, 0.0, &range), 15.3);
+    }
+
+    #[test]
+    fn test_range_slider_grab() {
+        let (mut lower, mut upper) = (10u8, 20u8);
+        let mut state = RangeSliderState::new();
+        let mut frame = |interaction: Interaction| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.interact(interaction);
+            let response =
+                ui.add(RangeSlider::new(&mut lower, &mut upper, 0..=30, &mut state).width(40));
+            (RangeBound::changed(&response), lower, upper)
+        };
+        // the track starts at x = 3 (border padding) + 10 (padding and knob radius), 1px per step
+        let at = |value: i32| Point::new(13 + value, 13);
+
+        // grabs the closest knob
+        assert_eq!(frame(Interaction::Click(at(12))), ((true, false), 12, 20));
+        // the grabbed knob stops at the other one
+        assert_eq!(frame(Interaction::Drag(at(25))), ((true, false), 20, 20));
+        assert_eq!(frame(Interaction::Drag(at(28))), ((false, false), 20, 20));
+        assert_eq!(
+            frame(Interaction::Release(at(28))),
+            ((false, false), 20, 20)
+        );
+
+        // with both knobs at the same position, the knob in the direction of the pointer is grabbed
+        assert_eq!(frame(Interaction::Click(at(25))), ((false, true), 20, 25));
+    }
+
+    #[test]
+    fn test_slider_smartstate_tracks_knob_position() {
+        let mut smartstate = Smartstate::empty();
+        let mut state_of = |mut value: u16| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.add(
+                Slider::new(&mut value, 0..=1000)
+                    .width(40)
+                    .smartstate(&mut smartstate),
+            );
+            smartstate.value()
+        };
+
+        // values on the same pixel don't require a redraw, values on different pixels always do
+        assert_eq!(state_of(500), state_of(501));
+        let states = [0, 100, 200, 300, 400, 500].map(&mut state_of);
+        for (i, state) in states.iter().enumerate() {
+            assert!(!states[i + 1..].contains(state));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_readout_wider_than_slider() {
+        use crate::testing::Harness;
+
+        let mut harness = Harness::new(Size::new(320, 240), medsize_rgb565_style());
+        for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+            let mut value = 0i16;
+            harness.frame(Interaction::None, |ui| {
+                let response = ui.add(
+                    Slider::new(&mut value, -10000..=10000)
+                        .width(10)
+                        .orientation(orientation)
+                        .readout(Readout::UnderKnob),
+                );
+                assert_eq!(response.error, None);
+            });
+        }
+    }
 }
@@ -0,0 +1,221 @@
+//! # Spinner Widget
+//!
+//! An indeterminate busy indicator, e.g. while homing an axis or waiting for a connection.
+//!
+//! See [Spinner] for more info.
+
+use crate::smartstate::{Container, Smartstate};
+use crate::{GuiResult, Response, Ui, Widget};
+use core::cmp::max;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, PrimitiveStyle};
+
+/// Directions of the spinner dots (clockwise, starting at the top), scaled by 1000.
+///
+/// `sin` / `cos` aren't available in `core`, so the directions are precomputed.
+const DOT_DIRECTIONS: [(i32, i32); 8] = [
+    (0, -1000),
+    (707, -707),
+    (1000, 0),
+    (707, 707),
+    (0, 1000),
+    (-707, 707),
+    (-1000, 0),
+    (-707, -707),
+];
+
+/// # Spinner Widget
+///
+/// An indeterminate busy indicator: a ring of dots, of which one (drawn in the
+/// [primary color](crate::style::Style::primary_color)) moves around the ring.
+/// The other dots are drawn in the [border color](crate::style::Style::border_color).
+///
+/// The spinner doesn't keep time by itself. Instead, it animates from a tick counter supplied by
+/// the caller: every tick advances the highlighted dot by one position. This makes the animation
+/// speed independent of the frame rate.
+///
+/// With a smartstate, only the two dots that changed are redrawn each tick.
+///
+/// # Example
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::smartstate::*;
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+/// # let mut smartstates = SmartstateProvider::<20>::new();
+/// # let millis = 1234u32;
+/// use kolibri_embedded_gui::widgets::Spinner;
+///
+/// // advance the spinner every 100ms
+/// ui.add(Spinner::new(millis / 100).smartstate(smartstates.nxt()));
+///
+/// // bigger spinner
+/// ui.add(Spinner::new(millis / 100).size(32));
+/// ```
+pub struct Spinner<'a> {
+    tick: u32,
+    size: Option<u32>,
+    smartstate: Container<'a, Smartstate>,
+}
+
+impl<'a> Spinner<'a> {
+    /// Creates a new spinner at the given tick.
+    pub fn new(tick: u32) -> Self {
+        Self {
+            tick,
+            size: None,
+            smartstate: Container::empty(),
+        }
+    }
+
+    /// Sets the diameter of the spinner in pixels.
+    ///
+    /// By default, the spinner is as big as the row height (but at least as big as the
+    /// [default widget height](crate::style::Style::default_widget_height)).
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Adds a smartstate to the spinner for incremental redrawing.
+    ///
+    /// With a smartstate, only the dots that changed are redrawn.
+    pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
+        self.smartstate.set(smartstate);
+        self
+    }
+}
+
+impl Widget for Spinner<'_> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        // get size
+        let size = self
+            .size
+            .unwrap_or_else(|| max(ui.style().default_widget_height, ui.get_row_height()));
+
+        // allocate space
+        let iresponse = ui.allocate_space(Size::new(size, size))?;
+
+        let dot_diameter = max(size / 5, 2);
+        let ring_radius = size.saturating_sub(dot_diameter) as i32 / 2;
+        let center = iresponse.area.center();
+        let dot = |index: usize| {
+            let (x, y) = DOT_DIRECTIONS[index];
+            Circle::with_center(
+                center + Point::new(x * ring_radius / 1000, y * ring_radius / 1000),
+                dot_diameter,
+            )
+        };
+
+        let style = ui.style();
+        let active_style = PrimitiveStyle::with_fill(style.primary_color);
+        let inactive_style = PrimitiveStyle::with_fill(style.border_color);
+
+        // smartstate
+        ui.force_redraw_if_cleared(self.smartstate.as_mut_slice());
+        let active = (self.tick % DOT_DIRECTIONS.len() as u32) as usize;
+        let prev = self
+            .smartstate
+            .clone_inner()
+            .and_then(|state| state.value());
+        self.smartstate
+            .modify(|st| *st = Smartstate::state(active as u32));
+
+        match prev {
+            Some(prev) if prev as usize == active => {}
+            Some(prev) if (prev as usize) < DOT_DIRECTIONS.len() => {
+                // only redraw the previously and newly active dots
+                for (index, style) in [(prev as usize, inactive_style), (active, active_style)] {
+                    let dot = dot(index);
+                    ui.paint(&dot.bounding_box(), |ui| {
+                        ui.draw(&dot.into_styled(style))?;
+                        Ok(())
+                    })?;
+                }
+            }
+            _ => {
+                ui.paint(&iresponse.area, |ui| {
+                    if !ui.cleared() {
+                        ui.clear_area(iresponse.area)?;
+                    }
+                    for index in 0..DOT_DIRECTIONS.len() {
+                        let style = if index == active {
+                            active_style
+                        } else {
+                            inactive_style
+                        };
+                        ui.draw(&dot(index).into_styled(style))?;
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(Response::new(iresponse))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+    use embedded_graphics::primitives::Rectangle;
+
+    #[test]
+    fn test_spinner_partial_redraw() {
+        let mut smartstate = Smartstate::empty();
+        let frame = |tick: u32, smartstate: &mut Smartstate| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.add(Spinner::new(tick).size(20).smartstate(smartstate));
+            display.affected_area()
+        };
+
+        // the spinner is 20px big (dots of 4px on a ring with a radius of 8px), at (3, 3)
+        let full = Rectangle::new(Point::new(3, 3), Size::new(20, 20));
+        assert_eq!(frame(0, &mut smartstate), full);
+        // only the top dot and the one right of it are redrawn
+        assert_eq!(
+            frame(1, &mut smartstate),
+            Rectangle::new(Point::new(11, 3), Size::new(9, 7))
+        );
+        assert_eq!(frame(1, &mut smartstate), Rectangle::zero());
+        // the tick wraps around to the top dot, in both directions
+        assert_eq!(
+            frame(8, &mut smartstate),
+            Rectangle::new(Point::new(11, 3), Size::new(9, 7))
+        );
+        assert_eq!(
+            frame(7, &mut smartstate),
+            Rectangle::new(Point::new(6, 3), Size::new(9, 7))
+        );
+
+        // a state that isn't a dot index (e.g. from another widget) redraws everything
+        smartstate.set_state(100);
+        assert_eq!(frame(7, &mut smartstate), full);
+    }
+
+    #[test]
+    fn test_spinner_smaller_than_dot() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        // the dots are bigger than the spinner itself, so the ring has no radius
+        let response = ui.add(Spinner::new(3).size(1));
+        assert_eq!(response.error(), None);
+        assert_eq!(response.internal.area.size, Size::new(1, 1));
+    }
+}
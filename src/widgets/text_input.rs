@@ -0,0 +1,729 @@
+//! # Text Input Widget
+//!
+//! A single-line text input that edits a [heapless::String].
+//!
+//! The text is displayed in a bordered box together with a cursor. Tapping the input moves the
+//! cursor, and dragging over the text selects it. Characters are inserted at the cursor using
+//! [TextCursor::insert] (e.g. by [draw_keyboard_with_cursor](crate::helpers::keyboard::draw_keyboard_with_cursor)),
+//! replacing the selection if there is one.
+//!
+//! See [TextInput] for more info.
+
+use crate::font::{Font, FontText};
+use crate::smartstate::{Container, Smartstate};
+use crate::{GuiError, GuiResult, Interaction, Response, Ui, Widget};
+use core::cmp::{max, min};
+use core::hash::BuildHasher;
+use core::ops::Range;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, PrimitiveStyleBuilder, Rectangle};
+use foldhash::fast::FixedState;
+use heapless::String;
+
+/// Cursor (and selection) of a [TextInput].
+///
+/// The cursor has to persist across frames, so create it outside of the drawing loop. It is
+/// shared between the [TextInput] that displays it and the input source (e.g. the on-screen
+/// keyboard), which edits the text at the cursor position.
+///
+/// All positions are byte indices into the edited string. If the string is changed by other means,
+/// the cursor is moved back into the string (and onto a char boundary) on the next use.
+///
+/// ## Blinking
+///
+/// The cursor is drawn solid by default. For a blinking cursor, call [TextCursor::toggle_visible]
+/// periodically (e.g. every 500ms).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextCursor {
+    position: usize,
+    anchor: Option<usize>,
+    visible: bool,
+}
+
+impl Default for TextCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextCursor {
+    /// Creates a new cursor at the start of the text.
+    pub const fn new() -> Self {
+        Self {
+            position: 0,
+            anchor: None,
+            visible: true,
+        }
+    }
+
+    /// Creates a new cursor at the end of the given text.
+    pub fn at_end(text: &str) -> Self {
+        Self {
+            position: text.len(),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the cursor position (as byte index into the text).
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the cursor to the given byte index, and removes the selection.
+    ///
+    /// The position is clamped to the text, and moved back onto a char boundary if necessary.
+    pub fn set_position(&mut self, text: &str, position: usize) {
+        self.position = floor_char_boundary(text, position);
+        self.anchor = None;
+    }
+
+    /// Returns the selected byte range of the text, if anything is selected.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        match self.anchor {
+            Some(anchor) if anchor != self.position => {
+                Some(min(anchor, self.position)..max(anchor, self.position))
+            }
+            _ => None,
+        }
+    }
+
+    /// Selects the whole text, placing the cursor at its end.
+    pub fn select_all(&mut self, text: &str) {
+        self.anchor = Some(0);
+        self.position = text.len();
+    }
+
+    /// Removes the selection, keeping the cursor where it is.
+    pub fn clear_selection(&mut self) {
+        self.anchor = None;
+    }
+
+    /// Moves the cursor one character to the left, and removes the selection.
+    pub fn move_left(&mut self, text: &str) {
+        self.clamp(text);
+        let position = text[..self.position]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i);
+        self.set_position(text, position);
+    }
+
+    /// Moves the cursor one character to the right, and removes the selection.
+    pub fn move_right(&mut self, text: &str) {
+        self.clamp(text);
+        let position = text[self.position..]
+            .chars()
+            .next()
+            .map_or(self.position, |c| self.position + c.len_utf8());
+        self.set_position(text, position);
+    }
+
+    /// Returns whether the cursor is currently drawn.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Shows or hides the cursor.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Toggles the cursor visibility. Call this periodically for a blinking cursor.
+    pub fn toggle_visible(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Inserts a character at the cursor (replacing the selection), and moves the cursor behind it.
+    ///
+    /// Returns `false` (without changing the text) if the text would exceed its capacity `N`.
+    pub fn insert<const N: usize>(&mut self, text: &mut String<N>, c: char) -> bool {
+        self.insert_str(text, c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Inserts a string at the cursor (replacing the selection), and moves the cursor behind it.
+    ///
+    /// Returns `false` (without changing the text) if the text would exceed its capacity `N`.
+    pub fn insert_str<const N: usize>(&mut self, text: &mut String<N>, s: &str) -> bool {
+        self.clamp(text);
+        let range = self.selection().unwrap_or(self.position..self.position);
+        let start = range.start;
+        if !replace_range(text, range, s) {
+            return false;
+        }
+        self.set_position(text, start + s.len());
+        true
+    }
+
+    /// Deletes the selection, or the character before the cursor (like backspace).
+    ///
+    /// Returns `false` if nothing was deleted.
+    pub fn delete_backward<const N: usize>(&mut self, text: &mut String<N>) -> bool {
+        self.clamp(text);
+        if self.selection().is_none() {
+            if self.position == 0 {
+                return false;
+            }
+            self.anchor = Some(self.position);
+            self.move_left_keep_anchor(text);
+        }
+        self.insert_str(text, "")
+    }
+
+    /// Deletes the selection, or the character behind the cursor (like delete).
+    ///
+    /// Returns `false` if nothing was deleted.
+    pub fn delete_forward<const N: usize>(&mut self, text: &mut String<N>) -> bool {
+        self.clamp(text);
+        if self.selection().is_none() {
+            if self.position == text.len() {
+                return false;
+            }
+            let anchor = self.position;
+            self.move_right(text);
+            self.anchor = Some(anchor);
+        }
+        self.insert_str(text, "")
+    }
+
+    /// Moves the cursor one character to the left without touching the anchor.
+    fn move_left_keep_anchor(&mut self, text: &str) {
+        let anchor = self.anchor;
+        self.move_left(text);
+        self.anchor = anchor;
+    }
+
+    /// Moves the cursor and the anchor back into the text.
+    fn clamp(&mut self, text: &str) {
+        self.position = floor_char_boundary(text, self.position);
+        self.anchor = self.anchor.map(|anchor| floor_char_boundary(text, anchor));
+    }
+}
+
+/// Returns the largest char boundary of `text` that is `<= index`.
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = min(index, text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Replaces the given byte range of the text. Returns `false` if the result doesn't fit.
+fn replace_range<const N: usize>(text: &mut String<N>, range: Range<usize>, with: &str) -> bool {
+    if text.len() - range.len() + with.len() > N {
+        return false;
+    }
+    let mut tail = String::<N>::new();
+    // can't fail: the tail is part of the text
+    tail.push_str(&text[range.end..]).ok();
+    text.truncate(range.start);
+    text.push_str(with).ok();
+    text.push_str(&tail).ok();
+    true
+}
+
+/// # Text Input Widget
+///
+/// A single-line text input that displays and edits a [heapless::String] in a bordered box.
+///
+/// The maximum length of the text is the capacity `N` of the string. The [TextCursor] holds the
+/// cursor position and selection across frames.
+///
+/// # Features
+/// - Tapping moves the cursor, dragging selects text
+/// - Insertion and deletion at the cursor (see [TextCursor]), e.g. from the on-screen keyboard
+/// - Solid or blinking cursor
+/// - Optional placeholder text that is shown while the text is empty
+/// - Password masking
+/// - Horizontal scrolling that keeps the cursor visible if the text is longer than the box
+/// - Optional smartstate support for incremental redrawing
+///
+/// # Example
+/// ```no_run
+/// # use embedded_graphics::pixelcolor::Rgb565;
+/// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+/// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+/// # use kolibri_embedded_gui::ui::Ui;
+/// # use embedded_graphics::prelude::*;
+/// # use kolibri_embedded_gui::smartstate::*;
+/// # use kolibri_embedded_gui::helpers::keyboard::{draw_keyboard_with_cursor, Layout};
+/// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+/// # let mut smartstates = SmartstateProvider::<64>::new();
+/// # let (mut shift, mut open) = (false, true);
+/// use kolibri_embedded_gui::widgets::{TextCursor, TextInput};
+/// use heapless::String;
+///
+/// // create the text and cursor outside of the drawing loop
+/// let mut password = String::<16>::new();
+/// let mut cursor = TextCursor::new();
+///
+/// loop {
+///     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+///     ui.add(
+///         TextInput::new(&mut password, &mut cursor)
+///             .placeholder("Password")
+///             .password(true)
+///             .smartstate(smartstates.nxt()),
+///     );
+///
+///     // the keyboard inserts characters at the cursor
+///     draw_keyboard_with_cursor(
+///         &mut ui,
+///         &Layout::qwerty(),
+///         Some(&mut smartstates),
+///         true,
+///         true,
+///         &mut shift,
+///         &mut open,
+///         &mut password,
+///         &mut cursor,
+///     );
+///     # smartstates.restart_counter();
+/// }
+/// ```
+pub struct TextInput<'a, const N: usize> {
+    text: &'a mut String<N>,
+    cursor: &'a mut TextCursor,
+    placeholder: Option<&'a str>,
+    password: bool,
+    width: Option<u32>,
+    smartstate: Container<'a, Smartstate>,
+    enabled: bool,
+}
+
+impl<'a, const N: usize> TextInput<'a, N> {
+    /// Creates a new text input for the given text and cursor.
+    pub fn new(text: &'a mut String<N>, cursor: &'a mut TextCursor) -> Self {
+        Self {
+            text,
+            cursor,
+            placeholder: None,
+            password: false,
+            width: None,
+            smartstate: Container::empty(),
+            enabled: true,
+        }
+    }
+
+    /// Sets a placeholder text that is shown while the text is empty.
+    pub fn placeholder(mut self, placeholder: &'a str) -> Self {
+        self.placeholder = Some(placeholder);
+        self
+    }
+
+    /// Masks the text (e.g. for passwords), showing a `*` for every character.
+    pub fn password(mut self, password: bool) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// Sets the width of the input (including border and padding).
+    ///
+    /// By default, the input is wide enough for `N` characters, but never wider than the
+    /// remaining space of the row.
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Adds a smartstate to the text input for incremental redrawing.
+    ///
+    /// The input is redrawn whenever the text, cursor or interaction state changes.
+    pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
+        self.smartstate.set(smartstate);
+        self
+    }
+
+    /// Enables or disables the text input (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled text input still shows its text, but is greyed out and can't be edited.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+impl<const N: usize> Widget for TextInput<'_, N> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |input| input.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl<const N: usize> TextInput<'_, N> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        // get size
+        let font = ui.style().default_font;
+        let padding = ui.style().spacing.button_padding;
+        let border = max(ui.style().border_width, 1);
+        let inset = Size::new(padding.width + border, padding.height + border);
+
+        let height = max(
+            font.line_height() + 2 * inset.height,
+            ui.style().default_widget_height,
+        );
+        let width = self.width.unwrap_or_else(|| {
+            // N chars, plus one for the cursor at the end
+            min(
+                (N as u32 + 1) * font.char_width('0') + 2 * inset.width,
+                ui.space_available().width,
+            )
+        });
+
+        // allocate space
+        let focus = ui.register_focus(false);
+        let iresponse = ui.allocate_space(Size::new(width, height))?;
+
+        let text_origin = iresponse.area.top_left + Point::new(inset.width as i32, 0);
+        let text_width = iresponse.area.size.width.saturating_sub(2 * inset.width);
+        let measure = Measure {
+            font,
+            masked: self.password,
+        };
+        self.cursor.clamp(self.text);
+
+        // check interaction (hit test with the text as it was displayed)
+        let first = measure.first_visible(self.text, self.cursor.position, text_width);
+        let hit = |point: Point| measure.hit(self.text, first, point.x - text_origin.x);
+
+        let mut click = focus.activated;
+        match iresponse.interaction {
+            Interaction::Click(point) => {
+                let position = hit(point);
+                self.cursor.position = position;
+                self.cursor.anchor = Some(position);
+            }
+            Interaction::Drag(point) => {
+                let position = hit(point);
+                self.cursor.anchor.get_or_insert(self.cursor.position);
+                self.cursor.position = position;
+            }
+            Interaction::Release(point) => {
+                let position = hit(point);
+                self.cursor.position = position;
+                if self.cursor.selection().is_none() {
+                    self.cursor.anchor = None;
+                }
+                click = true;
+            }
+            _ => {}
+        }
+        let down = matches!(
+            iresponse.interaction,
+            Interaction::Click(_) | Interaction::Drag(_)
+        );
+
+        // styles and smartstate
+        let style = ui.style();
+        let (rect_style, interact_val) = match iresponse.interaction {
+            Interaction::Click(_) | Interaction::Drag(_) => (
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(style.highlight_border_color)
+                    .stroke_width(max(style.highlight_border_width, 1))
+                    .fill_color(style.highlight_item_background_color)
+                    .build(),
+                2u8,
+            ),
+            Interaction::Hover(_) | Interaction::Release(_) => (
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(style.highlight_border_color)
+                    .stroke_width(max(style.highlight_border_width, 1))
+                    .fill_color(style.item_background_color)
+                    .build(),
+                1,
+            ),
+            _ if focus.focused => (
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(style.highlight_border_color)
+                    .stroke_width(max(style.highlight_border_width, 1))
+                    .fill_color(style.item_background_color)
+                    .build(),
+                1,
+            ),
+            _ => (
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(style.border_color)
+                    .stroke_width(border)
+                    .fill_color(style.item_background_color)
+                    .build(),
+                0,
+            ),
+        };
+
+        let hash = FixedState::default().hash_one((
+            self.text.as_str(),
+            *self.cursor,
+            interact_val,
+            self.password,
+            self.placeholder,
+        )) as u32;
+        let redraw = !self.smartstate.eq_option(&Some(Smartstate::state(hash)));
+        self.smartstate.modify(|st| *st = Smartstate::state(hash));
+
+        if redraw {
+            let style = *ui.style();
+            let background = rect_style.fill_color.unwrap_or(style.item_background_color);
+            let text_y = (iresponse.area.size.height - font.line_height()) as i32 / 2;
+
+            // the cursor may have moved, so scroll again
+            let first = measure.first_visible(self.text, self.cursor.position, text_width);
+            let end = first + measure.fit(&self.text[first..], text_width);
+            let x = |index: usize| {
+                text_origin + Point::new(measure.width(&self.text[first..index]) as i32, text_y)
+            };
+
+            ui.paint(&iresponse.area, |ui| {
+                ui.draw(&iresponse.area.into_styled(rect_style))
+                    .map_err(|_| GuiError::DrawError(Some("Couldn't draw TextInput")))?;
+
+                // selection
+                let selection = self
+                    .cursor
+                    .selection()
+                    .map(|selection| selection.start.max(first)..selection.end.min(end))
+                    .filter(|selection| !selection.is_empty());
+                if let Some(selection) = selection.clone() {
+                    let start = x(selection.start);
+                    let highlight = Rectangle::new(
+                        start,
+                        Size::new((x(selection.end).x - start.x) as u32, font.line_height()),
+                    );
+                    ui.draw(&highlight.into_styled(PrimitiveStyle::with_fill(style.primary_color)))
+                        .map_err(|_| GuiError::DrawError(Some("Couldn't draw TextInput")))?;
+                }
+
+                // text (or placeholder)
+                match self.placeholder {
+                    Some(placeholder) if self.text.is_empty() => {
+                        let shown = &placeholder[..font.fit(placeholder, text_width)];
+                        ui.draw(
+                            &FontText::new(shown, x(0), font, style.primary_color)
+                                .blend(background, style.text_blend),
+                        )
+                        .map_err(|_| GuiError::DrawError(Some("Couldn't draw text")))?;
+                    }
+                    _ => {
+                        let mut buf = [0; 4];
+                        for (index, c) in self.text[first..end].char_indices() {
+                            let index = first + index;
+                            let c = if self.password { '*' } else { c };
+                            let selected = selection
+                                .as_ref()
+                                .is_some_and(|selection| selection.contains(&index));
+                            ui.draw(
+                                &FontText::new(
+                                    c.encode_utf8(&mut buf),
+                                    x(index),
+                                    font,
+                                    style.text_color,
+                                )
+                                .blend(
+                                    if selected {
+                                        style.primary_color
+                                    } else {
+                                        background
+                                    },
+                                    style.text_blend,
+                                ),
+                            )
+                            .map_err(|_| GuiError::DrawError(Some("Couldn't draw text")))?;
+                        }
+                    }
+                }
+
+                // cursor
+                if self.cursor.visible {
+                    let cursor = Rectangle::new(
+                        x(self.cursor.position) - Point::new(1, 0),
+                        Size::new(1, font.line_height()),
+                    );
+                    ui.draw(&cursor.into_styled(PrimitiveStyle::with_fill(style.text_color)))
+                        .map_err(|_| GuiError::DrawError(Some("Couldn't draw cursor")))?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(Response::new(iresponse).set_clicked(click).set_down(down))
+    }
+}
+
+/// Measures the text of a [TextInput] as it is displayed (i.e. masked for passwords).
+struct Measure<'f> {
+    font: &'f dyn Font,
+    masked: bool,
+}
+
+impl Measure<'_> {
+    fn char_width(&self, c: char) -> u32 {
+        self.font.char_width(if self.masked { '*' } else { c })
+    }
+
+    /// Returns the width of the displayed text.
+    fn width(&self, text: &str) -> u32 {
+        text.chars().map(|c| self.char_width(c)).sum()
+    }
+
+    /// Returns the byte length of the longest prefix of `text` that fits into `width`.
+    fn fit(&self, text: &str, width: u32) -> usize {
+        if !self.masked {
+            return self.font.fit(text, width);
+        }
+        // all chars are drawn as '*'
+        let chars = width
+            .checked_div(self.font.char_width('*'))
+            .map_or(usize::MAX, |chars| chars as usize);
+        text.char_indices()
+            .nth(chars)
+            .map_or(text.len(), |(index, _)| index)
+    }
+
+    /// Returns the byte index of the first visible char, so that the cursor is always visible.
+    fn first_visible(&self, text: &str, cursor: usize, width: u32) -> usize {
+        let mut used = 0;
+        let mut first = cursor;
+        for (index, c) in text[..cursor].char_indices().rev() {
+            used += self.char_width(c);
+            if used >= width {
+                break;
+            }
+            first = index;
+        }
+        first
+    }
+
+    /// Returns the byte index of the char boundary closest to `x` (relative to the first
+    /// visible char).
+    fn hit(&self, text: &str, first: usize, x: i32) -> usize {
+        let mut used = 0;
+        for (index, c) in text[first..].char_indices() {
+            let width = self.char_width(c) as i32;
+            if x < used + width / 2 {
+                return first + index;
+            }
+            used += width;
+        }
+        text.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    fn frame(text: &mut String<8>, cursor: &mut TextCursor, interaction: Interaction) {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(interaction);
+        ui.add(TextInput::new(text, cursor).width(58));
+    }
+
+    #[test]
+    fn test_cursor_editing() {
+        let mut text = String::<8>::new();
+        let mut cursor = TextCursor::new();
+
+        assert!(cursor.insert_str(&mut text, "hllo"));
+        cursor.set_position(&text, 1);
+        assert!(cursor.insert(&mut text, 'e'));
+        assert_eq!(text.as_str(), "hello");
+        assert_eq!(cursor.position(), 2);
+
+        assert!(cursor.delete_backward(&mut text));
+        assert!(cursor.delete_forward(&mut text));
+        assert_eq!(text.as_str(), "hlo");
+
+        cursor.set_position(&text, 0);
+        assert!(!cursor.delete_backward(&mut text));
+
+        // multi-byte characters
+        cursor.move_right(&text);
+        assert!(cursor.insert(&mut text, 'ö'));
+        assert_eq!(text.as_str(), "hölo");
+        cursor.move_left(&text);
+        assert_eq!(cursor.position(), 1);
+        cursor.move_right(&text);
+        assert!(cursor.delete_backward(&mut text));
+        assert_eq!(text.as_str(), "hlo");
+
+        // capacity of the string
+        cursor.set_position(&text, 3);
+        assert!(cursor.insert_str(&mut text, "12345"));
+        assert!(!cursor.insert(&mut text, '6'));
+        assert_eq!(text.as_str(), "hlo12345");
+    }
+
+    #[test]
+    fn test_cursor_selection() {
+        let mut text = String::<8>::new();
+        text.push_str("hello").unwrap();
+        let mut cursor = TextCursor::at_end(&text);
+
+        cursor.select_all(&text);
+        assert_eq!(cursor.selection(), Some(0..5));
+        assert!(cursor.insert(&mut text, 'x'));
+        assert_eq!(text.as_str(), "x");
+        assert_eq!(cursor.selection(), None);
+
+        // text changed from outside
+        text.clear();
+        assert!(!cursor.delete_backward(&mut text));
+        assert!(cursor.insert(&mut text, 'y'));
+        assert_eq!(text.as_str(), "y");
+    }
+
+    #[test]
+    fn test_tap_and_drag() {
+        let mut text = String::<8>::new();
+        text.push_str("hello").unwrap();
+        let mut cursor = TextCursor::new();
+
+        // 9px wide characters, starting at x = 3 (border padding) + 6 (inset)
+        let x = |column: i32| 9 + column * 9;
+        frame(
+            &mut text,
+            &mut cursor,
+            Interaction::Release(Point::new(x(2) + 2, 10)),
+        );
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.selection(), None);
+
+        frame(
+            &mut text,
+            &mut cursor,
+            Interaction::Click(Point::new(x(0), 10)),
+        );
+        frame(
+            &mut text,
+            &mut cursor,
+            Interaction::Drag(Point::new(x(3) - 3, 10)),
+        );
+        frame(
+            &mut text,
+            &mut cursor,
+            Interaction::Release(Point::new(x(3), 10)),
+        );
+        assert_eq!(cursor.selection(), Some(0..3));
+
+        assert!(cursor.delete_backward(&mut text));
+        assert_eq!(text.as_str(), "lo");
+    }
+}
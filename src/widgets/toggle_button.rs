@@ -9,16 +9,15 @@
 //! This widget is part of the Kolibri embedded GUI framework's core widget set and integrates
 //! with the framework's [Smartstate] system for efficient rendering.
 //!
+use crate::font::FontText;
 use crate::smartstate::{Container, Smartstate};
 use crate::{GuiError, GuiResult, Interaction, Response, Ui, Widget};
 use core::cmp::max;
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::{Point, Size};
-use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::PixelColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::{PrimitiveStyleBuilder, Rectangle};
-use embedded_graphics::text::{Baseline, Text};
 
 /// A button widget that can be toggled on and off.
 ///
@@ -55,6 +54,7 @@ pub struct ToggleButton<'a> {
     label: &'a str,
     active: &'a mut bool,
     smartstate: Container<'a, Smartstate>,
+    enabled: bool,
 }
 
 impl<'a> ToggleButton<'a> {
@@ -101,6 +101,7 @@ impl<'a> ToggleButton<'a> {
             label,
             active,
             smartstate: Container::empty(),
+            enabled: true,
         }
     }
 
@@ -114,20 +115,40 @@ impl<'a> ToggleButton<'a> {
         self.smartstate.set(smartstate);
         self
     }
+
+    /// Enables or disables the toggle button (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled toggle button is greyed out (but still shows whether it is active), and can't be
+    /// toggled or focused.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
 }
 
 impl Widget for ToggleButton<'_> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
         ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |button| button.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl ToggleButton<'_> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response> {
         // Prepare text
         let font = ui.style().default_font;
-        let mut text = Text::new(
-            self.label,
-            Point::zero(),
-            MonoTextStyle::new(&font, ui.style().text_color),
-        );
+        let mut text = FontText::new(self.label, Point::zero(), font, ui.style().text_color);
 
         // Determine size
         let text_bounds = text.bounding_box();
@@ -144,7 +165,7 @@ impl Widget for ToggleButton<'_> {
         );
 
         // Allocate space
-        let iresponse = ui.allocate_space(size)?;
+        let iresponse = ui.allocate_focusable_space(size)?;
 
         // Position text
         text.translate_mut(
@@ -154,11 +175,10 @@ impl Widget for ToggleButton<'_> {
                     (padding.height + border) as i32,
                 ),
         );
-        text.text_style.baseline = Baseline::Top;
 
         // Handle interaction
         let mut changed = false;
-        if let Interaction::Release(_) = iresponse.interaction {
+        if iresponse.released() {
             *self.active = !*self.active;
             changed = true;
         }
@@ -221,18 +241,18 @@ impl Widget for ToggleButton<'_> {
         let redraw = !self.smartstate.eq_option(&prevstate) || changed;
 
         if redraw {
-            ui.start_drawing(&iresponse.area);
-
-            let rect = Rectangle::new(iresponse.area.top_left, iresponse.area.size);
-            ui.draw(&rect.into_styled(style))
-                .map_err(|_| GuiError::DrawError(Some("Couldn't draw ToggleButton")))?;
-            ui.draw(&text)
-                .map_err(|_| GuiError::DrawError(Some("Couldn't draw ToggleButton label")))?;
-
-            ui.finalize()?;
+            ui.paint(&iresponse.area, |ui| {
+                let rect = Rectangle::new(iresponse.area.top_left, iresponse.area.size);
+                ui.draw(&rect.into_styled(style))
+                    .map_err(|_| GuiError::DrawError(Some("Couldn't draw ToggleButton")))?;
+                let background = style.fill_color.unwrap_or(ui.style().item_background_color);
+                ui.draw(&text.blend(background, ui.style().text_blend))
+                    .map_err(|_| GuiError::DrawError(Some("Couldn't draw ToggleButton label")))?;
+                Ok(())
+            })?;
         }
 
-        let click = matches!(iresponse.interaction, Interaction::Release(_));
+        let click = iresponse.released();
         let down = matches!(
             iresponse.interaction,
             Interaction::Click(_) | Interaction::Drag(_)
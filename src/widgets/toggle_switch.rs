@@ -62,6 +62,7 @@ use embedded_graphics::primitives::{
 pub struct ToggleSwitch<'a> {
     active: &'a mut bool,
     smartstate: Container<'a, Smartstate>,
+    enabled: bool,
     width: u32,
     height: u32,
 }
@@ -74,6 +75,7 @@ impl<'a> ToggleSwitch<'a> {
         ToggleSwitch {
             active,
             smartstate: Container::empty(),
+            enabled: true,
             width: 50,
             height: 25,
         }
@@ -89,6 +91,15 @@ impl<'a> ToggleSwitch<'a> {
         self
     }
 
+    /// Enables or disables the switch (enabled by default, see [Ui::enabled]).
+    ///
+    /// A disabled switch is greyed out (but still shows whether it is on), and can't be toggled or
+    /// focused.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
     /// Sets the width of the toggle switch.
     ///
     /// The width determines the horizontal size of the switch's track. A minimum
@@ -159,6 +170,21 @@ impl Widget for ToggleSwitch<'_> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
         ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let enabled = self.enabled;
+        ui.draw_enabled(
+            self,
+            enabled,
+            |switch| switch.smartstate.as_mut_slice(),
+            Self::draw_widget,
+        )
+    }
+}
+
+impl ToggleSwitch<'_> {
+    fn draw_widget<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response> {
         // Calculate total size including padding
         let padding = ui.style().spacing.button_padding;
@@ -168,11 +194,11 @@ impl Widget for ToggleSwitch<'_> {
         );
 
         // Allocate space in the UI
-        let iresponse = ui.allocate_space(total_size)?;
+        let iresponse = ui.allocate_focusable_space(total_size)?;
 
         // Handle interaction
         let mut changed = false;
-        if matches!(iresponse.interaction, Interaction::Release(_)) {
+        if iresponse.released() {
             *self.active = !*self.active;
             changed = true;
         }
@@ -216,62 +242,63 @@ impl Widget for ToggleSwitch<'_> {
         let redraw = !self.smartstate.eq_option(&prevstate) || changed;
 
         if redraw {
-            ui.start_drawing(&iresponse.area);
-
-            // Define the switch background (rounded rectangle)
-            let switch_rect = RoundedRectangle::new(
-                Rectangle::new(
-                    iresponse.area.top_left
-                        + Point::new(padding.width as i32, padding.height as i32),
-                    Size::new(self.width, self.height),
-                ),
-                CornerRadii::new(Size::new(self.height / 2, self.height / 2)),
-            );
-
-            let switch_style = PrimitiveStyleBuilder::new()
-                .fill_color(switch_color)
-                .stroke_color(border_color)
-                .stroke_width(ui.style().border_width)
-                .build();
+            ui.paint(&iresponse.area, |ui| {
+                // Define the switch background (rounded rectangle)
+                let switch_rect = RoundedRectangle::new(
+                    Rectangle::new(
+                        iresponse.area.top_left
+                            + Point::new(padding.width as i32, padding.height as i32),
+                        Size::new(self.width, self.height),
+                    ),
+                    CornerRadii::new(Size::new(self.height / 2, self.height / 2)),
+                );
 
-            ui.draw(&switch_rect.into_styled(switch_style))
-                .map_err(|_| GuiError::DrawError(Some("Couldn't draw ToggleSwitch background")))?;
+                let switch_style = PrimitiveStyleBuilder::new()
+                    .fill_color(switch_color)
+                    .stroke_color(border_color)
+                    .stroke_width(ui.style().border_width)
+                    .build();
 
-            // Calculate knob position
-            let knob_radius = (self.height / 2) - ui.style().border_width;
-            let knob_x = if *self.active {
-                // Positioned on the right
-                iresponse.area.top_left.x + padding.width as i32 + self.width as i32
-                    - knob_radius as i32
-                    - ui.style().border_width as i32
-            } else {
-                // Positioned on the left
-                iresponse.area.top_left.x
-                    + padding.width as i32
-                    + knob_radius as i32
-                    + ui.style().border_width as i32
-            };
+                ui.draw(&switch_rect.into_styled(switch_style))
+                    .map_err(|_| {
+                        GuiError::DrawError(Some("Couldn't draw ToggleSwitch background"))
+                    })?;
 
-            let knob_center = Point::new(
-                knob_x,
-                iresponse.area.top_left.y + padding.height as i32 + (self.height / 2) as i32,
-            );
+                // Calculate knob position
+                let knob_radius = (self.height / 2) - ui.style().border_width;
+                let knob_x = if *self.active {
+                    // Positioned on the right
+                    iresponse.area.top_left.x + padding.width as i32 + self.width as i32
+                        - knob_radius as i32
+                        - ui.style().border_width as i32
+                } else {
+                    // Positioned on the left
+                    iresponse.area.top_left.x
+                        + padding.width as i32
+                        + knob_radius as i32
+                        + ui.style().border_width as i32
+                };
 
-            let knob = Circle::with_center(knob_center, knob_radius * 2 - 3);
+                let knob_center = Point::new(
+                    knob_x,
+                    iresponse.area.top_left.y + padding.height as i32 + (self.height / 2) as i32,
+                );
 
-            let knob_style = PrimitiveStyleBuilder::new()
-                .fill_color(knob_color)
-                .stroke_color(border_color)
-                .stroke_width(2)
-                .build();
+                let knob = Circle::with_center(knob_center, knob_radius * 2 - 3);
 
-            ui.draw(&knob.into_styled(knob_style))
-                .map_err(|_| GuiError::DrawError(Some("Couldn't draw ToggleSwitch knob")))?;
+                let knob_style = PrimitiveStyleBuilder::new()
+                    .fill_color(knob_color)
+                    .stroke_color(border_color)
+                    .stroke_width(2)
+                    .build();
 
-            ui.finalize()?;
+                ui.draw(&knob.into_styled(knob_style))
+                    .map_err(|_| GuiError::DrawError(Some("Couldn't draw ToggleSwitch knob")))?;
+                Ok(())
+            })?;
         }
 
-        let click = matches!(iresponse.interaction, Interaction::Release(_));
+        let click = iresponse.released();
         let down = matches!(
             iresponse.interaction,
             Interaction::Click(_) | Interaction::Drag(_)